@@ -16,6 +16,8 @@ pub enum Afi {
     Ip6 = 2,
     #[strum(serialize = "L2VPN")]
     L2vpn = 25,
+    #[strum(serialize = "Link-State")]
+    LinkState = 16388,
     #[strum(to_string = "Unknown({0})")]
     Unknown(u16),
 }
@@ -31,11 +33,17 @@ pub enum Safi {
     Encap = 7,
     #[strum(serialize = "EVPN")]
     Evpn = 70,
+    #[strum(serialize = "BGP-LS")]
+    LinkState = 71,
+    #[strum(serialize = "SR Policy")]
+    SrPolicy = 73,
     #[strum(serialize = "MPLS VPN")]
     MplsVpn = 128,
     #[strum(serialize = "RTC")]
     Rtc = 132,
     Flowspec = 133,
+    #[strum(serialize = "Flowspec VPN")]
+    FlowspecVpn = 134,
     #[strum(to_string = "Unknown({0})")]
     Unknown(u8),
 }
@@ -117,6 +125,7 @@ impl From<Afi> for u16 {
             Ip => 1,
             Ip6 => 2,
             L2vpn => 25,
+            LinkState => 16388,
             Unknown(v) => v,
         }
     }
@@ -129,6 +138,7 @@ impl From<u16> for Afi {
             1 => Ip,
             2 => Ip6,
             25 => L2vpn,
+            16388 => LinkState,
             v => Unknown(v),
         }
     }
@@ -143,9 +153,12 @@ impl From<Safi> for u8 {
             MplsLabel => 4,
             Encap => 7,
             Evpn => 70,
+            LinkState => 71,
+            SrPolicy => 73,
             MplsVpn => 128,
             Rtc => 132,
             Flowspec => 133,
+            FlowspecVpn => 134,
             Unknown(v) => v,
         }
     }
@@ -160,9 +173,12 @@ impl From<u8> for Safi {
             4 => MplsLabel,
             7 => Encap,
             70 => Evpn,
+            71 => LinkState,
+            73 => SrPolicy,
             128 => MplsVpn,
             132 => Rtc,
             133 => Flowspec,
+            134 => FlowspecVpn,
             v => Unknown(v),
         }
     }
@@ -74,7 +74,7 @@ impl Aggregator2 {
 
 impl AttrEmitter for Aggregator2 {
     fn attr_flags(&self) -> AttrFlags {
-        AttrFlags::new().with_transitive(true)
+        AttrFlags::new().with_transitive(true).with_optional(true)
     }
 
     fn attr_type(&self) -> AttrType {
@@ -116,11 +116,72 @@ impl From<Aggregator2> for Aggregator {
 // Aggregator to Aggregator2.
 impl From<Aggregator> for Aggregator2 {
     fn from(value: Aggregator) -> Self {
-        let asn: u16 = if value.asn <= 65536 {
-            value.asn.try_into().unwrap()
-        } else {
-            AS_TRANS
-        };
+        let asn: u16 = u16::try_from(value.asn).unwrap_or(AS_TRANS);
         Self { asn, ip: value.ip }
     }
 }
+
+// AS4_AGGREGATOR (RFC 6793). Carries 4 octet aggregator AS across OLD BGP
+// speakers.
+#[derive(Clone, NomBE)]
+pub struct As4Aggregator {
+    pub asn: u32,
+    pub ip: Ipv4Addr,
+}
+
+impl As4Aggregator {
+    pub fn new(asn: u32, ip: Ipv4Addr) -> Self {
+        Self { asn, ip }
+    }
+}
+
+impl AttrEmitter for As4Aggregator {
+    fn attr_flags(&self) -> AttrFlags {
+        AttrFlags::new().with_transitive(true).with_optional(true)
+    }
+
+    fn attr_type(&self) -> AttrType {
+        AttrType::As4Aggregator
+    }
+
+    fn len(&self) -> Option<usize> {
+        Some(8)
+    }
+
+    fn emit(&self, buf: &mut BytesMut) {
+        buf.put_u32(self.asn);
+        buf.put(&self.ip.octets()[..]);
+    }
+}
+
+impl fmt::Display for As4Aggregator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.asn)
+    }
+}
+
+impl fmt::Debug for As4Aggregator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, " AS4 Aggregator: {}", self)
+    }
+}
+
+// As4Aggregator to Aggregator.
+impl From<As4Aggregator> for Aggregator {
+    fn from(value: As4Aggregator) -> Self {
+        Self {
+            asn: value.asn,
+            ip: value.ip,
+        }
+    }
+}
+
+// Aggregator to As4Aggregator.
+impl From<&Aggregator> for As4Aggregator {
+    fn from(value: &Aggregator) -> Self {
+        Self {
+            asn: value.asn,
+            ip: value.ip,
+        }
+    }
+}
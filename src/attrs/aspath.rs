@@ -17,7 +17,6 @@ pub const AS_SEQ: u8 = 2;
 pub const AS_CONFED_SEQ: u8 = 3;
 pub const AS_CONFED_SET: u8 = 4;
 
-#[allow(dead_code)]
 pub const AS_TRANS: u16 = 23456;
 
 /// Calculate AS Path segment length according to RFC 4271 and RFC 5065.
@@ -71,6 +70,128 @@ impl ParseBe<As2Path> for As2Path {
     }
 }
 
+impl AttrEmitter for As2Path {
+    fn attr_flags(&self) -> AttrFlags {
+        AttrFlags::new().with_transitive(true)
+    }
+
+    fn attr_type(&self) -> AttrType {
+        AttrType::AsPath
+    }
+
+    fn len(&self) -> Option<usize> {
+        None
+    }
+
+    fn emit(&self, buf: &mut BytesMut) {
+        for seg in self.segs.iter() {
+            buf.put_u8(seg.typ);
+            buf.put_u8(seg.asn.len() as u8);
+            seg.asn.iter().for_each(|x| buf.put_u16(*x));
+        }
+    }
+}
+
+// As2Path to As4Path.
+impl From<As2Path> for As4Path {
+    fn from(value: As2Path) -> Self {
+        let segs = value
+            .segs
+            .into_iter()
+            .map(|seg| As4Segment {
+                typ: seg.typ,
+                asn: seg.asn.into_iter().map(u32::from).collect(),
+            })
+            .collect();
+        As4Path {
+            segs,
+            length: value.length,
+        }
+    }
+}
+
+// As4Path to As2Path. 4 octet AS number is replaced with AS_TRANS.
+impl From<&As4Path> for As2Path {
+    fn from(value: &As4Path) -> Self {
+        let segs = value
+            .segs
+            .iter()
+            .map(|seg| As2Segment {
+                typ: seg.typ,
+                asn: seg
+                    .asn
+                    .iter()
+                    .map(|asn| u16::try_from(*asn).unwrap_or(AS_TRANS))
+                    .collect(),
+            })
+            .collect();
+        As2Path {
+            segs,
+            length: value.length,
+        }
+    }
+}
+
+impl As2Path {
+    /// Reconstruct AS path from AS_PATH and AS4_PATH (RFC 6793 4.2.3).
+    pub fn merge_as4(self, as4path: &As4Path) -> As4Path {
+        let aspath: As4Path = self.into();
+
+        // Confederation segments in AS4_PATH are discarded (RFC 6793 6).
+        let mut as4path = As4Path {
+            segs: as4path
+                .segs
+                .iter()
+                .filter(|seg| matches!(seg.typ, AS_SEQ | AS_SET))
+                .cloned()
+                .collect(),
+            length: 0,
+        };
+        as4path.update_length();
+
+        // AS4_PATH is ignored when it is longer than AS_PATH.
+        if aspath.length < as4path.length {
+            return aspath;
+        }
+
+        // Take leading ASes from AS_PATH which are not in AS4_PATH.
+        let mut count = aspath.length - as4path.length;
+        let mut merged = As4Path::new();
+        for mut seg in aspath.segs.into_iter() {
+            match seg.typ {
+                AS_SEQ => {
+                    if count == 0 {
+                        break;
+                    }
+                    let len = seg.asn.len().min(count as usize);
+                    seg.asn.truncate(len);
+                    count -= len as u32;
+                }
+                AS_SET => {
+                    if count == 0 {
+                        break;
+                    }
+                    count -= 1;
+                }
+                _ => {}
+            }
+            merged.segs.push_back(seg);
+        }
+
+        // Join AS_SEQ at the boundary.
+        for seg in as4path.segs.into_iter() {
+            match merged.segs.back_mut() {
+                Some(last) if last.typ == AS_SEQ && seg.typ == AS_SEQ => {
+                    last.asn.extend(seg.asn);
+                }
+                _ => merged.segs.push_back(seg),
+            }
+        }
+        merged.update_length();
+        merged
+    }
+}
+
 fn parse_bgp_attr_as2_segment(input: &[u8]) -> IResult<&[u8], As2Segment> {
     let (input, header) = AsSegmentHeader::parse_be(input)?;
     let (input, asns) = count(be_u16, header.length as usize).parse(input)?;
@@ -281,6 +402,29 @@ impl As4Path {
             .sum()
     }
 
+    /// Returns true when the path has AS number which does not fit in 2 octet.
+    pub fn has_as4(&self) -> bool {
+        self.segs
+            .iter()
+            .any(|seg| seg.asn.iter().any(|asn| *asn > u16::MAX as u32))
+    }
+
+    /// AS4_PATH to be sent with AS_PATH. Confederation segments are not
+    /// included (RFC 6793 4.2.2).
+    pub fn as4_path(&self) -> As4PathAttr {
+        let mut path = As4Path {
+            segs: self
+                .segs
+                .iter()
+                .filter(|seg| matches!(seg.typ, AS_SEQ | AS_SET))
+                .cloned()
+                .collect(),
+            length: 0,
+        };
+        path.update_length();
+        As4PathAttr(path)
+    }
+
     pub fn update_length(&mut self) {
         self.length = self
             .segs
@@ -384,6 +528,42 @@ impl As4Path {
     }
 }
 
+// AS4_PATH attribute (RFC 6793). Carries 4 octet AS path across OLD BGP
+// speakers.
+#[derive(Clone, Debug)]
+pub struct As4PathAttr(pub As4Path);
+
+impl AttrEmitter for As4PathAttr {
+    fn attr_flags(&self) -> AttrFlags {
+        AttrFlags::new().with_transitive(true).with_optional(true)
+    }
+
+    fn attr_type(&self) -> AttrType {
+        AttrType::As4Path
+    }
+
+    fn len(&self) -> Option<usize> {
+        None
+    }
+
+    fn emit(&self, buf: &mut BytesMut) {
+        self.0.emit(buf);
+    }
+}
+
+impl ParseBe<As4PathAttr> for As4PathAttr {
+    fn parse_be(input: &[u8]) -> IResult<&[u8], As4PathAttr> {
+        let (input, path) = As4Path::parse_be(input)?;
+        Ok((input, As4PathAttr(path)))
+    }
+}
+
+impl fmt::Display for As4PathAttr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
 impl Default for As4Path {
     fn default() -> Self {
         Self::new()
@@ -589,4 +769,24 @@ mod tests {
         assert_eq!(aspath.to_string(), "2 {3} 4 5 1 {2}");
         assert_eq!(aspath.length(), 6);
     }
+
+    #[test]
+    fn merge_as4() {
+        let as4path = As4Path::from_str("70000 3").unwrap();
+        let aspath = As2Path::from(&As4Path::from_str("1 2 70000 3").unwrap());
+        assert_eq!(aspath.segs[0].asn, vec![1, 2, AS_TRANS, 3]);
+        let merged = aspath.merge_as4(&as4path);
+        assert_eq!(merged.to_string(), "1 2 1.4464 3");
+        assert_eq!(merged.length(), 4);
+
+        // AS4_PATH longer than AS_PATH is ignored.
+        let aspath = As2Path::from(&As4Path::from_str("1 2").unwrap());
+        let merged = aspath.merge_as4(&As4Path::from_str("70000 3 4").unwrap());
+        assert_eq!(merged.to_string(), "1 2");
+
+        // Confederation segments are kept from AS_PATH.
+        let aspath = As2Path::from(&As4Path::from_str("(10) 1 {2 3} 23456").unwrap());
+        let merged = aspath.merge_as4(&As4Path::from_str("70000").unwrap());
+        assert_eq!(merged.to_string(), "(10) 1 {2 3} 1.4464");
+    }
 }
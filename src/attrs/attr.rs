@@ -1,11 +1,15 @@
+use std::collections::BTreeSet;
 use std::fmt;
+use std::net::IpAddr;
 
 use bytes::BytesMut;
 use nom::bytes::complete::take;
 use nom::number::complete::be_u8;
 use nom_derive::*;
 
-use crate::{BgpAttr, BgpNexthop, BgpParseError, ParseBe, ParseOption};
+use crate::{
+    BgpAttr, BgpNexthop, BgpParseError, ParseBe, ParseOption, UpdateError, UpdateErrorAction,
+};
 
 use super::*;
 
@@ -25,10 +29,16 @@ pub enum AttrType {
     MpReachNlri = 14,
     MpUnreachNlri = 15,
     ExtendedCom = 16,
+    As4Path = 17,
+    As4Aggregator = 18,
     PmsiTunnel = 22,
+    TunnelEncap = 23,
     ExtendedIpv6Com = 25,
     Aigp = 26,
+    LinkState = 29,
     LargeCom = 32,
+    Otc = 35,
+    PrefixSid = 40,
     Unknown(u8),
 }
 
@@ -49,10 +59,16 @@ impl From<u8> for AttrType {
             14 => MpReachNlri,
             15 => MpUnreachNlri,
             16 => ExtendedCom,
+            17 => As4Path,
+            18 => As4Aggregator,
             22 => PmsiTunnel,
+            23 => TunnelEncap,
             25 => ExtendedIpv6Com,
             26 => Aigp,
+            29 => LinkState,
             32 => LargeCom,
+            35 => Otc,
+            40 => PrefixSid,
             v => Unknown(v),
         }
     }
@@ -75,10 +91,16 @@ impl From<AttrType> for u8 {
             MpReachNlri => 14,
             MpUnreachNlri => 15,
             ExtendedCom => 16,
+            As4Path => 17,
+            As4Aggregator => 18,
             PmsiTunnel => 22,
+            TunnelEncap => 23,
             ExtendedIpv6Com => 25,
             Aigp => 26,
+            LinkState => 29,
             LargeCom => 32,
+            Otc => 35,
+            PrefixSid => 40,
             Unknown(v) => v,
         }
     }
@@ -119,18 +141,42 @@ pub enum Attr {
     MpUnreachNlri(MpNlriUnreachAttr),
     #[nom(Selector = "AttrSelector(AttrType::ExtendedCom, None)")]
     ExtendedCom(ExtCommunity),
+    #[nom(Selector = "AttrSelector(AttrType::ExtendedIpv6Com, None)")]
+    ExtendedIpv6Com(ExtIpv6Community),
+    #[nom(Selector = "AttrSelector(AttrType::As4Path, None)")]
+    As4PathAttr(As4PathAttr),
+    #[nom(Selector = "AttrSelector(AttrType::As4Aggregator, None)")]
+    As4Aggregator(As4Aggregator),
     #[nom(Selector = "AttrSelector(AttrType::PmsiTunnel, None)")]
     PmsiTunnel(PmsiTunnel),
+    #[nom(Selector = "AttrSelector(AttrType::TunnelEncap, None)")]
+    TunnelEncap(TunnelEncap),
     #[nom(Selector = "AttrSelector(AttrType::Aigp, None)")]
     Aigp(Aigp),
+    #[nom(Selector = "AttrSelector(AttrType::LinkState, None)")]
+    LinkState(LinkStateAttr),
     #[nom(Selector = "AttrSelector(AttrType::LargeCom, None)")]
     LargeCom(LargeCommunity),
+    #[nom(Selector = "AttrSelector(AttrType::Otc, None)")]
+    Otc(OnlyToCustomer),
+    #[nom(Selector = "AttrSelector(AttrType::PrefixSid, None)")]
+    PrefixSid(PrefixSid),
+    #[nom(Selector = "AttrSelector(AttrType::Unknown(_), None)")]
+    Unknown {
+        #[nom(Ignore)]
+        flags: AttrFlags,
+        #[nom(Ignore)]
+        type_code: u8,
+        #[nom(Ignore)]
+        data: Vec<u8>,
+    },
 }
 
 impl Attr {
     pub fn emit(&self, buf: &mut BytesMut) {
         match self {
             Attr::Origin(v) => v.attr_emit(buf),
+            Attr::As2Path(v) => v.attr_emit(buf),
             Attr::As4Path(v) => v.attr_emit(buf),
             Attr::NextHop(v) => v.attr_emit(buf),
             Attr::Med(v) => v.attr_emit(buf),
@@ -140,15 +186,25 @@ impl Attr {
             Attr::Aggregator2(v) => v.attr_emit(buf),
             Attr::OriginatorId(v) => v.attr_emit(buf),
             Attr::ClusterList(v) => v.attr_emit(buf),
-            // Attr::MpReachNlri(v) => v.attr_emit(buf),
+            Attr::MpReachNlri(v) => v.attr_emit(buf),
+            Attr::MpUnreachNlri(v) => v.attr_emit(buf),
             Attr::Community(v) => v.attr_emit(buf),
             Attr::ExtendedCom(v) => v.attr_emit(buf),
+            Attr::ExtendedIpv6Com(v) => v.attr_emit(buf),
+            Attr::As4PathAttr(v) => v.attr_emit(buf),
+            Attr::As4Aggregator(v) => v.attr_emit(buf),
             Attr::PmsiTunnel(v) => v.attr_emit(buf),
+            Attr::TunnelEncap(v) => v.attr_emit(buf),
             Attr::LargeCom(v) => v.attr_emit(buf),
             Attr::Aigp(v) => v.attr_emit(buf),
-            _ => {
-                //
-            }
+            Attr::LinkState(v) => v.attr_emit(buf),
+            Attr::Otc(v) => v.attr_emit(buf),
+            Attr::PrefixSid(v) => v.attr_emit(buf),
+            Attr::Unknown {
+                flags,
+                type_code,
+                data,
+            } => UnknownAttr::new(*flags, *type_code, data.clone()).attr_emit(buf),
         }
     }
 }
@@ -170,9 +226,21 @@ impl fmt::Display for Attr {
             Attr::MpUnreachNlri(v) => write!(f, "{}", v),
             Attr::Community(v) => write!(f, "{}", v),
             Attr::ExtendedCom(v) => write!(f, "{}", v),
+            Attr::ExtendedIpv6Com(v) => write!(f, "{}", v),
+            Attr::As4PathAttr(v) => write!(f, "{}", v),
+            Attr::As4Aggregator(v) => write!(f, "{}", v),
             Attr::PmsiTunnel(v) => write!(f, "{}", v),
+            Attr::TunnelEncap(v) => write!(f, "{}", v),
             Attr::LargeCom(v) => write!(f, "{}", v),
             Attr::Aigp(v) => write!(f, "{}", v),
+            Attr::LinkState(v) => write!(f, "{}", v),
+            Attr::Otc(v) => write!(f, "{}", v),
+            Attr::PrefixSid(v) => write!(f, "{}", v),
+            Attr::Unknown {
+                flags: _,
+                type_code,
+                data,
+            } => write!(f, "Unknown Type: {} Length: {}", type_code, data.len()),
             _ => write!(f, "Unknown"),
         }
     }
@@ -195,25 +263,52 @@ impl fmt::Debug for Attr {
             Attr::MpUnreachNlri(v) => write!(f, "{:?}", v),
             Attr::Community(v) => write!(f, "{:?}", v),
             Attr::ExtendedCom(v) => write!(f, "{:?}", v),
+            Attr::ExtendedIpv6Com(v) => write!(f, "{:?}", v),
+            Attr::As4PathAttr(v) => write!(f, "{:?}", v),
+            Attr::As4Aggregator(v) => write!(f, "{:?}", v),
             Attr::PmsiTunnel(v) => write!(f, "{:?}", v),
+            Attr::TunnelEncap(v) => write!(f, "{:?}", v),
             Attr::LargeCom(v) => write!(f, "{:?}", v),
             Attr::Aigp(v) => write!(f, "{:?}", v),
+            Attr::LinkState(v) => write!(f, "{:?}", v),
+            Attr::Otc(v) => write!(f, "{:?}", v),
+            Attr::PrefixSid(v) => write!(f, "{:?}", v),
+            Attr::Unknown {
+                flags,
+                type_code,
+                data,
+            } => write!(
+                f,
+                "Unknown Type: {} Flags: 0x{:02x} Data: {:?}",
+                type_code,
+                u8::from(*flags),
+                data
+            ),
             _ => write!(f, "Unknown"),
         }
     }
 }
 
-impl Attr {
-    pub fn parse_attr<'a>(
-        input: &'a [u8],
-        as4: bool,
-        opt: &'a Option<ParseOption>,
-    ) -> Result<(&'a [u8], Attr), BgpParseError> {
+// Attribute header and the raw attribute bytes for NOTIFICATION data.
+struct AttrHeader<'a> {
+    flags: AttributeFlags,
+    flags_byte: u8,
+    type_code: u8,
+    attr_type: AttrType,
+    payload: &'a [u8],
+    raw: &'a [u8],
+}
+
+impl<'a> AttrHeader<'a> {
+    fn parse(input: &'a [u8]) -> Result<(&'a [u8], Self), BgpParseError> {
+        let start = input;
+
         // Parse the attribute flags and type code
         let (input, flags_byte) = be_u8(input)?;
-        let flags = AttributeFlags::from_bits(flags_byte).unwrap();
-        let (input, attr_type_byte) = be_u8(input)?;
-        let attr_type: AttrType = attr_type_byte.into();
+        // Lower 4 bits are unused and ignored.
+        let flags = AttributeFlags::from_bits_truncate(flags_byte);
+        let (input, type_code) = be_u8(input)?;
+        let attr_type: AttrType = type_code.into();
 
         // Decide extended length presence and parse length
         let (input, length_bytes) = if flags.is_extended() {
@@ -227,36 +322,87 @@ impl Attr {
             [0, length_bytes[0]]
         });
 
-        // Only AS_PATH or AGGREGATOR care about as4 extension
-        let as4_opt = matches!(attr_type, AttrType::AsPath | AttrType::Aggregator).then_some(as4);
-
         // Split out the payload for this attribute
         if input.len() < attr_len as usize {
             return Err(BgpParseError::IncompleteData {
                 needed: attr_len as usize - input.len(),
             });
         }
-        let (attr_payload, input) = input.split_at(attr_len as usize);
+        let (payload, input) = input.split_at(attr_len as usize);
+        let raw = &start[..start.len() - input.len()];
+
+        let header = Self {
+            flags,
+            flags_byte,
+            type_code,
+            attr_type,
+            payload,
+            raw,
+        };
+        Ok((input, header))
+    }
+}
+
+impl Attr {
+    pub fn parse_attr<'a>(
+        input: &'a [u8],
+        opt: &Option<ParseOption>,
+    ) -> Result<(&'a [u8], Attr), BgpParseError> {
+        let as4 = opt.as_ref().is_some_and(ParseOption::is_as4);
+        let (input, header) = AttrHeader::parse(input)?;
+        let attr = Attr::parse_attr_value(&header, as4, opt)?;
+        Ok((input, attr))
+    }
+
+    fn parse_attr_value(
+        header: &AttrHeader,
+        as4: bool,
+        opt: &Option<ParseOption>,
+    ) -> Result<Attr, BgpParseError> {
+        let attr_type = header.attr_type;
+        let attr_payload = header.payload;
+
+        // Check flags and length against the attribute catalogue.
+        if let Some(spec) = attr_type.spec(as4) {
+            spec.validate(attr_type, &header.flags, attr_payload.len(), header.raw)?;
+        }
+
+        // Only AS_PATH or AGGREGATOR care about as4 extension
+        let as4_opt = matches!(attr_type, AttrType::AsPath | AttrType::Aggregator).then_some(as4);
 
         // Parse the attribute using the appropriate selector with error context
         let (_, attr) = match attr_type {
+            AttrType::Unknown(_) => {
+                // RFC4271 6.3: Unrecognized well-known attribute is an error.
+                if !header.flags.contains(AttributeFlags::OPTIONAL) {
+                    return Err(BgpParseError::MalformedAttribute {
+                        attr_type,
+                        sub_code: UpdateError::UnrecognizedWellknownAttribute,
+                        data: header.raw.to_vec(),
+                    });
+                }
+                let attr = Attr::Unknown {
+                    flags: AttrFlags::from(header.flags_byte),
+                    type_code: header.type_code,
+                    data: attr_payload.to_vec(),
+                };
+                (attr_payload, attr)
+            }
             AttrType::MpReachNlri => {
-                let (remaining, mp_reach) =
-                    MpNlriReachAttr::parse_nlri_opt(attr_payload, opt.clone()).map_err(|e| {
-                        BgpParseError::AttributeParseError {
-                            attr_type,
-                            source: Box::new(BgpParseError::from(e)),
-                        }
+                let (remaining, mp_reach) = MpNlriReachAttr::parse_nlri_opt(attr_payload, opt)
+                    .map_err(|e| BgpParseError::AttributeParseError {
+                        attr_type,
+                        source: Box::new(BgpParseError::from(e)),
+                        data: header.raw.to_vec(),
                     })?;
                 (remaining, Attr::MpReachNlri(mp_reach))
             }
             AttrType::MpUnreachNlri => {
-                let (remaining, mp_unreach) =
-                    MpNlriUnreachAttr::parse_nlri_opt(attr_payload, opt.clone()).map_err(|e| {
-                        BgpParseError::AttributeParseError {
-                            attr_type,
-                            source: Box::new(BgpParseError::from(e)),
-                        }
+                let (remaining, mp_unreach) = MpNlriUnreachAttr::parse_nlri_opt(attr_payload, opt)
+                    .map_err(|e| BgpParseError::AttributeParseError {
+                        attr_type,
+                        source: Box::new(BgpParseError::from(e)),
+                        data: header.raw.to_vec(),
                     })?;
                 (remaining, Attr::MpUnreachNlri(mp_unreach))
             }
@@ -264,11 +410,37 @@ impl Attr {
                 BgpParseError::AttributeParseError {
                     attr_type,
                     source: Box::new(BgpParseError::from(e)),
+                    data: header.raw.to_vec(),
                 }
             })?,
         };
 
-        Ok((input, attr))
+        Ok(attr)
+    }
+}
+
+// RFC7606 7: Error handling approach for each malformed attribute. The
+// attributes which does not affect route selection are discarded. Errors in
+// MP_REACH_NLRI and MP_UNREACH_NLRI make NLRI unreliable so the session is
+// reset. Everything else including unknown optional attribute is handled as
+// treat-as-withdraw. RFC9552 8.2.2: Malformed BGP-LS Attribute is discarded.
+// RFC8669 6: So is malformed BGP Prefix-SID attribute.
+fn malformed_attr_action(
+    attr_type: AttrType,
+    err: &BgpParseError,
+    raw: &[u8],
+) -> UpdateErrorAction {
+    use AttrType::*;
+    let sub_code = match err {
+        BgpParseError::MalformedAttribute { sub_code, .. } => *sub_code,
+        _ => UpdateError::OptionalAttributeError,
+    };
+    match attr_type {
+        AtomicAggregate | Aggregator | As4Path | As4Aggregator | Aigp | LinkState | PrefixSid => {
+            UpdateErrorAction::AttributeDiscard
+        }
+        MpReachNlri | MpUnreachNlri => UpdateErrorAction::session_reset(sub_code, raw),
+        _ => UpdateErrorAction::TreatAsWithdraw,
     }
 }
 
@@ -278,30 +450,97 @@ type ParsedAttributes<'a> = Result<
         Option<BgpAttr>,
         Option<MpNlriReachAttr>,
         Option<MpNlriUnreachAttr>,
+        Option<UpdateErrorAction>,
     ),
     BgpParseError,
 >;
 
-pub fn parse_bgp_update_attribute(
-    input: &[u8],
+// Parse path attributes. Malformed attributes are handled as described in
+// RFC7606 and the approach is returned along with the attributes which are
+// successfully parsed. `ipv4_nlri` tells whether the UPDATE carries NLRI in
+// the NLRI field, which requires NEXT_HOP attribute.
+pub fn parse_bgp_update_attribute<'a>(
+    input: &'a [u8],
     length: u16,
-    as4: bool,
-    opt: Option<ParseOption>,
-) -> ParsedAttributes<'_> {
+    ipv4_nlri: bool,
+    opt: &Option<ParseOption>,
+) -> ParsedAttributes<'a> {
+    let as4 = opt.as_ref().is_some_and(ParseOption::is_as4);
+    if input.len() < length as usize {
+        return Err(BgpParseError::IncompleteData {
+            needed: length as usize - input.len(),
+        });
+    }
     let (attr, input) = input.split_at(length as usize);
     let mut remaining = attr;
     let mut bgp_attr = BgpAttr::default();
     let mut mp_update: Option<MpNlriReachAttr> = None;
     let mut mp_withdraw: Option<MpNlriUnreachAttr> = None;
+    let mut action: Option<UpdateErrorAction> = None;
+    let mut seen: BTreeSet<u8> = BTreeSet::new();
+    let mut as2path: Option<As2Path> = None;
+    let mut as4path: Option<As4Path> = None;
+    let mut aggregator2: Option<Aggregator2> = None;
+    let mut as4_aggregator: Option<As4Aggregator> = None;
 
     while !remaining.is_empty() {
-        let (new_remaining, attr) = Attr::parse_attr(remaining, as4, &opt)?;
+        // RFC7606 4: Attribute overruns the Total Attribute Length or too
+        // short to be an attribute. The NLRI is still located by Total
+        // Attribute Length.
+        let Ok((new_remaining, header)) = AttrHeader::parse(remaining) else {
+            UpdateErrorAction::escalate(&mut action, UpdateErrorAction::TreatAsWithdraw);
+            break;
+        };
+        remaining = new_remaining;
+
+        // RFC7606 3.g: Only the first occurrence of the attribute is used
+        // except MP_REACH_NLRI and MP_UNREACH_NLRI.
+        if !seen.insert(header.type_code) {
+            if matches!(
+                header.attr_type,
+                AttrType::MpReachNlri | AttrType::MpUnreachNlri
+            ) {
+                UpdateErrorAction::escalate(
+                    &mut action,
+                    UpdateErrorAction::session_reset(UpdateError::MalformedAttributeList, &[]),
+                );
+                break;
+            }
+            UpdateErrorAction::escalate(&mut action, UpdateErrorAction::AttributeDiscard);
+            continue;
+        }
+
+        let attr = match Attr::parse_attr_value(&header, as4, opt) {
+            Ok(attr) => attr,
+            Err(BgpParseError::MalformedAttribute {
+                sub_code: UpdateError::UnrecognizedWellknownAttribute,
+                ..
+            }) => {
+                UpdateErrorAction::escalate(
+                    &mut action,
+                    UpdateErrorAction::session_reset(
+                        UpdateError::UnrecognizedWellknownAttribute,
+                        header.raw,
+                    ),
+                );
+                break;
+            }
+            Err(err) => {
+                let other = malformed_attr_action(header.attr_type, &err, header.raw);
+                let reset = other.is_session_reset();
+                UpdateErrorAction::escalate(&mut action, other);
+                if reset {
+                    break;
+                }
+                continue;
+            }
+        };
         match attr {
             Attr::Origin(v) => {
                 bgp_attr.origin = Some(v);
             }
-            Attr::As2Path(_v) => {
-                // TODO.
+            Attr::As2Path(v) => {
+                as2path = Some(v);
             }
             Attr::As4Path(v) => {
                 bgp_attr.aspath = Some(v);
@@ -321,8 +560,8 @@ pub fn parse_bgp_update_attribute(
             Attr::Aggregator(v) => {
                 bgp_attr.aggregator = Some(v);
             }
-            Attr::Aggregator2(_v) => {
-                // TODO
+            Attr::Aggregator2(v) => {
+                aggregator2 = Some(v);
             }
             Attr::Community(v) => {
                 bgp_attr.com = Some(v);
@@ -334,13 +573,51 @@ pub fn parse_bgp_update_attribute(
                 bgp_attr.cluster_list = Some(v);
             }
             Attr::MpReachNlri(v) => {
+                // Kept apart from NEXT_HOP which is used for IPv4 NLRI in
+                // the same UPDATE.
+                if let MpNlriReachAttr::Ipv4 {
+                    nhop: IpAddr::V6(global),
+                    link_local,
+                    ..
+                }
+                | MpNlriReachAttr::Ipv6 {
+                    nhop: IpAddr::V6(global),
+                    link_local,
+                    ..
+                }
+                | MpNlriReachAttr::Ipv4Labeled {
+                    nhop: IpAddr::V6(global),
+                    link_local,
+                    ..
+                }
+                | MpNlriReachAttr::Ipv6Labeled {
+                    nhop: IpAddr::V6(global),
+                    link_local,
+                    ..
+                } = &v
+                {
+                    bgp_attr.mp_nexthop = Some(BgpNexthop::Ipv6 {
+                        global: *global,
+                        link_local: *link_local,
+                    });
+                }
+                if let MpNlriReachAttr::Ipv4Labeled {
+                    nhop: IpAddr::V4(addr),
+                    ..
+                } = &v
+                {
+                    bgp_attr.mp_nexthop = Some(BgpNexthop::Ipv4(*addr));
+                }
                 if let MpNlriReachAttr::Vpnv4 {
                     snpa: _,
                     nhop,
                     updates: _,
                 } = &v
                 {
-                    bgp_attr.nexthop = Some(BgpNexthop::Vpnv4(nhop.clone()));
+                    bgp_attr.mp_nexthop = Some(BgpNexthop::Vpnv4(nhop.clone()));
+                }
+                if let MpNlriReachAttr::Vpnv6 { nhop, .. } = &v {
+                    bgp_attr.mp_nexthop = Some(BgpNexthop::Vpnv6(nhop.clone()));
                 }
                 if let MpNlriReachAttr::Evpn {
                     snpa: _,
@@ -348,7 +625,7 @@ pub fn parse_bgp_update_attribute(
                     updates: _,
                 } = &v
                 {
-                    bgp_attr.nexthop = Some(BgpNexthop::Evpn(*nhop));
+                    bgp_attr.mp_nexthop = Some(BgpNexthop::Evpn(*nhop));
                 }
                 mp_update = Some(v);
             }
@@ -358,18 +635,82 @@ pub fn parse_bgp_update_attribute(
             Attr::ExtendedCom(v) => {
                 bgp_attr.ecom = Some(v);
             }
+            Attr::ExtendedIpv6Com(v) => {
+                bgp_attr.ecom_v6 = Some(v);
+            }
+            Attr::As4PathAttr(v) => {
+                // RFC6793 4.1: Discarded when it is received from NEW BGP
+                // speaker.
+                if !as4 {
+                    as4path = Some(v.0);
+                }
+            }
+            Attr::As4Aggregator(v) => {
+                if !as4 {
+                    as4_aggregator = Some(v);
+                }
+            }
             Attr::PmsiTunnel(v) => {
                 bgp_attr.pmsi_tunnel = Some(v);
             }
+            Attr::TunnelEncap(v) => {
+                bgp_attr.tunnel_encap = Some(v);
+            }
             Attr::Aigp(v) => {
                 bgp_attr.aigp = Some(v);
             }
+            Attr::LinkState(v) => {
+                bgp_attr.link_state = Some(v);
+            }
             Attr::LargeCom(v) => {
                 bgp_attr.lcom = Some(v);
             }
+            Attr::Otc(v) => {
+                bgp_attr.otc = Some(v);
+            }
+            Attr::PrefixSid(v) => {
+                bgp_attr.prefix_sid = Some(v);
+            }
+            Attr::Unknown {
+                flags,
+                type_code,
+                data,
+            } => {
+                bgp_attr
+                    .unknown
+                    .push(UnknownAttr::new(flags, type_code, data));
+            }
         }
-        remaining = new_remaining;
     }
 
-    Ok((input, Some(bgp_attr), mp_update, mp_withdraw))
+    // RFC6793 4.2.3: AS4_AGGREGATOR and AS4_PATH are used only when AGGREGATOR
+    // has AS_TRANS.
+    if let Some(aggregator) = aggregator2 {
+        if aggregator.asn != AS_TRANS {
+            as4path = None;
+            bgp_attr.aggregator = Some(aggregator.into());
+        } else if let Some(as4_aggregator) = as4_aggregator {
+            bgp_attr.aggregator = Some(as4_aggregator.into());
+        } else {
+            bgp_attr.aggregator = Some(aggregator.into());
+        }
+    }
+    if let Some(aspath) = as2path {
+        bgp_attr.aspath = Some(match &as4path {
+            Some(as4path) => aspath.merge_as4(as4path),
+            None => aspath.into(),
+        });
+    }
+
+    // RFC7606 3.d: Missing well-known mandatory attribute when NLRI exists.
+    let has_nlri = ipv4_nlri || mp_update.is_some();
+    let missing = [AttrType::Origin, AttrType::AsPath]
+        .iter()
+        .any(|typ| !seen.contains(&u8::from(*typ)))
+        || (ipv4_nlri && !seen.contains(&u8::from(AttrType::NextHop)));
+    if has_nlri && missing {
+        UpdateErrorAction::escalate(&mut action, UpdateErrorAction::TreatAsWithdraw);
+    }
+
+    Ok((input, Some(bgp_attr), mp_update, mp_withdraw, action))
 }
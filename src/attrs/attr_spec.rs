@@ -0,0 +1,161 @@
+use crate::{AttrType, AttributeFlags, BgpParseError, UpdateError};
+
+// Expected length of the attribute value.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum AttrLength {
+    Fixed(usize),
+    NonZeroMultiple(usize),
+    Min(usize),
+    Variable,
+}
+
+impl AttrLength {
+    pub fn is_valid(&self, len: usize) -> bool {
+        match self {
+            AttrLength::Fixed(v) => len == *v,
+            AttrLength::NonZeroMultiple(v) => len != 0 && len.is_multiple_of(*v),
+            AttrLength::Min(v) => len >= *v,
+            AttrLength::Variable => true,
+        }
+    }
+}
+
+// Attribute flags and length defined for each attribute type.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct AttrSpec {
+    pub optional: bool,
+    pub transitive: bool,
+    pub length: AttrLength,
+}
+
+const fn spec(optional: bool, transitive: bool, length: AttrLength) -> AttrSpec {
+    AttrSpec {
+        optional,
+        transitive,
+        length,
+    }
+}
+
+const WELL_KNOWN: bool = false;
+const OPTIONAL: bool = true;
+const TRANSITIVE: bool = true;
+const NON_TRANSITIVE: bool = false;
+
+impl AttrType {
+    // Attribute catalogue. `as4` decides the length of AGGREGATOR. None for
+    // unknown attribute type.
+    pub fn spec(&self, as4: bool) -> Option<AttrSpec> {
+        use AttrLength::*;
+        use AttrType::*;
+        let spec = match self {
+            Origin => spec(WELL_KNOWN, TRANSITIVE, Fixed(1)),
+            AsPath => spec(WELL_KNOWN, TRANSITIVE, Variable),
+            NextHop => spec(WELL_KNOWN, TRANSITIVE, Fixed(4)),
+            Med => spec(OPTIONAL, NON_TRANSITIVE, Fixed(4)),
+            LocalPref => spec(WELL_KNOWN, TRANSITIVE, Fixed(4)),
+            AtomicAggregate => spec(WELL_KNOWN, TRANSITIVE, Fixed(0)),
+            Aggregator => spec(OPTIONAL, TRANSITIVE, Fixed(if as4 { 8 } else { 6 })),
+            Community => spec(OPTIONAL, TRANSITIVE, NonZeroMultiple(4)),
+            OriginatorId => spec(OPTIONAL, NON_TRANSITIVE, Fixed(4)),
+            ClusterList => spec(OPTIONAL, NON_TRANSITIVE, NonZeroMultiple(4)),
+            MpReachNlri => spec(OPTIONAL, NON_TRANSITIVE, Min(5)),
+            MpUnreachNlri => spec(OPTIONAL, NON_TRANSITIVE, Min(3)),
+            ExtendedCom => spec(OPTIONAL, TRANSITIVE, NonZeroMultiple(8)),
+            As4Path => spec(OPTIONAL, TRANSITIVE, Variable),
+            As4Aggregator => spec(OPTIONAL, TRANSITIVE, Fixed(8)),
+            PmsiTunnel => spec(OPTIONAL, TRANSITIVE, Min(5)),
+            TunnelEncap => spec(OPTIONAL, TRANSITIVE, Variable),
+            ExtendedIpv6Com => spec(OPTIONAL, TRANSITIVE, NonZeroMultiple(20)),
+            Aigp => spec(OPTIONAL, NON_TRANSITIVE, Min(3)),
+            LinkState => spec(OPTIONAL, NON_TRANSITIVE, Variable),
+            LargeCom => spec(OPTIONAL, TRANSITIVE, NonZeroMultiple(12)),
+            Otc => spec(OPTIONAL, TRANSITIVE, Fixed(4)),
+            PrefixSid => spec(OPTIONAL, TRANSITIVE, Variable),
+            Unknown(_) => return None,
+        };
+        Some(spec)
+    }
+}
+
+impl AttrSpec {
+    // Check attribute flags and length. `raw` is the whole attribute which is
+    // used for NOTIFICATION data. Partial bit must be 0 for well-known and
+    // optional non-transitive attributes (RFC4271 4.3).
+    pub fn validate(
+        &self,
+        attr_type: AttrType,
+        flags: &AttributeFlags,
+        len: usize,
+        raw: &[u8],
+    ) -> Result<(), BgpParseError> {
+        if flags.contains(AttributeFlags::OPTIONAL) != self.optional
+            || flags.contains(AttributeFlags::TRANSITIVE) != self.transitive
+            || (flags.contains(AttributeFlags::PARTIAL) && !(self.optional && self.transitive))
+        {
+            return Err(BgpParseError::MalformedAttribute {
+                attr_type,
+                sub_code: UpdateError::AttributeFlagsError,
+                data: raw.to_vec(),
+            });
+        }
+        if !self.length.is_valid(len) {
+            return Err(BgpParseError::MalformedAttribute {
+                attr_type,
+                sub_code: UpdateError::AttributeLengthError,
+                data: raw.to_vec(),
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate() {
+        let spec = AttrType::LocalPref.spec(true).unwrap();
+        let flags = AttributeFlags::TRANSITIVE;
+        assert!(spec.validate(AttrType::LocalPref, &flags, 4, &[]).is_ok());
+
+        let raw = [0x40, 0x05, 0x03, 0x00, 0x00, 0x64];
+        let Err(BgpParseError::MalformedAttribute { sub_code, data, .. }) =
+            spec.validate(AttrType::LocalPref, &flags, 3, &raw)
+        else {
+            panic!("Length error expected");
+        };
+        assert_eq!(sub_code, UpdateError::AttributeLengthError);
+        assert_eq!(data, raw);
+
+        let spec = AttrType::Origin.spec(true).unwrap();
+        let flags = AttributeFlags::OPTIONAL | AttributeFlags::TRANSITIVE;
+        let Err(BgpParseError::MalformedAttribute { sub_code, .. }) =
+            spec.validate(AttrType::Origin, &flags, 1, &[])
+        else {
+            panic!("Flags error expected");
+        };
+        assert_eq!(sub_code, UpdateError::AttributeFlagsError);
+
+        // Partial bit is only allowed for optional transitive attributes.
+        let flags = AttributeFlags::TRANSITIVE | AttributeFlags::PARTIAL;
+        let Err(BgpParseError::MalformedAttribute { sub_code, .. }) =
+            spec.validate(AttrType::Origin, &flags, 1, &[])
+        else {
+            panic!("Flags error expected");
+        };
+        assert_eq!(sub_code, UpdateError::AttributeFlagsError);
+
+        let spec = AttrType::Med.spec(true).unwrap();
+        let flags = AttributeFlags::OPTIONAL | AttributeFlags::PARTIAL;
+        assert!(spec.validate(AttrType::Med, &flags, 4, &[]).is_err());
+
+        let spec = AttrType::Community.spec(true).unwrap();
+        let flags = AttributeFlags::OPTIONAL | AttributeFlags::TRANSITIVE | AttributeFlags::PARTIAL;
+        assert!(spec.validate(AttrType::Community, &flags, 4, &[]).is_ok());
+
+        let spec = AttrType::Aggregator.spec(false).unwrap();
+        assert!(spec.length.is_valid(6));
+        assert!(!AttrLength::NonZeroMultiple(4).is_valid(0));
+    }
+}
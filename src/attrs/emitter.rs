@@ -45,3 +45,9 @@ pub trait AttrEmitter {
         }
     }
 }
+
+// NLRI encoding in UPDATE message. ADD-PATH Path Identifier is emitted when
+// it is not zero.
+pub trait EmitNlri {
+    fn emit_nlri(&self, buf: &mut BytesMut);
+}
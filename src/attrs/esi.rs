@@ -0,0 +1,234 @@
+use std::fmt;
+use std::net::Ipv4Addr;
+
+use bytes::{BufMut, BytesMut};
+use nom::IResult;
+use nom::bytes::complete::take;
+use nom::number::complete::{be_u8, be_u16, be_u24, be_u32};
+use nom_derive::*;
+
+// Ethernet Segment Identifier (RFC7432 5). Type octet followed by 9 octets
+// value. Trailing octet of type 1, 2, 4 and 5 should be zero but the received
+// value is kept.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Esi {
+    // Type 0: Operator configured.
+    Arbitrary([u8; 9]),
+    // Type 1: CE LACP system MAC address and port key.
+    Lacp {
+        mac: [u8; 6],
+        port_key: u16,
+        reserved: u8,
+    },
+    // Type 2: Root bridge MAC address and priority.
+    Bridge {
+        mac: [u8; 6],
+        priority: u16,
+        reserved: u8,
+    },
+    // Type 3: System MAC address and 3 octets local discriminator.
+    Mac {
+        mac: [u8; 6],
+        discriminator: u32,
+    },
+    // Type 4: Router ID and local discriminator.
+    RouterId {
+        router_id: Ipv4Addr,
+        discriminator: u32,
+        reserved: u8,
+    },
+    // Type 5: AS number and local discriminator.
+    As {
+        asn: u32,
+        discriminator: u32,
+        reserved: u8,
+    },
+    Unknown(u8, [u8; 9]),
+}
+
+impl Default for Esi {
+    fn default() -> Self {
+        Esi::Arbitrary([0u8; 9])
+    }
+}
+
+fn parse_mac(input: &[u8]) -> IResult<&[u8], [u8; 6]> {
+    let (input, val) = take(6usize).parse(input)?;
+    let mut mac = [0u8; 6];
+    mac.copy_from_slice(val);
+    Ok((input, mac))
+}
+
+impl Esi {
+    pub fn parse_be(input: &[u8]) -> IResult<&[u8], Self> {
+        let (input, esi_type) = be_u8(input)?;
+        let (input, val) = take(9usize).parse(input)?;
+        let mut value = [0u8; 9];
+        value.copy_from_slice(val);
+        let esi = match esi_type {
+            0 => Esi::Arbitrary(value),
+            1 => {
+                let (val, mac) = parse_mac(val)?;
+                let (val, port_key) = be_u16(val)?;
+                let (_, reserved) = be_u8(val)?;
+                Esi::Lacp {
+                    mac,
+                    port_key,
+                    reserved,
+                }
+            }
+            2 => {
+                let (val, mac) = parse_mac(val)?;
+                let (val, priority) = be_u16(val)?;
+                let (_, reserved) = be_u8(val)?;
+                Esi::Bridge {
+                    mac,
+                    priority,
+                    reserved,
+                }
+            }
+            3 => {
+                let (val, mac) = parse_mac(val)?;
+                let (_, discriminator) = be_u24(val)?;
+                Esi::Mac { mac, discriminator }
+            }
+            4 => {
+                let (val, router_id) = be_u32(val)?;
+                let (val, discriminator) = be_u32(val)?;
+                let (_, reserved) = be_u8(val)?;
+                Esi::RouterId {
+                    router_id: Ipv4Addr::from(router_id),
+                    discriminator,
+                    reserved,
+                }
+            }
+            5 => {
+                let (val, asn) = be_u32(val)?;
+                let (val, discriminator) = be_u32(val)?;
+                let (_, reserved) = be_u8(val)?;
+                Esi::As {
+                    asn,
+                    discriminator,
+                    reserved,
+                }
+            }
+            _ => Esi::Unknown(esi_type, value),
+        };
+        Ok((input, esi))
+    }
+
+    pub fn esi_type(&self) -> u8 {
+        match self {
+            Esi::Arbitrary(_) => 0,
+            Esi::Lacp { .. } => 1,
+            Esi::Bridge { .. } => 2,
+            Esi::Mac { .. } => 3,
+            Esi::RouterId { .. } => 4,
+            Esi::As { .. } => 5,
+            Esi::Unknown(typ, _) => *typ,
+        }
+    }
+
+    // 9 octets value.
+    pub fn value(&self) -> [u8; 9] {
+        let mut buf = BytesMut::with_capacity(9);
+        match self {
+            Esi::Arbitrary(value) | Esi::Unknown(_, value) => {
+                buf.put(&value[..]);
+            }
+            Esi::Lacp {
+                mac,
+                port_key: val,
+                reserved,
+            }
+            | Esi::Bridge {
+                mac,
+                priority: val,
+                reserved,
+            } => {
+                buf.put(&mac[..]);
+                buf.put_u16(*val);
+                buf.put_u8(*reserved);
+            }
+            Esi::Mac { mac, discriminator } => {
+                buf.put(&mac[..]);
+                buf.put(&discriminator.to_be_bytes()[1..]);
+            }
+            Esi::RouterId {
+                router_id,
+                discriminator,
+                reserved,
+            } => {
+                buf.put(&router_id.octets()[..]);
+                buf.put_u32(*discriminator);
+                buf.put_u8(*reserved);
+            }
+            Esi::As {
+                asn,
+                discriminator,
+                reserved,
+            } => {
+                buf.put_u32(*asn);
+                buf.put_u32(*discriminator);
+                buf.put_u8(*reserved);
+            }
+        }
+        let mut value = [0u8; 9];
+        value.copy_from_slice(&buf);
+        value
+    }
+
+    // Zero ESI means single-homed site.
+    pub fn is_zero(&self) -> bool {
+        self.esi_type() == 0 && self.value() == [0u8; 9]
+    }
+
+    pub fn emit(&self, buf: &mut BytesMut) {
+        buf.put_u8(self.esi_type());
+        buf.put(&self.value()[..]);
+    }
+}
+
+impl fmt::Display for Esi {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02x}", self.esi_type())?;
+        for val in self.value().iter() {
+            write!(f, ":{:02x}", val)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+
+    #[test]
+    fn esi_types() {
+        let inputs: [&[u8]; 6] = [
+            &hex!("00 010203040506070809"),
+            &hex!("01 aabbccddeeff 0102 00"),
+            &hex!("03 aabbccddeeff 000064"),
+            &hex!("04 c0a80001 00000064 00"),
+            &hex!("05 0000fde8 00000064 00"),
+            &hex!("02 aabbccddeeff 8000 ff"),
+        ];
+        for input in inputs {
+            let (_, esi) = Esi::parse_be(input).unwrap();
+            let mut buf = BytesMut::new();
+            esi.emit(&mut buf);
+            assert_eq!(&buf[..], input);
+        }
+        let (_, esi) = Esi::parse_be(inputs[3]).unwrap();
+        assert_eq!(
+            esi,
+            Esi::RouterId {
+                router_id: Ipv4Addr::new(192, 168, 0, 1),
+                discriminator: 100,
+                reserved: 0,
+            }
+        );
+        assert!(Esi::default().is_zero());
+    }
+}
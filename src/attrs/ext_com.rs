@@ -1,21 +1,17 @@
 use std::fmt;
-use std::net::Ipv4Addr;
 use std::str::FromStr;
 
 use bytes::{BufMut, BytesMut};
 use nom_derive::NomBE;
 
 use crate::{
-    AttrEmitter, AttrFlags, AttrType, ExtCommunitySubType, ExtCommunityType, RouteDistinguisher,
-    RouteDistinguisherType, TunnelType,
+    AttrEmitter, AttrFlags, AttrType, ExtCommunityKind, RouteDistinguisher, RouteDistinguisherType,
 };
 
-use super::ext_com_token::{Token, tokenizer};
-
 #[derive(Clone, Default, NomBE)]
 pub struct ExtCommunity(pub Vec<ExtCommunityValue>);
 
-#[derive(Clone, Debug, Default, NomBE)]
+#[derive(Clone, Debug, Default, PartialEq, NomBE)]
 pub struct ExtCommunityValue {
     pub high_type: u8,
     pub low_type: u8,
@@ -32,41 +28,7 @@ impl ExtCommunityValue {
 
 impl fmt::Display for ExtCommunityValue {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        use ExtCommunityType::*;
-        if self.high_type == TransTwoOctetAS as u8 {
-            let asn = u16::from_be_bytes([self.val[0], self.val[1]]);
-            let val = u32::from_be_bytes([self.val[2], self.val[3], self.val[4], self.val[5]]);
-            write!(
-                f,
-                "{} {asn}:{val}",
-                ExtCommunitySubType::display(self.low_type)
-            )
-        } else if self.high_type == TransOpaque as u8 {
-            let ip = Ipv4Addr::new(self.val[0], self.val[1], self.val[2], self.val[3]);
-            let val = u16::from_be_bytes([self.val[4], self.val[5]]);
-            if let Ok(tunnel_type) = TunnelType::try_from(val) {
-                write!(
-                    f,
-                    "{} {}",
-                    ExtCommunitySubType::display(self.low_type),
-                    tunnel_type
-                )
-            } else {
-                write!(
-                    f,
-                    "{} {ip}:{val}",
-                    ExtCommunitySubType::display(self.low_type)
-                )
-            }
-        } else {
-            let ip = Ipv4Addr::new(self.val[0], self.val[1], self.val[2], self.val[3]);
-            let val = u16::from_be_bytes([self.val[4], self.val[5]]);
-            write!(
-                f,
-                "{} {ip}:{val}",
-                ExtCommunitySubType::display(self.low_type)
-            )
-        }
+        write!(f, "{}", ExtCommunityKind::from(self))
     }
 }
 
@@ -110,45 +72,40 @@ impl fmt::Debug for ExtCommunity {
     }
 }
 
-#[derive(PartialEq)]
-enum State {
-    Unspec,
-    Rt,
-    Soo,
+// Longest value in ExtCommunityKind format at the start of `tokens`. The
+// value is prefixed by `keyword` when it is given. Returns the value and the
+// number of tokens used.
+fn parse_kind(keyword: Option<&str>, tokens: &[&str]) -> Option<(ExtCommunityKind, usize)> {
+    (1..=tokens.len()).rev().find_map(|len| {
+        let value: Vec<&str> = keyword
+            .into_iter()
+            .chain(tokens[..len].iter().copied())
+            .collect();
+        let kind = value.join(" ").parse().ok()?;
+        Some((kind, len))
+    })
 }
 
+// Values in ExtCommunityKind format, for example "rt 65000:100 soo
+// 10.0.0.1:100 color 100". The keyword may be omitted for the following
+// value with the same keyword as "rt 65000:100 65000:200".
 impl FromStr for ExtCommunity {
     type Err = ();
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut ecom = ExtCommunity::default();
-        let tokens = tokenizer(String::from(s)).unwrap();
-        let mut state = State::Unspec;
-
-        for token in tokens.into_iter() {
-            match token {
-                Token::Rd(rd) => {
-                    let mut val: ExtCommunityValue = rd.into();
-                    match state {
-                        State::Unspec => {
-                            return Err(());
-                        }
-                        State::Rt => {
-                            val.low_type = 0x02;
-                        }
-                        State::Soo => {
-                            val.low_type = 0x03;
-                        }
-                    }
-                    ecom.0.push(val);
-                }
-                Token::Rt => {
-                    state = State::Rt;
+        let mut tokens: Vec<&str> = s.split_whitespace().collect();
+        let mut keyword = None;
+        while !tokens.is_empty() {
+            let (kind, len) = match parse_kind(None, &tokens) {
+                Some(v) => {
+                    keyword = Some(tokens[0]);
+                    v
                 }
-                Token::Soo => {
-                    state = State::Soo;
-                }
-            }
+                None => parse_kind(keyword, &tokens).ok_or(())?,
+            };
+            ecom.0.push(kind.into());
+            tokens.drain(..len);
         }
         Ok(ecom)
     }
@@ -175,6 +132,7 @@ impl From<RouteDistinguisher> for ExtCommunityValue {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{EviRt, EvpnMcastFlags, ExtCommunityAdmin, FlowspecAction};
 
     #[test]
     fn parse() {
@@ -186,5 +144,151 @@ mod tests {
 
         let ecom: ExtCommunity = ExtCommunity::from_str("rt 1.2.3.4:100 soo 10:100").unwrap();
         assert_eq!(ecom.to_string(), "rt 1.2.3.4:100 soo 10:100");
+
+        // Keyword is omitted for the following value.
+        let ecom: ExtCommunity = ExtCommunity::from_str("rt 100:1 100:2 color 10").unwrap();
+        assert_eq!(ecom.to_string(), "rt 100:1 rt 100:2 color 10");
+
+        assert!(ExtCommunity::from_str("100:1").is_err());
+        assert!(ExtCommunity::from_str("rt 100:1 unknown").is_err());
+    }
+
+    #[test]
+    fn evpn_mcast() {
+        let flags = EvpnMcastFlags {
+            igmp_proxy: true,
+            mld_proxy: false,
+        };
+        let val: ExtCommunityValue = flags.into();
+        assert_eq!(val.val, [0, 1, 0, 0, 0, 0]);
+        assert_eq!(EvpnMcastFlags::try_from(&val), Ok(flags));
+
+        let evi_rt = EviRt(ExtCommunityAdmin::As2 { asn: 100, val: 200 });
+        let ecom = ExtCommunity(vec![val, evi_rt.into()]);
+        assert_eq!(ecom.to_string(), "mcast-flags igmp-proxy evi-rt 100:200");
+    }
+
+    #[test]
+    fn flowspec_action() {
+        let inputs = [
+            "traffic-rate 0:0",
+            "traffic-rate-packets 100:1000.5",
+            "traffic-action sample terminal",
+            "redirect 65000:100",
+            "redirect 10.0.0.1:100",
+            "redirect 4200000000:100",
+            "redirect-ip copy",
+            "traffic-marking 46",
+        ];
+        let actions: Vec<FlowspecAction> = inputs.iter().map(|s| s.parse().unwrap()).collect();
+        let ecom = ExtCommunity(actions.iter().map(|a| (*a).into()).collect());
+        assert_eq!(ecom.to_string(), inputs.join(" "));
+        assert_eq!(ecom.flowspec_actions(), actions);
+
+        // Discard traffic.
+        let val: ExtCommunityValue = actions[0].into();
+        let mut buf = BytesMut::new();
+        val.encode(&mut buf);
+        assert_eq!(&buf[..], &[0x80, 0x06, 0, 0, 0, 0, 0, 0]);
+
+        assert!("traffic-marking 64".parse::<FlowspecAction>().is_err());
+
+        // Redirect in the extended community attribute.
+        let ecom = ExtCommunity::from_str("redirect 65000:100").unwrap();
+        assert_eq!(
+            ecom.flowspec_actions(),
+            [FlowspecAction::Redirect(ExtCommunityAdmin::As2 {
+                asn: 65000,
+                val: 100
+            })]
+        );
+        let mut buf = BytesMut::new();
+        ecom.emit(&mut buf);
+        assert_eq!(&buf[..], &[0x80, 0x08, 0xfd, 0xe8, 0, 0, 0, 100]);
+        assert_eq!(ecom.to_string(), "redirect 65000:100");
+    }
+
+    #[test]
+    fn ext_community_kind() {
+        let inputs = [
+            "rt 65000:100",
+            "rt 10.0.0.1:100",
+            "rt 4200000000:100",
+            "soo 65000:200",
+            "encap VXLAN",
+            "encap 15",
+            "color 100",
+            "color 200 flags 0x4000",
+            "bandwidth 65000:1250000",
+            "ospf-domain-id 10.0.0.1:0",
+            "ospf-route-type 0.0.0.1:5:1",
+            "ospf-router-id 10.0.0.1",
+            "vrf-import 10.0.0.1:10",
+            "source-as 65000",
+            "source-as 4200000000",
+            "mac-mobility 10 sticky",
+            "esi-label 100 single-active",
+            "es-import-rt 00:00:5e:00:53:01",
+            "router-mac 00:00:5e:00:53:02",
+            "mcast-flags igmp-proxy",
+            "evi-rt 65000:100",
+            "evi-rt 10.0.0.1:100",
+            "evi-rt 4200000000:100",
+            "redirect 65000:100",
+        ];
+        for input in inputs {
+            let kind: ExtCommunityKind = input.parse().unwrap();
+            let val: ExtCommunityValue = kind.clone().into();
+            assert_eq!(ExtCommunityKind::from(&val), kind);
+            assert_eq!(val.to_string(), input);
+        }
+
+        // All kinds in one attribute.
+        let input = inputs.join(" ");
+        let ecom = ExtCommunity::from_str(&input).unwrap();
+        assert_eq!(ecom.0.len(), inputs.len());
+        assert_eq!(ecom.to_string(), input);
+
+        let kind: ExtCommunityKind = "rt 4200000000:100".parse().unwrap();
+        assert_eq!(
+            kind,
+            ExtCommunityKind::RouteTarget(ExtCommunityAdmin::As4 {
+                asn: 4200000000,
+                val: 100
+            })
+        );
+        let mut buf = BytesMut::new();
+        ExtCommunityValue::from(kind).encode(&mut buf);
+        assert_eq!(&buf[..], &[0x02, 0x02, 0xfa, 0x56, 0xea, 0x00, 0x00, 0x64]);
+
+        // Unknown flags and values are kept as is.
+        let val = ExtCommunityValue {
+            high_type: 0x06,
+            low_type: 0x00,
+            val: [0x02, 0, 0, 0, 0, 10],
+        };
+        let kind = ExtCommunityKind::from(&val);
+        assert!(matches!(kind, ExtCommunityKind::Other(_)));
+        assert_eq!(kind.to_string(), "unknown 0x02000000000a");
+        assert_eq!(ExtCommunityValue::from(kind), val);
+
+        // Unknown sub-types are shown by the high type.
+        let other = |high_type, low_type, val| {
+            ExtCommunityKind::from(&ExtCommunityValue {
+                high_type,
+                low_type,
+                val,
+            })
+            .to_string()
+        };
+        assert_eq!(
+            other(0x00, 0x10, [0xfd, 0xe8, 0, 0, 0, 100]),
+            "unknown 65000:100"
+        );
+        assert_eq!(
+            other(0x41, 0x10, [10, 0, 0, 1, 0, 100]),
+            "unknown 10.0.0.1:100"
+        );
+        assert_eq!(other(0x03, 0x0c, [1, 0, 0, 0, 0, 8]), "opaque VXLAN");
     }
 }
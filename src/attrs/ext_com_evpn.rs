@@ -0,0 +1,108 @@
+use std::fmt;
+
+use crate::{ExtCommunityAdmin, ExtCommunityType, ExtCommunityValue};
+
+// EVPN extended community sub-types for IGMP/MLD proxy (RFC9251 9.5, 9.6).
+const MCAST_FLAGS: u8 = 0x09;
+const EVI_RT_TYPE_0: u8 = 0x0a;
+const EVI_RT_TYPE_1: u8 = 0x0b;
+const EVI_RT_TYPE_2: u8 = 0x0c;
+
+// Multicast Flags extended community.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct EvpnMcastFlags {
+    pub igmp_proxy: bool,
+    pub mld_proxy: bool,
+}
+
+impl From<EvpnMcastFlags> for ExtCommunityValue {
+    fn from(from: EvpnMcastFlags) -> Self {
+        let mut flags = 0u16;
+        if from.igmp_proxy {
+            flags |= 0x0001;
+        }
+        if from.mld_proxy {
+            flags |= 0x0002;
+        }
+        let mut val = [0u8; 6];
+        val[..2].copy_from_slice(&flags.to_be_bytes());
+        ExtCommunityValue {
+            high_type: ExtCommunityType::Evpn as u8,
+            low_type: MCAST_FLAGS,
+            val,
+        }
+    }
+}
+
+impl TryFrom<&ExtCommunityValue> for EvpnMcastFlags {
+    type Error = ();
+
+    fn try_from(from: &ExtCommunityValue) -> Result<Self, Self::Error> {
+        if from.high_type != ExtCommunityType::Evpn as u8 || from.low_type != MCAST_FLAGS {
+            return Err(());
+        }
+        let flags = u16::from_be_bytes([from.val[0], from.val[1]]);
+        Ok(Self {
+            igmp_proxy: flags & 0x0001 != 0,
+            mld_proxy: flags & 0x0002 != 0,
+        })
+    }
+}
+
+impl fmt::Display for EvpnMcastFlags {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "mcast-flags")?;
+        if self.igmp_proxy {
+            write!(f, " igmp-proxy")?;
+        }
+        if self.mld_proxy {
+            write!(f, " mld-proxy")?;
+        }
+        Ok(())
+    }
+}
+
+// EVI-RT extended community. Route target of the EVI for the Join/Leave
+// Synch routes. Type 0, 1 and 2 follow the administrator form.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EviRt(pub ExtCommunityAdmin);
+
+impl From<EviRt> for ExtCommunityValue {
+    fn from(from: EviRt) -> Self {
+        let low_type = match from.0 {
+            ExtCommunityAdmin::As2 { .. } => EVI_RT_TYPE_0,
+            ExtCommunityAdmin::Ipv4 { .. } => EVI_RT_TYPE_1,
+            ExtCommunityAdmin::As4 { .. } => EVI_RT_TYPE_2,
+        };
+        ExtCommunityValue {
+            high_type: ExtCommunityType::Evpn as u8,
+            low_type,
+            val: from.0.encode(),
+        }
+    }
+}
+
+impl TryFrom<&ExtCommunityValue> for EviRt {
+    type Error = ();
+
+    fn try_from(from: &ExtCommunityValue) -> Result<Self, Self::Error> {
+        if from.high_type != ExtCommunityType::Evpn as u8 {
+            return Err(());
+        }
+        let high_type = match from.low_type {
+            EVI_RT_TYPE_0 => ExtCommunityType::TransTwoOctetAS,
+            EVI_RT_TYPE_1 => ExtCommunityType::TransIpv4Addr,
+            EVI_RT_TYPE_2 => ExtCommunityType::TransFourOctetAS,
+            _ => return Err(()),
+        };
+        ExtCommunityAdmin::decode(high_type as u8, &from.val)
+            .map(EviRt)
+            .ok_or(())
+    }
+}
+
+impl fmt::Display for EviRt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "evi-rt {}", self.0)
+    }
+}
@@ -0,0 +1,223 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{ExtCommunity, ExtCommunityAdmin, ExtCommunityType, ExtCommunityValue};
+
+// Flow Specification action sub-types (RFC8955 7).
+const TRAFFIC_RATE_BYTES: u8 = 0x06;
+const TRAFFIC_ACTION: u8 = 0x07;
+const REDIRECT: u8 = 0x08;
+const TRAFFIC_MARKING: u8 = 0x09;
+const TRAFFIC_RATE_PACKETS: u8 = 0x0c;
+
+const EXPERIMENTAL: u8 = ExtCommunityType::Experimental as u8;
+const EXPERIMENTAL_IPV4: u8 = ExtCommunityType::ExperimentalIpv4 as u8;
+const EXPERIMENTAL_AS4: u8 = ExtCommunityType::ExperimentalAs4 as u8;
+const REDIRECT_IP: u8 = ExtCommunityType::FlowspecRedirectIp as u8;
+
+// Traffic action bits in the last octet.
+const ACTION_SAMPLE: u8 = 0x02;
+const ACTION_TERMINAL: u8 = 0x01;
+
+// Redirect to IP next hop copy bit (draft-ietf-idr-flowspec-redirect-ip).
+const REDIRECT_IP_COPY: u8 = 0x01;
+
+// Flow Specification traffic filtering action. Rate is IEEE floating point
+// in bytes or packets per second and zero rate discards the traffic.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FlowspecAction {
+    TrafficRateBytes { asn: u16, rate: f32 },
+    TrafficRatePackets { asn: u16, rate: f32 },
+    TrafficAction { sample: bool, terminal: bool },
+    // Redirect to the VRF of the route target.
+    Redirect(ExtCommunityAdmin),
+    // Redirect to the next hop in MP_REACH_NLRI.
+    RedirectIp { copy: bool },
+    TrafficMarking { dscp: u8 },
+}
+
+impl From<FlowspecAction> for ExtCommunityValue {
+    fn from(from: FlowspecAction) -> Self {
+        use FlowspecAction::*;
+        let mut val = [0u8; 6];
+        let (high_type, low_type) = match from {
+            TrafficRateBytes { asn, rate } | TrafficRatePackets { asn, rate } => {
+                val[..2].copy_from_slice(&asn.to_be_bytes());
+                val[2..].copy_from_slice(&rate.to_be_bytes());
+                let low_type = if matches!(from, TrafficRateBytes { .. }) {
+                    TRAFFIC_RATE_BYTES
+                } else {
+                    TRAFFIC_RATE_PACKETS
+                };
+                (ExtCommunityType::Experimental, low_type)
+            }
+            TrafficAction { sample, terminal } => {
+                if sample {
+                    val[5] |= ACTION_SAMPLE;
+                }
+                if terminal {
+                    val[5] |= ACTION_TERMINAL;
+                }
+                (ExtCommunityType::Experimental, TRAFFIC_ACTION)
+            }
+            Redirect(admin) => {
+                val = admin.encode();
+                let high_type = match admin {
+                    ExtCommunityAdmin::As2 { .. } => ExtCommunityType::Experimental,
+                    ExtCommunityAdmin::Ipv4 { .. } => ExtCommunityType::ExperimentalIpv4,
+                    ExtCommunityAdmin::As4 { .. } => ExtCommunityType::ExperimentalAs4,
+                };
+                (high_type, REDIRECT)
+            }
+            RedirectIp { copy } => {
+                if copy {
+                    val[5] |= REDIRECT_IP_COPY;
+                }
+                (ExtCommunityType::FlowspecRedirectIp, 0x00)
+            }
+            TrafficMarking { dscp } => {
+                val[5] = dscp & 0x3f;
+                (ExtCommunityType::Experimental, TRAFFIC_MARKING)
+            }
+        };
+        ExtCommunityValue {
+            high_type: high_type as u8,
+            low_type,
+            val,
+        }
+    }
+}
+
+impl TryFrom<&ExtCommunityValue> for FlowspecAction {
+    type Error = ();
+
+    fn try_from(from: &ExtCommunityValue) -> Result<Self, Self::Error> {
+        use FlowspecAction::*;
+        let v = &from.val;
+        let u16_at = |i: usize| u16::from_be_bytes([v[i], v[i + 1]]);
+        let u32_at = |i: usize| u32::from_be_bytes([v[i], v[i + 1], v[i + 2], v[i + 3]]);
+        let action = match (from.high_type, from.low_type) {
+            (EXPERIMENTAL, TRAFFIC_RATE_BYTES) => TrafficRateBytes {
+                asn: u16_at(0),
+                rate: f32::from_bits(u32_at(2)),
+            },
+            (EXPERIMENTAL, TRAFFIC_RATE_PACKETS) => TrafficRatePackets {
+                asn: u16_at(0),
+                rate: f32::from_bits(u32_at(2)),
+            },
+            (EXPERIMENTAL, TRAFFIC_ACTION) => TrafficAction {
+                sample: v[5] & ACTION_SAMPLE != 0,
+                terminal: v[5] & ACTION_TERMINAL != 0,
+            },
+            // Experimental types are the transitive types with the high bit.
+            (high @ (EXPERIMENTAL | EXPERIMENTAL_IPV4 | EXPERIMENTAL_AS4), REDIRECT) => {
+                Redirect(ExtCommunityAdmin::decode(high & !EXPERIMENTAL, v).ok_or(())?)
+            }
+            (REDIRECT_IP, 0x00) => RedirectIp {
+                copy: v[5] & REDIRECT_IP_COPY != 0,
+            },
+            (EXPERIMENTAL, TRAFFIC_MARKING) => TrafficMarking { dscp: v[5] & 0x3f },
+            _ => return Err(()),
+        };
+        Ok(action)
+    }
+}
+
+impl fmt::Display for FlowspecAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use FlowspecAction::*;
+        match self {
+            TrafficRateBytes { asn, rate } => write!(f, "traffic-rate {asn}:{rate}"),
+            TrafficRatePackets { asn, rate } => write!(f, "traffic-rate-packets {asn}:{rate}"),
+            TrafficAction { sample, terminal } => {
+                write!(f, "traffic-action")?;
+                if *sample {
+                    write!(f, " sample")?;
+                }
+                if *terminal {
+                    write!(f, " terminal")?;
+                }
+                Ok(())
+            }
+            Redirect(admin) => write!(f, "redirect {admin}"),
+            RedirectIp { copy } => {
+                write!(f, "redirect-ip")?;
+                if *copy {
+                    write!(f, " copy")?;
+                }
+                Ok(())
+            }
+            TrafficMarking { dscp } => write!(f, "traffic-marking {dscp}"),
+        }
+    }
+}
+
+// Same format as Display, for example "traffic-rate 0:1000",
+// "redirect 65000:100" and "traffic-action sample terminal". Redirect uses
+// 4 octets AS number when it does not fit in 2 octets.
+impl FromStr for FlowspecAction {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use FlowspecAction::*;
+        let mut tokens = s.split_whitespace();
+        let keyword = tokens.next().ok_or(())?;
+        let args: Vec<&str> = tokens.collect();
+        let arg = || -> Result<&str, ()> {
+            match args.as_slice() {
+                [arg] => Ok(*arg),
+                _ => Err(()),
+            }
+        };
+        let pair = || -> Result<(&str, &str), ()> { arg()?.split_once(':').ok_or(()) };
+        let action = match keyword {
+            "traffic-rate" | "traffic-rate-packets" => {
+                let (asn, rate) = pair()?;
+                let asn = asn.parse::<u16>().map_err(|_| ())?;
+                let rate = rate.parse::<f32>().map_err(|_| ())?;
+                if keyword == "traffic-rate" {
+                    TrafficRateBytes { asn, rate }
+                } else {
+                    TrafficRatePackets { asn, rate }
+                }
+            }
+            "traffic-action" => {
+                let mut sample = false;
+                let mut terminal = false;
+                for arg in args.iter() {
+                    match *arg {
+                        "sample" => sample = true,
+                        "terminal" => terminal = true,
+                        _ => return Err(()),
+                    }
+                }
+                TrafficAction { sample, terminal }
+            }
+            "redirect" => Redirect(arg()?.parse()?),
+            "redirect-ip" => match args.as_slice() {
+                [] => RedirectIp { copy: false },
+                ["copy"] => RedirectIp { copy: true },
+                _ => return Err(()),
+            },
+            "traffic-marking" => {
+                let dscp = arg()?.parse::<u8>().map_err(|_| ())?;
+                if dscp > 0x3f {
+                    return Err(());
+                }
+                TrafficMarking { dscp }
+            }
+            _ => return Err(()),
+        };
+        Ok(action)
+    }
+}
+
+impl ExtCommunity {
+    // Flow Specification actions in the extended communities.
+    pub fn flowspec_actions(&self) -> Vec<FlowspecAction> {
+        self.0
+            .iter()
+            .filter_map(|v| FlowspecAction::try_from(v).ok())
+            .collect()
+    }
+}
@@ -0,0 +1,556 @@
+use std::fmt;
+use std::net::Ipv4Addr;
+use std::str::FromStr;
+
+use crate::{
+    EviRt, EvpnMcastFlags, ExtCommunitySubType, ExtCommunityType, ExtCommunityValue,
+    FlowspecAction, TunnelType,
+};
+
+const TRANS_AS2: u8 = ExtCommunityType::TransTwoOctetAS as u8;
+const TRANS_IPV4: u8 = ExtCommunityType::TransIpv4Addr as u8;
+const TRANS_AS4: u8 = ExtCommunityType::TransFourOctetAS as u8;
+const TRANS_OPAQUE: u8 = ExtCommunityType::TransOpaque as u8;
+const EVPN: u8 = ExtCommunityType::Evpn as u8;
+const NON_TRANS_AS2: u8 = ExtCommunityType::NonTransTwoOctetAS as u8;
+
+// Transitive bit of the high type is 0 for transitive (RFC4360 2).
+const NON_TRANSITIVE: u8 = 0x40;
+
+// Sub-types of the AS and IPv4 address specific types (RFC4360, RFC4577,
+// RFC6514, RFC7153).
+const ROUTE_TARGET: u8 = 0x02;
+const ROUTE_ORIGIN: u8 = 0x03;
+const LINK_BANDWIDTH: u8 = 0x04;
+const OSPF_DOMAIN_ID: u8 = 0x05;
+const OSPF_ROUTER_ID: u8 = 0x07;
+const SOURCE_AS: u8 = 0x09;
+const VRF_ROUTE_IMPORT: u8 = 0x0b;
+
+// Sub-types of the transitive opaque type (RFC4577, RFC9012).
+const OSPF_ROUTE_TYPE: u8 = 0x06;
+const COLOR: u8 = 0x0b;
+const ENCAPSULATION: u8 = 0x0c;
+
+// Sub-types of the EVPN type (RFC7432 7.5-7.7, RFC9135 8.1).
+const MAC_MOBILITY: u8 = 0x00;
+const ESI_LABEL: u8 = 0x01;
+const ES_IMPORT_RT: u8 = 0x02;
+const ROUTER_MAC: u8 = 0x03;
+
+const MAC_MOBILITY_STICKY: u8 = 0x01;
+const ESI_LABEL_SINGLE_ACTIVE: u8 = 0x01;
+
+// Global and local administrator in 2-octet AS, IPv4 address and 4-octet
+// AS specific forms.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExtCommunityAdmin {
+    As2 { asn: u16, val: u32 },
+    Ipv4 { addr: Ipv4Addr, val: u16 },
+    As4 { asn: u32, val: u16 },
+}
+
+impl ExtCommunityAdmin {
+    pub(crate) fn high_type(&self) -> u8 {
+        match self {
+            ExtCommunityAdmin::As2 { .. } => TRANS_AS2,
+            ExtCommunityAdmin::Ipv4 { .. } => TRANS_IPV4,
+            ExtCommunityAdmin::As4 { .. } => TRANS_AS4,
+        }
+    }
+
+    pub(crate) fn encode(&self) -> [u8; 6] {
+        let mut val = [0u8; 6];
+        match self {
+            ExtCommunityAdmin::As2 { asn, val: v } => {
+                val[..2].copy_from_slice(&asn.to_be_bytes());
+                val[2..].copy_from_slice(&v.to_be_bytes());
+            }
+            ExtCommunityAdmin::Ipv4 { addr, val: v } => {
+                val[..4].copy_from_slice(&addr.octets());
+                val[4..].copy_from_slice(&v.to_be_bytes());
+            }
+            ExtCommunityAdmin::As4 { asn, val: v } => {
+                val[..4].copy_from_slice(&asn.to_be_bytes());
+                val[4..].copy_from_slice(&v.to_be_bytes());
+            }
+        }
+        val
+    }
+
+    pub(crate) fn decode(high_type: u8, v: &[u8; 6]) -> Option<Self> {
+        let admin = match high_type {
+            TRANS_AS2 => ExtCommunityAdmin::As2 {
+                asn: u16::from_be_bytes([v[0], v[1]]),
+                val: u32::from_be_bytes([v[2], v[3], v[4], v[5]]),
+            },
+            TRANS_IPV4 => ExtCommunityAdmin::Ipv4 {
+                addr: Ipv4Addr::new(v[0], v[1], v[2], v[3]),
+                val: u16::from_be_bytes([v[4], v[5]]),
+            },
+            TRANS_AS4 => ExtCommunityAdmin::As4 {
+                asn: u32::from_be_bytes([v[0], v[1], v[2], v[3]]),
+                val: u16::from_be_bytes([v[4], v[5]]),
+            },
+            _ => return None,
+        };
+        Some(admin)
+    }
+}
+
+impl fmt::Display for ExtCommunityAdmin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExtCommunityAdmin::As2 { asn, val } => write!(f, "{asn}:{val}"),
+            ExtCommunityAdmin::Ipv4 { addr, val } => write!(f, "{addr}:{val}"),
+            ExtCommunityAdmin::As4 { asn, val } => write!(f, "{asn}:{val}"),
+        }
+    }
+}
+
+// "65000:100", "10.0.0.1:100" or "4200000000:100". 4-octet AS form is used
+// when AS number does not fit in 2 octets.
+impl FromStr for ExtCommunityAdmin {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (global, val) = s.split_once(':').ok_or(())?;
+        if let Ok(addr) = global.parse::<Ipv4Addr>() {
+            let val = val.parse::<u16>().map_err(|_| ())?;
+            return Ok(ExtCommunityAdmin::Ipv4 { addr, val });
+        }
+        if let Ok(asn) = global.parse::<u16>() {
+            let val = val.parse::<u32>().map_err(|_| ())?;
+            return Ok(ExtCommunityAdmin::As2 { asn, val });
+        }
+        let asn = global.parse::<u32>().map_err(|_| ())?;
+        let val = val.parse::<u16>().map_err(|_| ())?;
+        Ok(ExtCommunityAdmin::As4 { asn, val })
+    }
+}
+
+// Typed extended community. Value which does not match any of the known
+// formats is kept as `Other` so the conversion is lossless.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExtCommunityKind {
+    RouteTarget(ExtCommunityAdmin),
+    RouteOrigin(ExtCommunityAdmin),
+    Encapsulation(u16),
+    Color {
+        flags: u16,
+        color: u32,
+    },
+    // Bandwidth is IEEE floating point in bytes per second.
+    LinkBandwidth {
+        asn: u16,
+        bandwidth: f32,
+    },
+    OspfDomainId(ExtCommunityAdmin),
+    OspfRouteType {
+        area: Ipv4Addr,
+        route_type: u8,
+        options: u8,
+    },
+    OspfRouterId(Ipv4Addr),
+    VrfRouteImport {
+        addr: Ipv4Addr,
+        val: u16,
+    },
+    SourceAs(u16),
+    SourceAs4(u32),
+    MacMobility {
+        sticky: bool,
+        seq: u32,
+    },
+    EsiLabel {
+        single_active: bool,
+        label: u32,
+    },
+    EsImportRt([u8; 6]),
+    RouterMac([u8; 6]),
+    McastFlags(EvpnMcastFlags),
+    EviRt(EviRt),
+    Flowspec(FlowspecAction),
+    Other(ExtCommunityValue),
+}
+
+fn value(high_type: u8, low_type: u8, val: [u8; 6]) -> ExtCommunityValue {
+    ExtCommunityValue {
+        high_type,
+        low_type,
+        val,
+    }
+}
+
+impl From<ExtCommunityKind> for ExtCommunityValue {
+    fn from(from: ExtCommunityKind) -> Self {
+        use ExtCommunityKind::*;
+        let mut val = [0u8; 6];
+        match from {
+            RouteTarget(admin) => value(admin.high_type(), ROUTE_TARGET, admin.encode()),
+            RouteOrigin(admin) => value(admin.high_type(), ROUTE_ORIGIN, admin.encode()),
+            Encapsulation(tunnel_type) => {
+                val[4..].copy_from_slice(&tunnel_type.to_be_bytes());
+                value(TRANS_OPAQUE, ENCAPSULATION, val)
+            }
+            Color { flags, color } => {
+                val[..2].copy_from_slice(&flags.to_be_bytes());
+                val[2..].copy_from_slice(&color.to_be_bytes());
+                value(TRANS_OPAQUE, COLOR, val)
+            }
+            LinkBandwidth { asn, bandwidth } => {
+                val[..2].copy_from_slice(&asn.to_be_bytes());
+                val[2..].copy_from_slice(&bandwidth.to_be_bytes());
+                value(NON_TRANS_AS2, LINK_BANDWIDTH, val)
+            }
+            OspfDomainId(admin) => value(admin.high_type(), OSPF_DOMAIN_ID, admin.encode()),
+            OspfRouteType {
+                area,
+                route_type,
+                options,
+            } => {
+                val[..4].copy_from_slice(&area.octets());
+                val[4] = route_type;
+                val[5] = options;
+                value(TRANS_OPAQUE, OSPF_ROUTE_TYPE, val)
+            }
+            OspfRouterId(addr) => {
+                val[..4].copy_from_slice(&addr.octets());
+                value(TRANS_IPV4, OSPF_ROUTER_ID, val)
+            }
+            VrfRouteImport { addr, val: v } => {
+                val[..4].copy_from_slice(&addr.octets());
+                val[4..].copy_from_slice(&v.to_be_bytes());
+                value(TRANS_IPV4, VRF_ROUTE_IMPORT, val)
+            }
+            SourceAs(asn) => {
+                val[..2].copy_from_slice(&asn.to_be_bytes());
+                value(TRANS_AS2, SOURCE_AS, val)
+            }
+            SourceAs4(asn) => {
+                val[..4].copy_from_slice(&asn.to_be_bytes());
+                value(TRANS_AS4, SOURCE_AS, val)
+            }
+            MacMobility { sticky, seq } => {
+                if sticky {
+                    val[0] = MAC_MOBILITY_STICKY;
+                }
+                val[2..].copy_from_slice(&seq.to_be_bytes());
+                value(EVPN, MAC_MOBILITY, val)
+            }
+            EsiLabel {
+                single_active,
+                label,
+            } => {
+                if single_active {
+                    val[0] = ESI_LABEL_SINGLE_ACTIVE;
+                }
+                val[3..].copy_from_slice(&label.to_be_bytes()[1..]);
+                value(EVPN, ESI_LABEL, val)
+            }
+            EsImportRt(mac) => value(EVPN, ES_IMPORT_RT, mac),
+            RouterMac(mac) => value(EVPN, ROUTER_MAC, mac),
+            McastFlags(flags) => flags.into(),
+            EviRt(evi_rt) => evi_rt.into(),
+            Flowspec(action) => action.into(),
+            Other(v) => v,
+        }
+    }
+}
+
+// Typed value of the other extended community types only when it encodes
+// back to the same value.
+fn lossless<T>(from: &ExtCommunityValue) -> Option<T>
+where
+    T: for<'a> TryFrom<&'a ExtCommunityValue> + Into<ExtCommunityValue> + Clone,
+{
+    let typed = T::try_from(from).ok()?;
+    let encoded: ExtCommunityValue = typed.clone().into();
+    (encoded == *from).then_some(typed)
+}
+
+impl From<&ExtCommunityValue> for ExtCommunityKind {
+    fn from(from: &ExtCommunityValue) -> Self {
+        use ExtCommunityKind::*;
+        let v = &from.val;
+        let u16_at = |i: usize| u16::from_be_bytes([v[i], v[i + 1]]);
+        let u32_at = |i: usize| u32::from_be_bytes([v[i], v[i + 1], v[i + 2], v[i + 3]]);
+        let zero = |range: std::ops::Range<usize>| v[range].iter().all(|b| *b == 0);
+        let kind = match (from.high_type, from.low_type) {
+            (high, ROUTE_TARGET) if high <= TRANS_AS4 => {
+                ExtCommunityAdmin::decode(high, v).map(RouteTarget)
+            }
+            (high, ROUTE_ORIGIN) if high <= TRANS_AS4 => {
+                ExtCommunityAdmin::decode(high, v).map(RouteOrigin)
+            }
+            (high, OSPF_DOMAIN_ID) if high <= TRANS_AS4 => {
+                ExtCommunityAdmin::decode(high, v).map(OspfDomainId)
+            }
+            (TRANS_IPV4, OSPF_ROUTER_ID) if zero(4..6) => {
+                Some(OspfRouterId(Ipv4Addr::from(u32_at(0))))
+            }
+            (TRANS_IPV4, VRF_ROUTE_IMPORT) => Some(VrfRouteImport {
+                addr: Ipv4Addr::from(u32_at(0)),
+                val: u16_at(4),
+            }),
+            (TRANS_AS2, SOURCE_AS) if zero(2..6) => Some(SourceAs(u16_at(0))),
+            (TRANS_AS4, SOURCE_AS) if zero(4..6) => Some(SourceAs4(u32_at(0))),
+            (TRANS_OPAQUE, OSPF_ROUTE_TYPE) => Some(OspfRouteType {
+                area: Ipv4Addr::from(u32_at(0)),
+                route_type: v[4],
+                options: v[5],
+            }),
+            (TRANS_OPAQUE, COLOR) => Some(Color {
+                flags: u16_at(0),
+                color: u32_at(2),
+            }),
+            (TRANS_OPAQUE, ENCAPSULATION) if zero(0..4) => Some(Encapsulation(u16_at(4))),
+            (NON_TRANS_AS2, LINK_BANDWIDTH) => Some(LinkBandwidth {
+                asn: u16_at(0),
+                bandwidth: f32::from_bits(u32_at(2)),
+            }),
+            (EVPN, MAC_MOBILITY) if v[0] & !MAC_MOBILITY_STICKY == 0 && v[1] == 0 => {
+                Some(MacMobility {
+                    sticky: v[0] & MAC_MOBILITY_STICKY != 0,
+                    seq: u32_at(2),
+                })
+            }
+            (EVPN, ESI_LABEL) if v[0] & !ESI_LABEL_SINGLE_ACTIVE == 0 && zero(1..3) => {
+                Some(EsiLabel {
+                    single_active: v[0] & ESI_LABEL_SINGLE_ACTIVE != 0,
+                    label: u32_at(2) & 0xffffff,
+                })
+            }
+            (EVPN, ES_IMPORT_RT) => Some(EsImportRt(*v)),
+            (EVPN, ROUTER_MAC) => Some(RouterMac(*v)),
+            _ => None,
+        };
+        kind.or_else(|| lossless::<EvpnMcastFlags>(from).map(McastFlags))
+            .or_else(|| lossless::<crate::EviRt>(from).map(EviRt))
+            .or_else(|| lossless::<FlowspecAction>(from).map(Flowspec))
+            .unwrap_or_else(|| Other(from.clone()))
+    }
+}
+
+fn fmt_mac(f: &mut fmt::Formatter<'_>, mac: &[u8; 6]) -> fmt::Result {
+    write!(
+        f,
+        "{:02x}:{:02x}:{:02x}:{:02x}:{:02x}:{:02x}",
+        mac[0], mac[1], mac[2], mac[3], mac[4], mac[5]
+    )
+}
+
+fn mac_from_str(s: &str) -> Result<[u8; 6], ()> {
+    let mut mac = [0u8; 6];
+    let octets: Vec<&str> = s.split(':').collect();
+    if octets.len() != 6 {
+        return Err(());
+    }
+    for (i, octet) in octets.iter().enumerate() {
+        mac[i] = u8::from_str_radix(octet, 16).map_err(|_| ())?;
+    }
+    Ok(mac)
+}
+
+fn fmt_tunnel_type(f: &mut fmt::Formatter<'_>, tunnel_type: u16) -> fmt::Result {
+    match TunnelType::try_from(tunnel_type) {
+        Ok(tunnel_type) => write!(f, "{tunnel_type}"),
+        Err(_) => write!(f, "{tunnel_type}"),
+    }
+}
+
+impl fmt::Display for ExtCommunityKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use ExtCommunityKind::*;
+        match self {
+            RouteTarget(admin) => write!(f, "rt {admin}"),
+            RouteOrigin(admin) => write!(f, "soo {admin}"),
+            Encapsulation(tunnel_type) => {
+                write!(f, "encap ")?;
+                fmt_tunnel_type(f, *tunnel_type)
+            }
+            Color { flags, color } => {
+                write!(f, "color {color}")?;
+                if *flags != 0 {
+                    write!(f, " flags 0x{flags:04x}")?;
+                }
+                Ok(())
+            }
+            LinkBandwidth { asn, bandwidth } => write!(f, "bandwidth {asn}:{bandwidth}"),
+            OspfDomainId(admin) => write!(f, "ospf-domain-id {admin}"),
+            OspfRouteType {
+                area,
+                route_type,
+                options,
+            } => write!(f, "ospf-route-type {area}:{route_type}:{options}"),
+            OspfRouterId(addr) => write!(f, "ospf-router-id {addr}"),
+            VrfRouteImport { addr, val } => write!(f, "vrf-import {addr}:{val}"),
+            SourceAs(asn) => write!(f, "source-as {asn}"),
+            SourceAs4(asn) => write!(f, "source-as {asn}"),
+            MacMobility { sticky, seq } => {
+                write!(f, "mac-mobility {seq}")?;
+                if *sticky {
+                    write!(f, " sticky")?;
+                }
+                Ok(())
+            }
+            EsiLabel {
+                single_active,
+                label,
+            } => {
+                write!(f, "esi-label {label}")?;
+                if *single_active {
+                    write!(f, " single-active")?;
+                }
+                Ok(())
+            }
+            EsImportRt(mac) => {
+                write!(f, "es-import-rt ")?;
+                fmt_mac(f, mac)
+            }
+            RouterMac(mac) => {
+                write!(f, "router-mac ")?;
+                fmt_mac(f, mac)
+            }
+            McastFlags(flags) => write!(f, "{flags}"),
+            EviRt(evi_rt) => write!(f, "{evi_rt}"),
+            Flowspec(action) => write!(f, "{action}"),
+            Other(v) => {
+                write!(f, "{} ", ExtCommunitySubType::display(v.low_type))?;
+                // Value is decoded by the high type regardless of the
+                // transitive bit except opaque.
+                let high_type = v.high_type & !NON_TRANSITIVE;
+                if let Some(admin) = ExtCommunityAdmin::decode(high_type, &v.val) {
+                    write!(f, "{admin}")
+                } else if v.high_type == TRANS_OPAQUE {
+                    fmt_tunnel_type(f, u16::from_be_bytes([v.val[4], v.val[5]]))
+                } else {
+                    write!(f, "0x")?;
+                    for b in v.val.iter() {
+                        write!(f, "{b:02x}")?;
+                    }
+                    Ok(())
+                }
+            }
+        }
+    }
+}
+
+// Same format as Display, for example "rt 65000:100", "encap VXLAN",
+// "color 100" and "router-mac 00:00:5e:00:53:01". Unknown value can't be
+// parsed.
+impl FromStr for ExtCommunityKind {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use ExtCommunityKind::*;
+        let mut tokens = s.split_whitespace();
+        let keyword = tokens.next().ok_or(())?;
+        let args: Vec<&str> = tokens.collect();
+        let arg = || -> Result<&str, ()> {
+            match args.as_slice() {
+                [arg] => Ok(*arg),
+                _ => Err(()),
+            }
+        };
+        // First argument followed by an optional flag keyword.
+        let arg_flag = |flag: &str| -> Result<(&str, bool), ()> {
+            match args.as_slice() {
+                [arg] => Ok((*arg, false)),
+                [arg, v] if *v == flag => Ok((*arg, true)),
+                _ => Err(()),
+            }
+        };
+        let kind = match keyword {
+            "rt" => RouteTarget(arg()?.parse()?),
+            "soo" => RouteOrigin(arg()?.parse()?),
+            "encap" => {
+                let arg = arg()?;
+                match TunnelType::from_str(arg) {
+                    Ok(tunnel_type) => Encapsulation(tunnel_type.into()),
+                    Err(_) => Encapsulation(arg.parse().map_err(|_| ())?),
+                }
+            }
+            "color" => {
+                let (color, flags) = match args.as_slice() {
+                    [color] => (*color, 0),
+                    [color, "flags", flags] => {
+                        let flags = flags.strip_prefix("0x").ok_or(())?;
+                        (*color, u16::from_str_radix(flags, 16).map_err(|_| ())?)
+                    }
+                    _ => return Err(()),
+                };
+                let color = color.parse().map_err(|_| ())?;
+                Color { flags, color }
+            }
+            "bandwidth" => {
+                let (asn, bandwidth) = arg()?.split_once(':').ok_or(())?;
+                LinkBandwidth {
+                    asn: asn.parse().map_err(|_| ())?,
+                    bandwidth: bandwidth.parse().map_err(|_| ())?,
+                }
+            }
+            "ospf-domain-id" => OspfDomainId(arg()?.parse()?),
+            "ospf-route-type" => {
+                let fields: Vec<&str> = arg()?.split(':').collect();
+                let [area, route_type, options] = fields.as_slice() else {
+                    return Err(());
+                };
+                OspfRouteType {
+                    area: area.parse().map_err(|_| ())?,
+                    route_type: route_type.parse().map_err(|_| ())?,
+                    options: options.parse().map_err(|_| ())?,
+                }
+            }
+            "ospf-router-id" => OspfRouterId(arg()?.parse().map_err(|_| ())?),
+            "vrf-import" => {
+                let (addr, val) = arg()?.split_once(':').ok_or(())?;
+                VrfRouteImport {
+                    addr: addr.parse().map_err(|_| ())?,
+                    val: val.parse().map_err(|_| ())?,
+                }
+            }
+            "source-as" => {
+                let asn = arg()?.parse::<u32>().map_err(|_| ())?;
+                match u16::try_from(asn) {
+                    Ok(asn) => SourceAs(asn),
+                    Err(_) => SourceAs4(asn),
+                }
+            }
+            "mac-mobility" => {
+                let (seq, sticky) = arg_flag("sticky")?;
+                MacMobility {
+                    sticky,
+                    seq: seq.parse().map_err(|_| ())?,
+                }
+            }
+            "esi-label" => {
+                let (label, single_active) = arg_flag("single-active")?;
+                let label = label.parse::<u32>().map_err(|_| ())?;
+                if label > 0xffffff {
+                    return Err(());
+                }
+                EsiLabel {
+                    single_active,
+                    label,
+                }
+            }
+            "es-import-rt" => EsImportRt(mac_from_str(arg()?)?),
+            "router-mac" => RouterMac(mac_from_str(arg()?)?),
+            "mcast-flags" => {
+                let mut flags = EvpnMcastFlags::default();
+                for arg in args.iter() {
+                    match *arg {
+                        "igmp-proxy" => flags.igmp_proxy = true,
+                        "mld-proxy" => flags.mld_proxy = true,
+                        _ => return Err(()),
+                    }
+                }
+                McastFlags(flags)
+            }
+            "evi-rt" => EviRt(crate::EviRt(arg()?.parse()?)),
+            _ => Flowspec(s.parse()?),
+        };
+        Ok(kind)
+    }
+}
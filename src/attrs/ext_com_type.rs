@@ -1,12 +1,19 @@
 use num_enum::{IntoPrimitive, TryFromPrimitive};
 use strum_macros::{Display, EnumString};
 
+// Extended community high-order type octet (RFC7153 5.1).
 #[repr(u8)]
 pub enum ExtCommunityType {
     TransTwoOctetAS = 0x00,
-    // TransIpv4Addr = 0x01,
-    // TransFourOctetAS = 0x03,
+    TransIpv4Addr = 0x01,
+    TransFourOctetAS = 0x02,
     TransOpaque = 0x03,
+    Evpn = 0x06,
+    FlowspecRedirectIp = 0x08,
+    NonTransTwoOctetAS = 0x40,
+    Experimental = 0x80,
+    ExperimentalIpv4 = 0x81,
+    ExperimentalAs4 = 0x82,
 }
 
 #[derive(TryFromPrimitive, IntoPrimitive, EnumString, Display)]
@@ -16,7 +23,7 @@ pub enum ExtCommunitySubType {
     RouteTarget = 0x02,
     #[strum(serialize = "soo")]
     RouteOrigin = 0x03,
-    #[strum(serialize = "opqque")]
+    #[strum(serialize = "opaque")]
     Opaque = 0x0c,
 }
 
@@ -1,10 +1,10 @@
 use bytes::{BufMut, BytesMut};
 use nom_derive::NomBE;
 use std::fmt;
-use std::net::{Ipv4Addr, Ipv6Addr};
+use std::net::Ipv6Addr;
 use std::str::FromStr;
 
-use crate::ExtCommunitySubType;
+use crate::{AttrEmitter, AttrFlags, AttrType, ExtCommunitySubType};
 
 use super::ext_ipv6_com_token::{Token, tokenizer};
 
@@ -43,17 +43,17 @@ fn sub_type_str(sub_type: u8) -> &'static str {
     }
 }
 
+// RFC5701 3: Global administrator is IPv6 address and local administrator
+// is 2 octets number for both transitive and non-transitive type.
 impl fmt::Display for ExtIpv6CommunityValue {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        if self.high_type == 0 {
-            let asn = u16::from_be_bytes([self.val[0], self.val[1]]);
-            let val = u32::from_be_bytes([self.val[2], self.val[3], self.val[4], self.val[5]]);
-            write!(f, "{} {asn}:{val}", sub_type_str(self.low_type))
-        } else {
-            let ip = Ipv4Addr::new(self.val[0], self.val[1], self.val[2], self.val[3]);
-            let val = u16::from_be_bytes([self.val[4], self.val[5]]);
-            write!(f, "{} {ip}:{val}", sub_type_str(self.low_type))
-        }
+        write!(
+            f,
+            "{} [{}]:{}",
+            sub_type_str(self.low_type),
+            self.global_admin(),
+            self.local_admin()
+        )
     }
 }
 
@@ -65,9 +65,56 @@ impl ExtIpv6CommunityValue {
             val: [0u8; 18],
         };
         com.val[0..16].copy_from_slice(&addr.octets());
-        com.val[16..18].copy_from_slice(val.to_ne_bytes().as_slice());
+        com.val[16..18].copy_from_slice(&val.to_be_bytes());
         com
     }
+
+    pub fn global_admin(&self) -> Ipv6Addr {
+        let mut addr = [0u8; 16];
+        addr.copy_from_slice(&self.val[0..16]);
+        Ipv6Addr::from(addr)
+    }
+
+    pub fn local_admin(&self) -> u16 {
+        u16::from_be_bytes([self.val[16], self.val[17]])
+    }
+
+    pub fn is_route_target(&self) -> bool {
+        self.low_type == RouteTarget as u8
+    }
+}
+
+impl ExtIpv6Community {
+    // Route targets for import and export of IPv6-only VPN.
+    pub fn route_targets(&self) -> Vec<&ExtIpv6CommunityValue> {
+        self.0.iter().filter(|v| v.is_route_target()).collect()
+    }
+
+    pub fn has_route_target(&self, rt: &ExtIpv6CommunityValue) -> bool {
+        self.route_targets()
+            .iter()
+            .any(|v| v.global_admin() == rt.global_admin() && v.local_admin() == rt.local_admin())
+    }
+}
+
+impl AttrEmitter for ExtIpv6Community {
+    fn attr_flags(&self) -> AttrFlags {
+        AttrFlags::new().with_optional(true).with_transitive(true)
+    }
+
+    fn attr_type(&self) -> AttrType {
+        AttrType::ExtendedIpv6Com
+    }
+
+    fn len(&self) -> Option<usize> {
+        Some(self.0.len() * 20)
+    }
+
+    fn emit(&self, buf: &mut BytesMut) {
+        for ext_community in self.0.iter() {
+            ext_community.encode(buf);
+        }
+    }
 }
 
 impl fmt::Display for ExtIpv6Community {
@@ -87,7 +134,7 @@ impl FromStr for ExtIpv6Community {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut ecom = ExtIpv6Community::default();
-        let tokens = tokenizer(String::from(s)).unwrap();
+        let tokens = tokenizer(String::from(s)).map_err(|_| ())?;
         let mut state = State::Unspec;
 
         for token in tokens.into_iter() {
@@ -118,3 +165,29 @@ impl FromStr for ExtIpv6Community {
         Ok(ecom)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let ecom = ExtIpv6Community::from_str("rt [2001:db8::1]:10 soo 2001:db8::2:20").unwrap();
+        assert_eq!(ecom.to_string(), "rt [2001:db8::1]:10 soo [2001:db8::2]:20");
+
+        let mut buf = BytesMut::new();
+        ecom.0[0].encode(&mut buf);
+        assert_eq!(
+            &buf[..],
+            &[
+                0x00, 0x02, 0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x01, 0x00,
+                0x0a
+            ]
+        );
+
+        let rt = ExtIpv6CommunityValue::new("2001:db8::1".parse().unwrap(), 10);
+        assert_eq!(ecom.route_targets().len(), 1);
+        assert!(ecom.has_route_target(&rt));
+        assert!(ExtIpv6Community::from_str("rt 10").is_err());
+    }
+}
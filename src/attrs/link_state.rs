@@ -0,0 +1,363 @@
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use bytes::{BufMut, BytesMut};
+
+use super::length_error;
+use crate::{
+    AttrEmitter, AttrFlags, AttrType, LsTlv, ParseBe, emit_ls_tlv, ls_fixed, parse_ls_tlv,
+};
+
+// Node attribute TLVs (RFC9552 5.3.1).
+const NODE_FLAGS: u16 = 1024;
+const NODE_NAME: u16 = 1026;
+const ISIS_AREA_ID: u16 = 1027;
+const LOCAL_ROUTER_ID_V4: u16 = 1028;
+const LOCAL_ROUTER_ID_V6: u16 = 1029;
+
+// Link attribute TLVs (RFC9552 5.3.2).
+const REMOTE_ROUTER_ID_V4: u16 = 1030;
+const REMOTE_ROUTER_ID_V6: u16 = 1031;
+const ADMIN_GROUP: u16 = 1088;
+const MAX_LINK_BW: u16 = 1089;
+const MAX_RESV_BW: u16 = 1090;
+const UNRESV_BW: u16 = 1091;
+const TE_METRIC: u16 = 1092;
+const LINK_PROTECTION: u16 = 1093;
+const MPLS_PROTOCOL: u16 = 1094;
+const IGP_METRIC: u16 = 1095;
+const SRLG: u16 = 1096;
+const LINK_NAME: u16 = 1098;
+
+// Prefix attribute TLVs (RFC9552 5.3.3).
+const IGP_FLAGS: u16 = 1152;
+const ROUTE_TAG: u16 = 1153;
+const EXT_ROUTE_TAG: u16 = 1154;
+const PREFIX_METRIC: u16 = 1155;
+const OSPF_FWD_ADDR: u16 = 1156;
+
+// BGP-LS Attribute TLV. Bandwidth is IEEE floating point in bytes per
+// second.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LsAttrTlv {
+    NodeFlags(u8),
+    NodeName(String),
+    IsisAreaId(Vec<u8>),
+    LocalRouterIdV4(Ipv4Addr),
+    LocalRouterIdV6(Ipv6Addr),
+    RemoteRouterIdV4(Ipv4Addr),
+    RemoteRouterIdV6(Ipv6Addr),
+    AdminGroup(u32),
+    MaxLinkBandwidth(f32),
+    MaxReservableBandwidth(f32),
+    UnreservedBandwidth([f32; 8]),
+    TeDefaultMetric(u32),
+    LinkProtection(u16),
+    MplsProtocolMask(u8),
+    // IGP metric is 1 to 3 octets depending on the protocol.
+    IgpMetric { len: u8, metric: u32 },
+    Srlg(Vec<u32>),
+    LinkName(String),
+    IgpFlags(u8),
+    RouteTag(Vec<u32>),
+    ExtRouteTag(Vec<u64>),
+    PrefixMetric(u32),
+    OspfForwardingAddr(IpAddr),
+    Unknown(LsTlv),
+}
+
+type TlvResult<'a, T> = Result<T, nom::Err<nom::error::Error<&'a [u8]>>>;
+
+// List of `N` octets values.
+fn parse_list<const N: usize>(value: &[u8]) -> TlvResult<'_, Vec<[u8; N]>> {
+    if value.is_empty() || !value.len().is_multiple_of(N) {
+        return Err(length_error(value));
+    }
+    value.chunks(N).map(ls_fixed).collect()
+}
+
+impl LsAttrTlv {
+    fn parse_value(typ: u16, v: &[u8]) -> TlvResult<'_, Self> {
+        use LsAttrTlv::*;
+        let u32_value = |v| ls_fixed::<4>(v).map(u32::from_be_bytes);
+        let f32_value = |v| ls_fixed::<4>(v).map(f32::from_be_bytes);
+        let tlv = match typ {
+            NODE_FLAGS => NodeFlags(ls_fixed::<1>(v)?[0]),
+            NODE_NAME | LINK_NAME => {
+                // Name which is not UTF-8 is kept as is.
+                let Ok(name) = String::from_utf8(v.to_vec()) else {
+                    return Ok(Unknown(LsTlv::new(typ, v)));
+                };
+                if typ == NODE_NAME {
+                    NodeName(name)
+                } else {
+                    LinkName(name)
+                }
+            }
+            ISIS_AREA_ID => IsisAreaId(v.to_vec()),
+            LOCAL_ROUTER_ID_V4 => LocalRouterIdV4(Ipv4Addr::from(ls_fixed::<4>(v)?)),
+            LOCAL_ROUTER_ID_V6 => LocalRouterIdV6(Ipv6Addr::from(ls_fixed::<16>(v)?)),
+            REMOTE_ROUTER_ID_V4 => RemoteRouterIdV4(Ipv4Addr::from(ls_fixed::<4>(v)?)),
+            REMOTE_ROUTER_ID_V6 => RemoteRouterIdV6(Ipv6Addr::from(ls_fixed::<16>(v)?)),
+            ADMIN_GROUP => AdminGroup(u32_value(v)?),
+            MAX_LINK_BW => MaxLinkBandwidth(f32_value(v)?),
+            MAX_RESV_BW => MaxReservableBandwidth(f32_value(v)?),
+            UNRESV_BW => {
+                let list = parse_list::<4>(v)?;
+                let Ok(bw) = <[f32; 8]>::try_from(
+                    list.into_iter()
+                        .map(f32::from_be_bytes)
+                        .collect::<Vec<f32>>(),
+                ) else {
+                    return Err(length_error(v));
+                };
+                UnreservedBandwidth(bw)
+            }
+            TE_METRIC => TeDefaultMetric(u32_value(v)?),
+            LINK_PROTECTION => LinkProtection(u16::from_be_bytes(ls_fixed(v)?)),
+            MPLS_PROTOCOL => MplsProtocolMask(ls_fixed::<1>(v)?[0]),
+            IGP_METRIC => {
+                if v.is_empty() || v.len() > 3 {
+                    return Err(length_error(v));
+                }
+                let metric = v.iter().fold(0u32, |acc, v| (acc << 8) | *v as u32);
+                IgpMetric {
+                    len: v.len() as u8,
+                    metric,
+                }
+            }
+            SRLG => Srlg(
+                parse_list::<4>(v)?
+                    .into_iter()
+                    .map(u32::from_be_bytes)
+                    .collect(),
+            ),
+            IGP_FLAGS => IgpFlags(ls_fixed::<1>(v)?[0]),
+            ROUTE_TAG => RouteTag(
+                parse_list::<4>(v)?
+                    .into_iter()
+                    .map(u32::from_be_bytes)
+                    .collect(),
+            ),
+            EXT_ROUTE_TAG => ExtRouteTag(
+                parse_list::<8>(v)?
+                    .into_iter()
+                    .map(u64::from_be_bytes)
+                    .collect(),
+            ),
+            PREFIX_METRIC => PrefixMetric(u32_value(v)?),
+            OSPF_FWD_ADDR => match v.len() {
+                4 => OspfForwardingAddr(IpAddr::V4(Ipv4Addr::from(ls_fixed::<4>(v)?))),
+                16 => OspfForwardingAddr(IpAddr::V6(Ipv6Addr::from(ls_fixed::<16>(v)?))),
+                _ => return Err(length_error(v)),
+            },
+            _ => Unknown(LsTlv::new(typ, v)),
+        };
+        Ok(tlv)
+    }
+
+    pub fn typ(&self) -> u16 {
+        use LsAttrTlv::*;
+        match self {
+            NodeFlags(_) => NODE_FLAGS,
+            NodeName(_) => NODE_NAME,
+            IsisAreaId(_) => ISIS_AREA_ID,
+            LocalRouterIdV4(_) => LOCAL_ROUTER_ID_V4,
+            LocalRouterIdV6(_) => LOCAL_ROUTER_ID_V6,
+            RemoteRouterIdV4(_) => REMOTE_ROUTER_ID_V4,
+            RemoteRouterIdV6(_) => REMOTE_ROUTER_ID_V6,
+            AdminGroup(_) => ADMIN_GROUP,
+            MaxLinkBandwidth(_) => MAX_LINK_BW,
+            MaxReservableBandwidth(_) => MAX_RESV_BW,
+            UnreservedBandwidth(_) => UNRESV_BW,
+            TeDefaultMetric(_) => TE_METRIC,
+            LinkProtection(_) => LINK_PROTECTION,
+            MplsProtocolMask(_) => MPLS_PROTOCOL,
+            IgpMetric { .. } => IGP_METRIC,
+            Srlg(_) => SRLG,
+            LinkName(_) => LINK_NAME,
+            IgpFlags(_) => IGP_FLAGS,
+            RouteTag(_) => ROUTE_TAG,
+            ExtRouteTag(_) => EXT_ROUTE_TAG,
+            PrefixMetric(_) => PREFIX_METRIC,
+            OspfForwardingAddr(_) => OSPF_FWD_ADDR,
+            Unknown(tlv) => tlv.typ,
+        }
+    }
+
+    fn emit_value(&self, buf: &mut BytesMut) {
+        use LsAttrTlv::*;
+        match self {
+            NodeFlags(v) | MplsProtocolMask(v) | IgpFlags(v) => buf.put_u8(*v),
+            NodeName(v) | LinkName(v) => buf.put(v.as_bytes()),
+            IsisAreaId(v) => buf.put(&v[..]),
+            LocalRouterIdV4(v) | RemoteRouterIdV4(v) => buf.put(&v.octets()[..]),
+            LocalRouterIdV6(v) | RemoteRouterIdV6(v) => buf.put(&v.octets()[..]),
+            AdminGroup(v) | TeDefaultMetric(v) | PrefixMetric(v) => buf.put_u32(*v),
+            MaxLinkBandwidth(v) | MaxReservableBandwidth(v) => buf.put_f32(*v),
+            UnreservedBandwidth(v) => {
+                for bw in v.iter() {
+                    buf.put_f32(*bw);
+                }
+            }
+            LinkProtection(v) => buf.put_u16(*v),
+            IgpMetric { len, metric } => {
+                // Length is limited to 1 to 3 octets.
+                let len = (*len as usize).clamp(1, 3);
+                buf.put(&metric.to_be_bytes()[4 - len..]);
+            }
+            Srlg(v) | RouteTag(v) => {
+                for val in v.iter() {
+                    buf.put_u32(*val);
+                }
+            }
+            ExtRouteTag(v) => {
+                for val in v.iter() {
+                    buf.put_u64(*val);
+                }
+            }
+            OspfForwardingAddr(IpAddr::V4(v)) => buf.put(&v.octets()[..]),
+            OspfForwardingAddr(IpAddr::V6(v)) => buf.put(&v.octets()[..]),
+            Unknown(tlv) => buf.put(&tlv.value[..]),
+        }
+    }
+
+    pub fn emit(&self, buf: &mut BytesMut) {
+        emit_ls_tlv(buf, self.typ(), |buf| self.emit_value(buf));
+    }
+}
+
+fn fmt_list<T: fmt::Display>(f: &mut fmt::Formatter<'_>, name: &str, v: &[T]) -> fmt::Result {
+    write!(f, "{name}")?;
+    for val in v.iter() {
+        write!(f, " {val}")?;
+    }
+    Ok(())
+}
+
+impl fmt::Display for LsAttrTlv {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use LsAttrTlv::*;
+        match self {
+            NodeFlags(v) => write!(f, "node-flags 0x{v:02x}"),
+            NodeName(v) => write!(f, "node-name {v}"),
+            IsisAreaId(v) => {
+                write!(f, "area-id ")?;
+                for val in v.iter() {
+                    write!(f, "{val:02x}")?;
+                }
+                Ok(())
+            }
+            LocalRouterIdV4(v) => write!(f, "local-router-id {v}"),
+            LocalRouterIdV6(v) => write!(f, "local-router-id {v}"),
+            RemoteRouterIdV4(v) => write!(f, "remote-router-id {v}"),
+            RemoteRouterIdV6(v) => write!(f, "remote-router-id {v}"),
+            AdminGroup(v) => write!(f, "admin-group 0x{v:08x}"),
+            MaxLinkBandwidth(v) => write!(f, "max-bw {v}"),
+            MaxReservableBandwidth(v) => write!(f, "max-resv-bw {v}"),
+            UnreservedBandwidth(v) => fmt_list(f, "unresv-bw", v),
+            TeDefaultMetric(v) => write!(f, "te-metric {v}"),
+            LinkProtection(v) => write!(f, "link-protection 0x{v:04x}"),
+            MplsProtocolMask(v) => write!(f, "mpls-protocol 0x{v:02x}"),
+            IgpMetric { metric, .. } => write!(f, "igp-metric {metric}"),
+            Srlg(v) => fmt_list(f, "srlg", v),
+            LinkName(v) => write!(f, "link-name {v}"),
+            IgpFlags(v) => write!(f, "igp-flags 0x{v:02x}"),
+            RouteTag(v) => fmt_list(f, "route-tag", v),
+            ExtRouteTag(v) => fmt_list(f, "ext-route-tag", v),
+            PrefixMetric(v) => write!(f, "prefix-metric {v}"),
+            OspfForwardingAddr(v) => write!(f, "forwarding-addr {v}"),
+            Unknown(tlv) => write!(f, "{tlv}"),
+        }
+    }
+}
+
+// BGP-LS Attribute (RFC9552 5.3).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LinkStateAttr {
+    pub tlvs: Vec<LsAttrTlv>,
+}
+
+impl ParseBe<LinkStateAttr> for LinkStateAttr {
+    fn parse_be(input: &[u8]) -> nom::IResult<&[u8], LinkStateAttr> {
+        let mut input = input;
+        let mut attr = LinkStateAttr::default();
+        while !input.is_empty() {
+            let (remaining, (typ, value)) = parse_ls_tlv(input)?;
+            attr.tlvs.push(LsAttrTlv::parse_value(typ, value)?);
+            input = remaining;
+        }
+        Ok((input, attr))
+    }
+}
+
+impl AttrEmitter for LinkStateAttr {
+    fn attr_flags(&self) -> AttrFlags {
+        AttrFlags::new().with_optional(true)
+    }
+
+    fn attr_type(&self) -> AttrType {
+        AttrType::LinkState
+    }
+
+    fn len(&self) -> Option<usize> {
+        None
+    }
+
+    fn emit(&self, buf: &mut BytesMut) {
+        for tlv in self.tlvs.iter() {
+            tlv.emit(buf);
+        }
+    }
+}
+
+impl fmt::Display for LinkStateAttr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, tlv) in self.tlvs.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{tlv}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+
+    #[test]
+    fn link_state_attr() {
+        let input = hex!(
+            "0402 0002 7231"
+            "0404 0004 0a000001"
+            "0441 0004 4cbebc20"
+            "0447 0003 00000a"
+            "0448 0008 00000001 00000002"
+            "04ff 0002 0102"
+        );
+        let (rest, attr) = LinkStateAttr::parse_be(&input).unwrap();
+        assert!(rest.is_empty());
+        assert_eq!(attr.tlvs[0], LsAttrTlv::NodeName("r1".to_string()));
+        assert_eq!(attr.tlvs[2], LsAttrTlv::MaxLinkBandwidth(1.0e8));
+        assert_eq!(
+            attr.to_string(),
+            "node-name r1, local-router-id 10.0.0.1, max-bw 100000000, igp-metric 10, \
+             srlg 1 2, tlv 1279 0x0102"
+        );
+        let mut buf = BytesMut::new();
+        attr.emit(&mut buf);
+        assert_eq!(&buf[..], &input[..]);
+
+        // IGP metric longer than 3 octets.
+        assert!(LinkStateAttr::parse_be(&hex!("0447 0004 0000000a")).is_err());
+        let attr = LinkStateAttr {
+            tlvs: vec![LsAttrTlv::IgpMetric { len: 8, metric: 10 }],
+        };
+        let mut buf = BytesMut::new();
+        attr.emit(&mut buf);
+        assert_eq!(&buf[..], &hex!("0447 0003 00000a"));
+    }
+}
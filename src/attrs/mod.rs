@@ -1,6 +1,9 @@
 pub mod attr;
 pub use attr::*;
 
+pub mod attr_spec;
+pub use attr_spec::*;
+
 pub mod flags;
 pub use flags::*;
 
@@ -39,6 +42,15 @@ pub mod ext_com;
 pub use ext_com::*;
 pub mod ext_com_token;
 
+pub mod ext_com_evpn;
+pub use ext_com_evpn::*;
+
+pub mod ext_com_flowspec;
+pub use ext_com_flowspec::*;
+
+pub mod ext_com_kind;
+pub use ext_com_kind::*;
+
 pub mod ext_ipv6_com;
 pub use ext_ipv6_com::*;
 pub mod ext_ipv6_com_token;
@@ -55,12 +67,27 @@ pub use rd::*;
 pub mod aigp;
 pub use aigp::*;
 
+pub mod link_state;
+pub use link_state::*;
+
+pub mod otc;
+pub use otc::*;
+
+pub mod unknown_attr;
+pub use unknown_attr::*;
+
 pub mod emitter;
 pub use emitter::*;
 
 pub mod pmsi_tunnel;
 pub use pmsi_tunnel::*;
 
+pub mod tunnel_encap;
+pub use tunnel_encap::*;
+
+pub mod prefix_sid;
+pub use prefix_sid::*;
+
 pub mod mp_reach;
 pub use mp_reach::*;
 
@@ -73,11 +100,37 @@ pub use nlri_ipv4::*;
 pub mod nlri_ipv6;
 pub use nlri_ipv6::*;
 
+pub mod nlri_labeled;
+pub use nlri_labeled::*;
+
 pub mod nlri_vpnv4;
 pub use nlri_vpnv4::*;
 
+pub mod nlri_vpnv6;
+pub use nlri_vpnv6::*;
+
+pub mod esi;
+pub use esi::*;
+
 pub mod nlri_evpn;
 pub use nlri_evpn::*;
 
+pub mod nlri_flowspec;
+pub use nlri_flowspec::*;
+
+pub mod nlri_ls;
+pub use nlri_ls::*;
+
+pub mod nlri_srpolicy;
+pub use nlri_srpolicy::*;
+
 pub mod nlri_rtcv4;
 pub use nlri_rtcv4::*;
+
+// Error for the value which has invalid length.
+pub(crate) fn length_error(input: &[u8]) -> nom::Err<nom::error::Error<&[u8]>> {
+    nom::Err::Error(nom::error::make_error(
+        input,
+        nom::error::ErrorKind::LengthValue,
+    ))
+}
@@ -1,17 +1,19 @@
 use std::fmt;
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 
-use bytes::BytesMut;
+use bytes::{BufMut, BytesMut};
+use nom::bytes::complete::take;
 use nom::error::{ErrorKind, make_error};
 use nom::number::complete::{be_u8, be_u32, be_u128};
 use nom_derive::*;
 
 use crate::{
-    Afi, EvpnRoute, Ipv4Nlri, Ipv6Nlri, ParseBe, ParseNlri, ParseOption, Rtcv4, Safi, Vpnv4Nexthop,
-    Vpnv4Nlri, many0,
+    Afi, AttrFlags, AttrType, EmitNlri, EvpnRoute, FlowspecNlri, FlowspecVpnNlri, Ipv4Nlri,
+    Ipv6Nlri, LabeledIpv4Nlri, LabeledIpv6Nlri, LsNlri, ParseBe, ParseNlri, ParseOption, Rtcv4,
+    Safi, SrPolicyNlri, Vpnv4Nexthop, Vpnv4Nlri, Vpnv6Nexthop, Vpnv6Nlri, many0,
 };
 
-use super::{AttrEmitter, RouteDistinguisher, Vpnv4Reach};
+use super::{AttrEmitter, RouteDistinguisher, Vpnv4Reach, Vpnv6Reach};
 
 #[derive(Clone, Debug, NomBE)]
 pub struct MpNlriReachHeader {
@@ -25,21 +27,37 @@ pub enum MpNlriReachAttr {
     Ipv4 {
         snpa: u8,
         nhop: IpAddr,
+        link_local: Option<Ipv6Addr>,
         updates: Vec<Ipv4Nlri>,
     },
     Ipv6 {
         snpa: u8,
         nhop: IpAddr,
+        link_local: Option<Ipv6Addr>,
         updates: Vec<Ipv6Nlri>,
     },
+    Ipv4Labeled {
+        snpa: u8,
+        nhop: IpAddr,
+        link_local: Option<Ipv6Addr>,
+        updates: Vec<LabeledIpv4Nlri>,
+    },
+    Ipv6Labeled {
+        snpa: u8,
+        nhop: IpAddr,
+        link_local: Option<Ipv6Addr>,
+        updates: Vec<LabeledIpv6Nlri>,
+    },
     Vpnv4 {
         snpa: u8,
         nhop: Vpnv4Nexthop,
         updates: Vec<Vpnv4Nlri>,
     },
-    // Vpnv6 {
-    //     //
-    // },
+    Vpnv6 {
+        snpa: u8,
+        nhop: Vpnv6Nexthop,
+        updates: Vec<Vpnv6Nlri>,
+    },
     Evpn {
         snpa: u8,
         nhop: IpAddr,
@@ -50,12 +68,167 @@ pub enum MpNlriReachAttr {
         nhop: IpAddr,
         updates: Vec<Rtcv4>,
     },
+    LinkState {
+        snpa: u8,
+        nhop: IpAddr,
+        updates: Vec<LsNlri>,
+    },
+    Flowspec {
+        afi: Afi,
+        snpa: u8,
+        nhop: Option<IpAddr>,
+        updates: Vec<FlowspecNlri>,
+    },
+    FlowspecVpn {
+        afi: Afi,
+        snpa: u8,
+        nhop: Option<IpAddr>,
+        updates: Vec<FlowspecVpnNlri>,
+    },
+    SrPolicy {
+        afi: Afi,
+        snpa: u8,
+        nhop: IpAddr,
+        updates: Vec<SrPolicyNlri>,
+    },
+}
+
+// MP_REACH_NLRI for the AFI/SAFI with encoded next hop.
+struct MpReach<'a, T: EmitNlri> {
+    afi: Afi,
+    safi: Safi,
+    nhop: BytesMut,
+    updates: &'a [T],
+}
+
+impl<'a, T: EmitNlri> MpReach<'a, T> {
+    fn new(afi: Afi, safi: Safi, nhop: &IpAddr, updates: &'a [T]) -> Self {
+        let mut buf = BytesMut::new();
+        match nhop {
+            IpAddr::V4(addr) => buf.put(&addr.octets()[..]),
+            IpAddr::V6(addr) => buf.put(&addr.octets()[..]),
+        }
+        Self {
+            afi,
+            safi,
+            nhop: buf,
+            updates,
+        }
+    }
+
+    // Zero length next hop (RFC8955 4).
+    fn without_nexthop(afi: Afi, safi: Safi, updates: &'a [T]) -> Self {
+        Self {
+            afi,
+            safi,
+            nhop: BytesMut::new(),
+            updates,
+        }
+    }
+
+    // IPv6 link-local address follows the global address. It is not encoded
+    // with IPv4 next hop since 20 octets next hop is invalid (RFC2545 3).
+    fn link_local(mut self, link_local: &Option<Ipv6Addr>) -> Self {
+        if let Some(addr) = link_local
+            && self.nhop.len() == 16
+        {
+            self.nhop.put(&addr.octets()[..]);
+        }
+        self
+    }
+}
+
+impl<T: EmitNlri> AttrEmitter for MpReach<'_, T> {
+    fn attr_type(&self) -> AttrType {
+        AttrType::MpReachNlri
+    }
+
+    fn attr_flags(&self) -> AttrFlags {
+        AttrFlags::new().with_optional(true)
+    }
+
+    fn len(&self) -> Option<usize> {
+        None
+    }
+
+    fn emit(&self, buf: &mut BytesMut) {
+        buf.put_u16(u16::from(self.afi));
+        buf.put_u8(u8::from(self.safi));
+        buf.put_u8(self.nhop.len() as u8);
+        buf.put(&self.nhop[..]);
+        // Reserved.
+        buf.put_u8(0);
+        for update in self.updates.iter() {
+            update.emit_nlri(buf);
+        }
+    }
 }
 
 impl MpNlriReachAttr {
+    // Limit labels of labeled unicast NLRI to the number which the peer can
+    // receive.
+    pub fn truncate_labels(&mut self, opt: &ParseOption) {
+        match self {
+            MpNlriReachAttr::Ipv4Labeled { updates, .. } => {
+                let count = opt.label_send_count(Afi::Ip, Safi::MplsLabel);
+                for update in updates.iter_mut() {
+                    update.truncate_labels(count);
+                }
+            }
+            MpNlriReachAttr::Ipv6Labeled { updates, .. } => {
+                let count = opt.label_send_count(Afi::Ip6, Safi::MplsLabel);
+                for update in updates.iter_mut() {
+                    update.truncate_labels(count);
+                }
+            }
+            _ => {}
+        }
+    }
+
     pub fn attr_emit(&self, buf: &mut BytesMut) {
+        use MpNlriReachAttr::*;
         match self {
-            MpNlriReachAttr::Vpnv4 {
+            Ipv4 {
+                nhop,
+                link_local,
+                updates,
+                ..
+            } => {
+                MpReach::new(Afi::Ip, Safi::Unicast, nhop, updates)
+                    .link_local(link_local)
+                    .attr_emit(buf);
+            }
+            Ipv6 {
+                nhop,
+                link_local,
+                updates,
+                ..
+            } => {
+                MpReach::new(Afi::Ip6, Safi::Unicast, nhop, updates)
+                    .link_local(link_local)
+                    .attr_emit(buf);
+            }
+            Ipv4Labeled {
+                nhop,
+                link_local,
+                updates,
+                ..
+            } => {
+                MpReach::new(Afi::Ip, Safi::MplsLabel, nhop, updates)
+                    .link_local(link_local)
+                    .attr_emit(buf);
+            }
+            Ipv6Labeled {
+                nhop,
+                link_local,
+                updates,
+                ..
+            } => {
+                MpReach::new(Afi::Ip6, Safi::MplsLabel, nhop, updates)
+                    .link_local(link_local)
+                    .attr_emit(buf);
+            }
+            Vpnv4 {
                 snpa,
                 nhop,
                 updates,
@@ -67,29 +240,162 @@ impl MpNlriReachAttr {
                 };
                 attr.attr_emit(buf);
             }
-            _ => {
-                //
+            Vpnv6 {
+                snpa,
+                nhop,
+                updates,
+            } => {
+                let attr = Vpnv6Reach {
+                    snpa: *snpa,
+                    nhop: nhop.clone(),
+                    updates: updates.clone(),
+                };
+                attr.attr_emit(buf);
+            }
+            Evpn { nhop, updates, .. } => {
+                MpReach::new(Afi::L2vpn, Safi::Evpn, nhop, updates).attr_emit(buf);
+            }
+            Rtcv4 { nhop, updates, .. } => {
+                MpReach::new(Afi::Ip, Safi::Rtc, nhop, updates).attr_emit(buf);
+            }
+            LinkState { nhop, updates, .. } => {
+                MpReach::new(Afi::LinkState, Safi::LinkState, nhop, updates).attr_emit(buf);
+            }
+            Flowspec {
+                afi, nhop, updates, ..
+            } => match nhop {
+                Some(nhop) => MpReach::new(*afi, Safi::Flowspec, nhop, updates).attr_emit(buf),
+                None => MpReach::without_nexthop(*afi, Safi::Flowspec, updates).attr_emit(buf),
+            },
+            FlowspecVpn {
+                afi, nhop, updates, ..
+            } => match nhop {
+                Some(nhop) => MpReach::new(*afi, Safi::FlowspecVpn, nhop, updates).attr_emit(buf),
+                None => MpReach::without_nexthop(*afi, Safi::FlowspecVpn, updates).attr_emit(buf),
+            },
+            SrPolicy {
+                afi, nhop, updates, ..
+            } => {
+                MpReach::new(*afi, Safi::SrPolicy, nhop, updates).attr_emit(buf);
             }
         }
     }
 }
 
+// Next hop of `nhop_len` which must be one of `valid`. 32 octets are IPv6
+// global address followed by link-local address (RFC2545 3).
+fn parse_nexthop<'a>(
+    input: &'a [u8],
+    nhop_len: u8,
+    valid: &[u8],
+) -> nom::IResult<&'a [u8], (IpAddr, Option<Ipv6Addr>)> {
+    if !valid.contains(&nhop_len) {
+        return Err(nom::Err::Error(make_error(input, ErrorKind::LengthValue)));
+    }
+    match nhop_len {
+        4 => {
+            let (input, addr) = be_u32(input)?;
+            Ok((input, (IpAddr::V4(Ipv4Addr::from(addr)), None)))
+        }
+        16 => {
+            let (input, addr) = be_u128(input)?;
+            Ok((input, (IpAddr::V6(Ipv6Addr::from(addr)), None)))
+        }
+        _ => {
+            let (input, addr) = be_u128(input)?;
+            let (input, link_local) = be_u128(input)?;
+            let nhop = IpAddr::V6(Ipv6Addr::from(addr));
+            Ok((input, (nhop, Some(Ipv6Addr::from(link_local)))))
+        }
+    }
+}
+
 impl MpNlriReachAttr {
-    pub fn parse_nlri_opt(input: &[u8], opt: Option<ParseOption>) -> nom::IResult<&[u8], Self> {
+    pub fn parse_nlri_opt<'a>(
+        input: &'a [u8],
+        opt: &Option<ParseOption>,
+    ) -> nom::IResult<&'a [u8], Self> {
         if input.len() < size_of::<MpNlriReachHeader>() {
             return Err(nom::Err::Error(make_error(input, ErrorKind::Eof)));
         }
         let (input, header) = MpNlriReachHeader::parse_be(input)?;
+        // RFC8277 2.1: Number of labels is limited by Multiple Labels
+        // capability.
+        let label_count = opt
+            .as_ref()
+            .map_or(u8::MAX, |opt| opt.label_recv_count(header.afi, header.safi));
+        // RFC8950: IPv4 NLRI can have IPv6 next hop when Extended Next Hop
+        // Encoding capability is negotiated.
+        let ext_nexthop = opt
+            .as_ref()
+            .is_none_or(|opt| opt.is_extended_nexthop(header.afi, header.safi));
+        let ipv4_nexthop: &[u8] = if ext_nexthop { &[4, 16, 32] } else { &[4] };
         let add_path = if let Some(opt) = opt {
             opt.is_add_path_recv(header.afi, header.safi)
         } else {
             false
         };
+        if header.afi == Afi::Ip && header.safi == Safi::Unicast {
+            let (input, (nhop, link_local)) = parse_nexthop(input, header.nhop_len, ipv4_nexthop)?;
+            let (input, snpa) = be_u8(input)?;
+            let (_, updates) = many0(|i| Ipv4Nlri::parse_nlri(i, add_path)).parse(input)?;
+            let mp_nlri = MpNlriReachAttr::Ipv4 {
+                snpa,
+                nhop,
+                link_local,
+                updates,
+            };
+            return Ok((input, mp_nlri));
+        }
+        if header.afi == Afi::Ip && header.safi == Safi::MplsLabel {
+            let (input, (nhop, link_local)) = parse_nexthop(input, header.nhop_len, ipv4_nexthop)?;
+            let (input, snpa) = be_u8(input)?;
+            let (_, updates) =
+                many0(|i| LabeledIpv4Nlri::parse_labeled(i, add_path, label_count)).parse(input)?;
+            let mp_nlri = MpNlriReachAttr::Ipv4Labeled {
+                snpa,
+                nhop,
+                link_local,
+                updates,
+            };
+            return Ok((input, mp_nlri));
+        }
+        if header.afi == Afi::Ip6 && header.safi == Safi::MplsLabel {
+            let (input, (nhop, link_local)) = parse_nexthop(input, header.nhop_len, &[16, 32])?;
+            let (input, snpa) = be_u8(input)?;
+            let (_, updates) =
+                many0(|i| LabeledIpv6Nlri::parse_labeled(i, add_path, label_count)).parse(input)?;
+            let mp_nlri = MpNlriReachAttr::Ipv6Labeled {
+                snpa,
+                nhop,
+                link_local,
+                updates,
+            };
+            return Ok((input, mp_nlri));
+        }
         if header.afi == Afi::Ip && header.safi == Safi::MplsVpn {
+            // RD(8)+IPv4 or RFC8950 RD(8)+IPv6 with optional RD(8)+IPv6
+            // link-local.
+            if !ext_nexthop && header.nhop_len != 12 {
+                return Err(nom::Err::Error(make_error(input, ErrorKind::LengthValue)));
+            }
             let (input, rd) = RouteDistinguisher::parse_be(input)?;
-            let (input, nhop) = be_u32(input)?;
-            let nhop: Ipv4Addr = Ipv4Addr::from(nhop);
-            let nhop = Vpnv4Nexthop { rd, nhop };
+            let (input, (nhop, link_local)) = match header.nhop_len {
+                12 => parse_nexthop(input, 4, &[4])?,
+                24 => parse_nexthop(input, 16, &[16])?,
+                48 => {
+                    let (input, (nhop, _)) = parse_nexthop(input, 16, &[16])?;
+                    let (input, _) = take(8usize).parse(input)?;
+                    let (input, link_local) = be_u128(input)?;
+                    (input, (nhop, Some(Ipv6Addr::from(link_local))))
+                }
+                _ => return Err(nom::Err::Error(make_error(input, ErrorKind::LengthValue))),
+            };
+            let nhop = Vpnv4Nexthop {
+                rd,
+                nhop,
+                link_local,
+            };
             let (input, snpa) = be_u8(input)?;
             let (_, updates) = many0(|i| Vpnv4Nlri::parse_nlri(i, add_path)).parse(input)?;
             let mp_nlri = MpNlriReachAttr::Vpnv4 {
@@ -99,35 +405,52 @@ impl MpNlriReachAttr {
             };
             return Ok((input, mp_nlri));
         }
-        if header.afi == Afi::Ip6 && header.safi == Safi::Unicast {
-            if header.nhop_len != 16 {
+        if header.afi == Afi::Ip6 && header.safi == Safi::MplsVpn {
+            // RFC4659 3.2.1.1: RD(8)+IPv6 with optional RD(8)+IPv6
+            // link-local.
+            if header.nhop_len != 24 && header.nhop_len != 48 {
                 return Err(nom::Err::Error(make_error(input, ErrorKind::LengthValue)));
             }
+            let (input, rd) = RouteDistinguisher::parse_be(input)?;
             let (input, nhop) = be_u128(input)?;
-            let nhop = IpAddr::V6(Ipv6Addr::from(nhop));
+            let nhop = Ipv6Addr::from(nhop);
+            let (input, link_local) = if header.nhop_len == 48 {
+                let (input, _) = take(8usize).parse(input)?;
+                let (input, link_local) = be_u128(input)?;
+                (input, Some(Ipv6Addr::from(link_local)))
+            } else {
+                (input, None)
+            };
+            let nhop = Vpnv6Nexthop {
+                rd,
+                nhop,
+                link_local,
+            };
+            let (input, snpa) = be_u8(input)?;
+            let (_, updates) = many0(|i| Vpnv6Nlri::parse_nlri(i, add_path)).parse(input)?;
+            let mp_nlri = MpNlriReachAttr::Vpnv6 {
+                snpa,
+                nhop,
+                updates,
+            };
+            return Ok((input, mp_nlri));
+        }
+        if header.afi == Afi::Ip6 && header.safi == Safi::Unicast {
+            // Global address with optional link-local address.
+            let (input, (nhop, link_local)) = parse_nexthop(input, header.nhop_len, &[16, 32])?;
             let (input, snpa) = be_u8(input)?;
             let (_, updates) = many0(|i| Ipv6Nlri::parse_nlri(i, add_path)).parse(input)?;
             let mp_nlri = MpNlriReachAttr::Ipv6 {
                 snpa,
                 nhop,
+                link_local,
                 updates,
             };
             return Ok((input, mp_nlri));
         }
         if header.afi == Afi::L2vpn && header.safi == Safi::Evpn {
             // Nexthop can be IPv4 or IPv6 address.
-            if header.nhop_len != 4 && header.nhop_len != 16 {
-                return Err(nom::Err::Error(make_error(input, ErrorKind::LengthValue)));
-            }
-            let (input, nhop) = if header.nhop_len == 4 {
-                let (input, addr) = be_u32(input)?;
-                let nhop: IpAddr = IpAddr::V4(Ipv4Addr::from(addr));
-                (input, nhop)
-            } else {
-                let (input, addr) = be_u128(input)?;
-                let nhop: IpAddr = IpAddr::V6(Ipv6Addr::from(addr));
-                (input, nhop)
-            };
+            let (input, (nhop, _)) = parse_nexthop(input, header.nhop_len, &[4, 16])?;
             let (input, snpa) = be_u8(input)?;
 
             // EVPN
@@ -142,18 +465,7 @@ impl MpNlriReachAttr {
         }
         if header.afi == Afi::Ip && header.safi == Safi::Rtc {
             // Nexthop can be IPv4 or IPv6 address.
-            if header.nhop_len != 4 && header.nhop_len != 16 {
-                return Err(nom::Err::Error(make_error(input, ErrorKind::LengthValue)));
-            }
-            let (input, nhop) = if header.nhop_len == 4 {
-                let (input, addr) = be_u32(input)?;
-                let nhop: IpAddr = IpAddr::V4(Ipv4Addr::from(addr));
-                (input, nhop)
-            } else {
-                let (input, addr) = be_u128(input)?;
-                let nhop: IpAddr = IpAddr::V6(Ipv6Addr::from(addr));
-                (input, nhop)
-            };
+            let (input, (nhop, _)) = parse_nexthop(input, header.nhop_len, &[4, 16])?;
             let (input, snpa) = be_u8(input)?;
             let (input, updates) = many0(|i| Rtcv4::parse_nlri(i, add_path)).parse(input)?;
             let rtc_nlri = MpNlriReachAttr::Rtcv4 {
@@ -163,6 +475,62 @@ impl MpNlriReachAttr {
             };
             return Ok((input, rtc_nlri));
         }
+        if header.afi == Afi::LinkState && header.safi == Safi::LinkState {
+            let (input, (nhop, _)) = parse_nexthop(input, header.nhop_len, &[4, 16, 32])?;
+            let (input, snpa) = be_u8(input)?;
+            let (input, updates) = many0(LsNlri::parse_be).parse(input)?;
+            let mp_nlri = MpNlriReachAttr::LinkState {
+                snpa,
+                nhop,
+                updates,
+            };
+            return Ok((input, mp_nlri));
+        }
+        if (header.afi == Afi::Ip || header.afi == Afi::Ip6)
+            && (header.safi == Safi::Flowspec || header.safi == Safi::FlowspecVpn)
+        {
+            // RFC8955 4: Next hop length is usually zero. Otherwise it is used
+            // for redirect to IP action.
+            let (input, nhop) = if header.nhop_len == 0 {
+                (input, None)
+            } else {
+                let (input, (nhop, _)) = parse_nexthop(input, header.nhop_len, &[4, 16, 32])?;
+                (input, Some(nhop))
+            };
+            let (input, snpa) = be_u8(input)?;
+            let afi = header.afi;
+            if header.safi == Safi::Flowspec {
+                let (input, updates) = many0(|i| FlowspecNlri::parse_nlri(i, afi)).parse(input)?;
+                let mp_nlri = MpNlriReachAttr::Flowspec {
+                    afi,
+                    snpa,
+                    nhop,
+                    updates,
+                };
+                return Ok((input, mp_nlri));
+            }
+            let (input, updates) = many0(|i| FlowspecVpnNlri::parse_nlri(i, afi)).parse(input)?;
+            let mp_nlri = MpNlriReachAttr::FlowspecVpn {
+                afi,
+                snpa,
+                nhop,
+                updates,
+            };
+            return Ok((input, mp_nlri));
+        }
+        if (header.afi == Afi::Ip || header.afi == Afi::Ip6) && header.safi == Safi::SrPolicy {
+            // RFC9830 2.1: Next hop is IPv4 or IPv6 address.
+            let (input, (nhop, _)) = parse_nexthop(input, header.nhop_len, &[4, 16])?;
+            let (input, snpa) = be_u8(input)?;
+            let (input, updates) = many0(|i| SrPolicyNlri::parse_nlri(i, add_path)).parse(input)?;
+            let mp_nlri = MpNlriReachAttr::SrPolicy {
+                afi: header.afi,
+                snpa,
+                nhop,
+                updates,
+            };
+            return Ok((input, mp_nlri));
+        }
         Err(nom::Err::Error(make_error(input, ErrorKind::NoneOf)))
     }
 }
@@ -170,7 +538,7 @@ impl MpNlriReachAttr {
 // Not used.
 impl ParseBe<MpNlriReachAttr> for MpNlriReachAttr {
     fn parse_be(input: &[u8]) -> nom::IResult<&[u8], Self> {
-        Self::parse_nlri_opt(input, None)
+        Self::parse_nlri_opt(input, &None)
     }
 }
 
@@ -178,15 +546,36 @@ impl fmt::Display for MpNlriReachAttr {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         use MpNlriReachAttr::*;
         match self {
+            Ipv4 {
+                snpa: _,
+                nhop,
+                link_local: _,
+                updates,
+            } => {
+                for update in updates.iter() {
+                    writeln!(f, "{}:{} => {}", update.id, update.prefix, nhop)?;
+                }
+            }
             Ipv6 {
                 snpa: _,
                 nhop,
+                link_local: _,
                 updates,
             } => {
                 for update in updates.iter() {
                     writeln!(f, "{}:{} => {}", update.id, update.prefix, nhop)?;
                 }
             }
+            Ipv4Labeled { nhop, updates, .. } => {
+                for update in updates.iter() {
+                    writeln!(f, "{} => {}", update, nhop)?;
+                }
+            }
+            Ipv6Labeled { nhop, updates, .. } => {
+                for update in updates.iter() {
+                    writeln!(f, "{} => {}", update, nhop)?;
+                }
+            }
             Vpnv4 {
                 snpa: _,
                 nhop: _,
@@ -200,35 +589,52 @@ impl fmt::Display for MpNlriReachAttr {
                     )?;
                 }
             }
+            Vpnv6 {
+                snpa: _,
+                nhop: _,
+                updates,
+            } => {
+                for update in updates.iter() {
+                    writeln!(
+                        f,
+                        " {}:[{}]:{}",
+                        update.nlri.id, update.rd, update.nlri.prefix,
+                    )?;
+                }
+            }
             Evpn {
                 snpa: _,
                 nhop: _,
                 updates,
             } => {
                 for update in updates.iter() {
-                    match update {
-                        EvpnRoute::Mac(v) => {
-                            writeln!(
-                                f,
-                                " [{}] VNI:{}, MAC:{:02x}{:02x}:{:02x}{:02x}:{:02x}{:02x}",
-                                v.rd,
-                                v.vni,
-                                v.mac[0],
-                                v.mac[1],
-                                v.mac[2],
-                                v.mac[3],
-                                v.mac[4],
-                                v.mac[5],
-                            )?;
-                        }
-                        EvpnRoute::Multicast(v) => {
-                            writeln!(f, " [{}] {}:{}", v.rd, v.ether_tag, v.addr)?;
-                        }
-                    }
+                    writeln!(f, " {}", update)?;
+                }
+            }
+            LinkState { updates, .. } => {
+                for update in updates.iter() {
+                    writeln!(f, " {}", update)?;
+                }
+            }
+            Flowspec { updates, .. } => {
+                for update in updates.iter() {
+                    writeln!(f, " {}", update)?;
                 }
             }
-            _ => {
-                //
+            FlowspecVpn { updates, .. } => {
+                for update in updates.iter() {
+                    writeln!(f, " {}", update)?;
+                }
+            }
+            Rtcv4 { nhop, updates, .. } => {
+                for update in updates.iter() {
+                    writeln!(f, " ASN:{} {} => {}", update.asn, update.rt, nhop)?;
+                }
+            }
+            SrPolicy { nhop, updates, .. } => {
+                for update in updates.iter() {
+                    writeln!(f, " {} => {}", update, nhop)?;
+                }
             }
         }
         Ok(())
@@ -240,3 +646,309 @@ impl fmt::Debug for MpNlriReachAttr {
         write!(f, "{self}")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AfiSafi;
+    use hex_literal::hex;
+
+    #[test]
+    fn ipv4_ipv6_nexthop() {
+        // IPv4 unicast with IPv6 global and link-local next hop.
+        let input = hex!(
+            "0001 01 20"
+            "20010db8000000000000000000000001"
+            "fe800000000000000000000000000001"
+            "00"
+            "18 0a0000"
+        );
+        let (_, attr) = MpNlriReachAttr::parse_nlri_opt(&input, &None).unwrap();
+        let MpNlriReachAttr::Ipv4 {
+            nhop,
+            link_local,
+            updates,
+            ..
+        } = attr
+        else {
+            panic!("IPv4 unicast expected");
+        };
+        assert_eq!(nhop, "2001:db8::1".parse::<IpAddr>().unwrap());
+        assert_eq!(link_local, Some("fe80::1".parse().unwrap()));
+        assert_eq!(updates[0].prefix, "10.0.0.0/24".parse().unwrap());
+
+        // VPNv4 with RD and IPv6 next hop.
+        let input = hex!(
+            "0001 80 18"
+            "0000000000000000"
+            "20010db8000000000000000000000001"
+            "00"
+            "70 000011 0000fde800000064 0a0000"
+        );
+        let (_, attr) = MpNlriReachAttr::parse_nlri_opt(&input, &None).unwrap();
+        let MpNlriReachAttr::Vpnv4 { nhop, updates, .. } = attr else {
+            panic!("VPNv4 expected");
+        };
+        assert_eq!(nhop.nhop, "2001:db8::1".parse::<IpAddr>().unwrap());
+        assert_eq!(updates.len(), 1);
+
+        // Next hop length must be 12, 24 or 48 for VPNv4.
+        let mut input = input.to_vec();
+        input[3] = 16;
+        assert!(MpNlriReachAttr::parse_nlri_opt(&input, &None).is_err());
+    }
+
+    #[test]
+    fn ipv4_ipv6_nexthop_not_negotiated() {
+        let input = hex!(
+            "0001 01 10"
+            "20010db8000000000000000000000001"
+            "00"
+            "18 0a0000"
+        );
+        let mut opt = ParseOption::default();
+        assert!(MpNlriReachAttr::parse_nlri_opt(&input, &Some(opt.clone())).is_err());
+
+        // VPNv4 with IPv6 next hop.
+        let vpnv4 = hex!(
+            "0001 80 18"
+            "0000000000000000"
+            "20010db8000000000000000000000001"
+            "00"
+            "70 000011 0000fde800000064 0a0000"
+        );
+        assert!(MpNlriReachAttr::parse_nlri_opt(&vpnv4, &Some(opt.clone())).is_err());
+
+        let key = AfiSafi::new(Afi::Ip, Safi::Unicast);
+        opt.extended_nexthop.insert(key, Afi::Ip6);
+        assert!(MpNlriReachAttr::parse_nlri_opt(&input, &Some(opt)).is_ok());
+    }
+
+    #[test]
+    fn ipv4_nexthop_link_local() {
+        // Link-local address is not encoded with IPv4 next hop.
+        let attr = MpNlriReachAttr::Ipv4 {
+            snpa: 0,
+            nhop: "192.168.0.1".parse().unwrap(),
+            link_local: Some("fe80::1".parse().unwrap()),
+            updates: vec![],
+        };
+        let mut buf = BytesMut::new();
+        attr.attr_emit(&mut buf);
+        assert_eq!(&buf[..], &hex!("800e09 0001 01 04 c0a80001 00"));
+    }
+
+    #[test]
+    fn evpn_mac_length() {
+        // MAC address length must be 48.
+        let input = hex!(
+            "0019 46 04 c0a80001 00"
+            "02 21 0000fde800000064 00000000000000000000 00000000 2f aabbccddeeff 00 000064"
+        );
+        assert!(MpNlriReachAttr::parse_nlri_opt(&input, &None).is_err());
+    }
+
+    fn roundtrip(input: &[u8], opt: Option<ParseOption>) {
+        let (_, attr) = MpNlriReachAttr::parse_nlri_opt(input, &opt).unwrap();
+        let mut buf = BytesMut::new();
+        attr.attr_emit(&mut buf);
+        assert_eq!(&buf[..3], &[0x80, 14, input.len() as u8]);
+        assert_eq!(&buf[3..], input);
+    }
+
+    #[test]
+    fn emit_roundtrip() {
+        // IPv6 unicast with ADD-PATH.
+        let mut opt = ParseOption::default();
+        opt.add_path.insert(
+            crate::AfiSafi::new(Afi::Ip6, Safi::Unicast),
+            crate::Direct {
+                recv: true,
+                send: true,
+            },
+        );
+        roundtrip(
+            &hex!(
+                "0002 01 10 20010db8000000000000000000000001 00"
+                "00000001 40 20010db800000000"
+            ),
+            Some(opt),
+        );
+
+        // IPv6 unicast with global and link-local next hop.
+        roundtrip(
+            &hex!(
+                "0002 01 20 20010db8000000000000000000000001 fe800000000000000000000000000001 00"
+                "30 20010db80001"
+            ),
+            None,
+        );
+
+        // EVPN MAC/IP Advertisement and Inclusive Multicast.
+        roundtrip(
+            &hex!(
+                "0019 46 04 c0a80001 00"
+                "02 21 0000fde800000064 00000000000000000000 00000000 30 aabbccddeeff 00 000064"
+                "03 11 0000fde800000064 00000000 20 c0a80001"
+            ),
+            None,
+        );
+
+        // EVPN MAC/IP Advertisement with ESI, IPv4/IPv6 address and
+        // second label.
+        roundtrip(
+            &hex!(
+                "0019 46 04 c0a80001 00"
+                "02 28 0000fde800000064 01 aabbccddeeff 0102 00 00000000 30 aabbccddeeff"
+                "20 0a000001 000064 0003e8"
+                "02 31 0000fde800000064 00 000000000000000000 00000000 30 aabbccddeeff"
+                "80 20010db8000000000000000000000001 000064"
+            ),
+            None,
+        );
+
+        // EVPN SMET, Join Synch and Leave Synch.
+        roundtrip(
+            &hex!(
+                "0019 46 04 c0a80001 00"
+                "06 18 0000fde800000064 00000000 00 20 e0000001 20 c0a80001 02"
+                "07 26 0000fde800000064 00 010203040506070809 00000000"
+                "20 0a000001 20 e0000001 20 c0a80001 0c"
+                "08 27 0000fde800000064 00 010203040506070809 00000000"
+                "00 20 e0000001 20 c0a80001 00000001 0a 02"
+            ),
+            None,
+        );
+
+        // EVPN Ethernet A-D, Ethernet Segment and IP Prefix.
+        roundtrip(
+            &hex!(
+                "0019 46 04 c0a80001 00"
+                "01 19 0000fde800000064 00 010203040506070809 00000000 000064"
+                "04 17 0000fde800000064 00 010203040506070809 20 c0a80001"
+                "05 22 0000fde800000064 00 000000000000000000 00000000 18 0a000000 00000000 0003e8"
+                "05 3a 0000fde800000064 00 000000000000000000 00000000 40"
+                "20010db8000000000000000000000000 00000000000000000000000000000000 0003e8"
+            ),
+            None,
+        );
+
+        // EVPN route of unknown type is kept.
+        roundtrip(
+            &hex!(
+                "0019 46 04 c0a80001 00"
+                "0b 03 010203"
+                "03 11 0000fde800000064 00000000 20 c0a80001"
+            ),
+            None,
+        );
+
+        // Route Target Constraint.
+        roundtrip(
+            &hex!("0001 84 04 c0a80001 00 60 0000fde8 0002fde800000064"),
+            None,
+        );
+        let input = hex!("0001 84 04 c0a80001 00 60 0000fde8 0002fde800000064");
+        let (_, attr) = MpNlriReachAttr::parse_nlri_opt(&input, &None).unwrap();
+        assert!(attr.to_string().contains("ASN:65000"));
+
+        // VPNv4 and IPv4 unicast.
+        roundtrip(
+            &hex!(
+                "0001 80 0c 0000000000000000 c0a80001 00"
+                "70 000011 0000fde800000064 0a0000"
+            ),
+            None,
+        );
+
+        // VPNv4 with IPv6 global and link-local next hop.
+        roundtrip(
+            &hex!(
+                "0001 80 30 0000000000000000 20010db8000000000000000000000001"
+                "0000000000000000 fe800000000000000000000000000001 00"
+                "70 000011 0000fde800000064 0a0000"
+            ),
+            None,
+        );
+        roundtrip(&hex!("0001 01 04 c0a80001 00 18 0a0000"), None);
+
+        // IPv4 and IPv6 labeled unicast with label stack.
+        roundtrip(
+            &hex!("0001 04 04 c0a80001 00 48 000100 000111 0a0000"),
+            None,
+        );
+        roundtrip(
+            &hex!(
+                "0002 04 10 20010db8000000000000000000000001 00"
+                "38 000101 20010db8"
+            ),
+            None,
+        );
+
+        // VPNv6.
+        roundtrip(
+            &hex!(
+                "0002 80 18 0000000000000000 20010db8000000000000000000000001 00"
+                "78 000011 0000fde800000064 20010db8"
+            ),
+            None,
+        );
+
+        // VPNv6 with global and link-local next hop.
+        roundtrip(
+            &hex!(
+                "0002 80 30 0000000000000000 20010db8000000000000000000000001"
+                "0000000000000000 fe800000000000000000000000000001 00"
+                "78 000011 0000fde800000064 20010db8"
+            ),
+            None,
+        );
+
+        // VPNv6 with ADD-PATH.
+        let mut opt = ParseOption::default();
+        opt.add_path.insert(
+            crate::AfiSafi::new(Afi::Ip6, Safi::MplsVpn),
+            crate::Direct {
+                recv: true,
+                send: true,
+            },
+        );
+        roundtrip(
+            &hex!(
+                "0002 80 18 0000000000000000 20010db8000000000000000000000001 00"
+                "00000001 78 000011 0000fde800000064 20010db8"
+            ),
+            Some(opt),
+        );
+
+        // BGP-LS node.
+        roundtrip(
+            &hex!(
+                "4004 47 04 c0a80001 00"
+                "0001 0025 03 0000000000000000 0100 0018 0200 0004 0000fde8 0201 0004 00000000"
+                "0203 0004 0a000001"
+            ),
+            None,
+        );
+
+        // IPv4 FlowSpec without next hop and VPN FlowSpec.
+        roundtrip(
+            &hex!("0001 85 00 00 0e 01 18 0a0001 03 81 06 05 01 50 91 1f90"),
+            None,
+        );
+        roundtrip(
+            &hex!("0001 86 00 00 10 0000fde800000064 01 18 0a0001 03 81 06"),
+            None,
+        );
+
+        // SR Policy with IPv4 and IPv6 endpoint.
+        roundtrip(
+            &hex!(
+                "0001 49 04 c0a80001 00"
+                "60 00000001 00000064 0a000001"
+                "c0 00000002 000000c8 20010db8000000000000000000000001"
+            ),
+            None,
+        );
+    }
+}
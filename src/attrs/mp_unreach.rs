@@ -1,14 +1,16 @@
 use std::fmt;
 
-use bytes::BytesMut;
+use bytes::{BufMut, BytesMut};
 use nom::error::{ErrorKind, make_error};
 use nom_derive::*;
 
 use crate::{
-    Afi, EvpnRoute, Ipv6Nlri, ParseBe, ParseNlri, ParseOption, Rtcv4, Safi, Vpnv4Nlri, many0,
+    Afi, AttrFlags, AttrType, EmitNlri, EvpnRoute, FlowspecNlri, FlowspecVpnNlri, Ipv4Nlri,
+    Ipv6Nlri, LabeledIpv4Nlri, LabeledIpv6Nlri, LsNlri, ParseBe, ParseNlri, ParseOption, Rtcv4,
+    Safi, SrPolicyNlri, Vpnv4Nlri, Vpnv6Nlri, many0,
 };
 
-use super::{AttrEmitter, Vpnv4Unreach};
+use super::{AttrEmitter, Vpnv4Unreach, Vpnv6Unreach};
 
 #[derive(Clone, Debug, NomBE)]
 pub struct MpNlriUnreachHeader {
@@ -18,42 +20,167 @@ pub struct MpNlriUnreachHeader {
 
 #[derive(Clone)]
 pub enum MpNlriUnreachAttr {
-    // Ipv4Nlri(Vec<>),
+    Ipv4Nlri(Vec<Ipv4Nlri>),
     Ipv4Eor,
     Ipv6Nlri(Vec<Ipv6Nlri>),
     Ipv6Eor,
+    Ipv4Labeled(Vec<LabeledIpv4Nlri>),
+    Ipv4LabeledEor,
+    Ipv6Labeled(Vec<LabeledIpv6Nlri>),
+    Ipv6LabeledEor,
     Vpnv4(Vec<Vpnv4Nlri>),
     Vpnv4Eor,
-    // Vpnv6,
-    // Vpnv6Eor,
+    Vpnv6(Vec<Vpnv6Nlri>),
+    Vpnv6Eor,
     Evpn(Vec<EvpnRoute>),
     EvpnEor,
     Rtcv4(Vec<Rtcv4>),
     Rtcv4Eor,
+    LinkState(Vec<LsNlri>),
+    LinkStateEor,
+    Flowspec(Afi, Vec<FlowspecNlri>),
+    FlowspecEor(Afi),
+    FlowspecVpn(Afi, Vec<FlowspecVpnNlri>),
+    FlowspecVpnEor(Afi),
+    SrPolicy(Afi, Vec<SrPolicyNlri>),
+    SrPolicyEor(Afi),
+}
+
+// MP_UNREACH_NLRI for the AFI/SAFI. Empty withdraw is End-of-RIB marker.
+struct MpUnreach<'a, T: EmitNlri> {
+    afi: Afi,
+    safi: Safi,
+    withdraw: &'a [T],
+}
+
+impl<'a, T: EmitNlri> MpUnreach<'a, T> {
+    fn new(afi: Afi, safi: Safi, withdraw: &'a [T]) -> Self {
+        Self {
+            afi,
+            safi,
+            withdraw,
+        }
+    }
+}
+
+impl<T: EmitNlri> AttrEmitter for MpUnreach<'_, T> {
+    fn attr_type(&self) -> AttrType {
+        AttrType::MpUnreachNlri
+    }
+
+    fn attr_flags(&self) -> AttrFlags {
+        AttrFlags::new().with_optional(true)
+    }
+
+    fn len(&self) -> Option<usize> {
+        None
+    }
+
+    fn emit(&self, buf: &mut BytesMut) {
+        buf.put_u16(u16::from(self.afi));
+        buf.put_u8(u8::from(self.safi));
+        for withdraw in self.withdraw.iter() {
+            withdraw.emit_nlri(buf);
+        }
+    }
 }
 
 impl MpNlriUnreachAttr {
     pub fn attr_emit(&self, buf: &mut BytesMut) {
+        use MpNlriUnreachAttr::*;
         match self {
-            MpNlriUnreachAttr::Vpnv4(withdraw) => {
+            Ipv4Nlri(withdraw) => {
+                MpUnreach::new(Afi::Ip, Safi::Unicast, withdraw).attr_emit(buf);
+            }
+            Ipv4Eor => {
+                MpUnreach::<crate::Ipv4Nlri>::new(Afi::Ip, Safi::Unicast, &[]).attr_emit(buf);
+            }
+            Ipv6Nlri(withdraw) => {
+                MpUnreach::new(Afi::Ip6, Safi::Unicast, withdraw).attr_emit(buf);
+            }
+            Ipv6Eor => {
+                MpUnreach::<crate::Ipv6Nlri>::new(Afi::Ip6, Safi::Unicast, &[]).attr_emit(buf);
+            }
+            Ipv4Labeled(withdraw) => {
+                // RFC8277 2.4: Withdrawn NLRI has the withdraw label only.
+                let withdraw: Vec<_> = withdraw.iter().map(|v| v.to_withdraw()).collect();
+                MpUnreach::new(Afi::Ip, Safi::MplsLabel, &withdraw).attr_emit(buf);
+            }
+            Ipv4LabeledEor => {
+                MpUnreach::<LabeledIpv4Nlri>::new(Afi::Ip, Safi::MplsLabel, &[]).attr_emit(buf);
+            }
+            Ipv6Labeled(withdraw) => {
+                let withdraw: Vec<_> = withdraw.iter().map(|v| v.to_withdraw()).collect();
+                MpUnreach::new(Afi::Ip6, Safi::MplsLabel, &withdraw).attr_emit(buf);
+            }
+            Ipv6LabeledEor => {
+                MpUnreach::<LabeledIpv6Nlri>::new(Afi::Ip6, Safi::MplsLabel, &[]).attr_emit(buf);
+            }
+            Vpnv4(withdraw) => {
                 let attr = Vpnv4Unreach {
                     withdraw: withdraw.clone(),
                 };
                 attr.attr_emit(buf);
             }
-            MpNlriUnreachAttr::Vpnv4Eor => {
+            Vpnv4Eor => {
                 let attr = Vpnv4Unreach { withdraw: vec![] };
                 attr.attr_emit(buf);
             }
-            _ => {
-                //
+            Vpnv6(withdraw) => {
+                let attr = Vpnv6Unreach {
+                    withdraw: withdraw.clone(),
+                };
+                attr.attr_emit(buf);
+            }
+            Vpnv6Eor => {
+                let attr = Vpnv6Unreach { withdraw: vec![] };
+                attr.attr_emit(buf);
+            }
+            Evpn(withdraw) => {
+                MpUnreach::new(Afi::L2vpn, Safi::Evpn, withdraw).attr_emit(buf);
+            }
+            EvpnEor => {
+                MpUnreach::<EvpnRoute>::new(Afi::L2vpn, Safi::Evpn, &[]).attr_emit(buf);
+            }
+            Rtcv4(withdraw) => {
+                MpUnreach::new(Afi::Ip, Safi::Rtc, withdraw).attr_emit(buf);
+            }
+            Rtcv4Eor => {
+                MpUnreach::<crate::Rtcv4>::new(Afi::Ip, Safi::Rtc, &[]).attr_emit(buf);
+            }
+            LinkState(withdraw) => {
+                MpUnreach::new(Afi::LinkState, Safi::LinkState, withdraw).attr_emit(buf);
+            }
+            LinkStateEor => {
+                MpUnreach::<LsNlri>::new(Afi::LinkState, Safi::LinkState, &[]).attr_emit(buf);
+            }
+            Flowspec(afi, withdraw) => {
+                MpUnreach::new(*afi, Safi::Flowspec, withdraw).attr_emit(buf);
+            }
+            FlowspecEor(afi) => {
+                MpUnreach::<FlowspecNlri>::new(*afi, Safi::Flowspec, &[]).attr_emit(buf);
+            }
+            FlowspecVpn(afi, withdraw) => {
+                MpUnreach::new(*afi, Safi::FlowspecVpn, withdraw).attr_emit(buf);
+            }
+            FlowspecVpnEor(afi) => {
+                MpUnreach::<FlowspecVpnNlri>::new(*afi, Safi::FlowspecVpn, &[]).attr_emit(buf);
+            }
+            SrPolicy(afi, withdraw) => {
+                MpUnreach::new(*afi, Safi::SrPolicy, withdraw).attr_emit(buf);
+            }
+            SrPolicyEor(afi) => {
+                MpUnreach::<SrPolicyNlri>::new(*afi, Safi::SrPolicy, &[]).attr_emit(buf);
             }
         }
     }
 }
 
 impl MpNlriUnreachAttr {
-    pub fn parse_nlri_opt(input: &[u8], opt: Option<ParseOption>) -> nom::IResult<&[u8], Self> {
+    pub fn parse_nlri_opt<'a>(
+        input: &'a [u8],
+        opt: &Option<ParseOption>,
+    ) -> nom::IResult<&'a [u8], Self> {
         // AFI + SAFI = 3.
         if input.len() < 3 {
             return Err(nom::Err::Error(nom::error::Error::new(
@@ -76,6 +203,15 @@ impl MpNlriUnreachAttr {
             let mp_nlri = MpNlriUnreachAttr::Vpnv4(withdrawal);
             return Ok((input, mp_nlri));
         }
+        if header.afi == Afi::Ip && header.safi == Safi::Unicast {
+            if input.is_empty() {
+                let mp_nlri = MpNlriUnreachAttr::Ipv4Eor;
+                return Ok((input, mp_nlri));
+            }
+            let (input, withdrawal) = many0(|i| Ipv4Nlri::parse_nlri(i, add_path)).parse(input)?;
+            let mp_nlri = MpNlriUnreachAttr::Ipv4Nlri(withdrawal);
+            return Ok((input, mp_nlri));
+        }
         if header.afi == Afi::Ip6 && header.safi == Safi::Unicast {
             if input.is_empty() {
                 let mp_nlri = MpNlriUnreachAttr::Ipv6Eor;
@@ -85,6 +221,35 @@ impl MpNlriUnreachAttr {
             let mp_nlri = MpNlriUnreachAttr::Ipv6Nlri(withdrawal);
             return Ok((input, mp_nlri));
         }
+        if header.afi == Afi::Ip && header.safi == Safi::MplsLabel {
+            if input.is_empty() {
+                let mp_nlri = MpNlriUnreachAttr::Ipv4LabeledEor;
+                return Ok((input, mp_nlri));
+            }
+            let (input, withdrawal) =
+                many0(|i| LabeledIpv4Nlri::parse_withdraw(i, add_path)).parse(input)?;
+            let mp_nlri = MpNlriUnreachAttr::Ipv4Labeled(withdrawal);
+            return Ok((input, mp_nlri));
+        }
+        if header.afi == Afi::Ip6 && header.safi == Safi::MplsLabel {
+            if input.is_empty() {
+                let mp_nlri = MpNlriUnreachAttr::Ipv6LabeledEor;
+                return Ok((input, mp_nlri));
+            }
+            let (input, withdrawal) =
+                many0(|i| LabeledIpv6Nlri::parse_withdraw(i, add_path)).parse(input)?;
+            let mp_nlri = MpNlriUnreachAttr::Ipv6Labeled(withdrawal);
+            return Ok((input, mp_nlri));
+        }
+        if header.afi == Afi::Ip6 && header.safi == Safi::MplsVpn {
+            if input.is_empty() {
+                let mp_nlri = MpNlriUnreachAttr::Vpnv6Eor;
+                return Ok((input, mp_nlri));
+            }
+            let (input, withdrawal) = many0(|i| Vpnv6Nlri::parse_nlri(i, add_path)).parse(input)?;
+            let mp_nlri = MpNlriUnreachAttr::Vpnv6(withdrawal);
+            return Ok((input, mp_nlri));
+        }
         if header.afi == Afi::L2vpn && header.safi == Safi::Evpn {
             if input.is_empty() {
                 let mp_nlri = MpNlriUnreachAttr::EvpnEor;
@@ -103,13 +268,52 @@ impl MpNlriUnreachAttr {
             let mp_nlri = MpNlriUnreachAttr::Rtcv4(rtcv4);
             return Ok((input, mp_nlri));
         }
+        if header.afi == Afi::LinkState && header.safi == Safi::LinkState {
+            if input.is_empty() {
+                let mp_nlri = MpNlriUnreachAttr::LinkStateEor;
+                return Ok((input, mp_nlri));
+            }
+            let (input, withdrawal) = many0(LsNlri::parse_be).parse(input)?;
+            let mp_nlri = MpNlriUnreachAttr::LinkState(withdrawal);
+            return Ok((input, mp_nlri));
+        }
+        if (header.afi == Afi::Ip || header.afi == Afi::Ip6) && header.safi == Safi::Flowspec {
+            if input.is_empty() {
+                let mp_nlri = MpNlriUnreachAttr::FlowspecEor(header.afi);
+                return Ok((input, mp_nlri));
+            }
+            let (input, withdrawal) =
+                many0(|i| FlowspecNlri::parse_nlri(i, header.afi)).parse(input)?;
+            let mp_nlri = MpNlriUnreachAttr::Flowspec(header.afi, withdrawal);
+            return Ok((input, mp_nlri));
+        }
+        if (header.afi == Afi::Ip || header.afi == Afi::Ip6) && header.safi == Safi::FlowspecVpn {
+            if input.is_empty() {
+                let mp_nlri = MpNlriUnreachAttr::FlowspecVpnEor(header.afi);
+                return Ok((input, mp_nlri));
+            }
+            let (input, withdrawal) =
+                many0(|i| FlowspecVpnNlri::parse_nlri(i, header.afi)).parse(input)?;
+            let mp_nlri = MpNlriUnreachAttr::FlowspecVpn(header.afi, withdrawal);
+            return Ok((input, mp_nlri));
+        }
+        if (header.afi == Afi::Ip || header.afi == Afi::Ip6) && header.safi == Safi::SrPolicy {
+            if input.is_empty() {
+                let mp_nlri = MpNlriUnreachAttr::SrPolicyEor(header.afi);
+                return Ok((input, mp_nlri));
+            }
+            let (input, withdrawal) =
+                many0(|i| SrPolicyNlri::parse_nlri(i, add_path)).parse(input)?;
+            let mp_nlri = MpNlriUnreachAttr::SrPolicy(header.afi, withdrawal);
+            return Ok((input, mp_nlri));
+        }
         Err(nom::Err::Error(make_error(input, ErrorKind::NoneOf)))
     }
 }
 
 impl ParseBe<MpNlriUnreachAttr> for MpNlriUnreachAttr {
     fn parse_be(input: &[u8]) -> nom::IResult<&[u8], Self> {
-        Self::parse_nlri_opt(input, None)
+        Self::parse_nlri_opt(input, &None)
     }
 }
 
@@ -117,6 +321,12 @@ impl fmt::Display for MpNlriUnreachAttr {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         use MpNlriUnreachAttr::*;
         match self {
+            Ipv4Nlri(ipv4_nlris) => {
+                for ipv4 in ipv4_nlris.iter() {
+                    writeln!(f, " {}:{}", ipv4.id, ipv4.prefix)?;
+                }
+                Ok(())
+            }
             Ipv4Eor => {
                 writeln!(f, " EoR: {}/{}", Afi::Ip, Safi::Unicast)
             }
@@ -129,6 +339,24 @@ impl fmt::Display for MpNlriUnreachAttr {
             Ipv6Eor => {
                 writeln!(f, " EoR: {}/{}", Afi::Ip6, Safi::Unicast)
             }
+            Ipv4Labeled(nlris) => {
+                for nlri in nlris.iter() {
+                    writeln!(f, " {}:{}", nlri.nlri.id, nlri.nlri.prefix)?;
+                }
+                Ok(())
+            }
+            Ipv4LabeledEor => {
+                writeln!(f, " EoR: {}/{}", Afi::Ip, Safi::MplsLabel)
+            }
+            Ipv6Labeled(nlris) => {
+                for nlri in nlris.iter() {
+                    writeln!(f, " {}:{}", nlri.nlri.id, nlri.nlri.prefix)?;
+                }
+                Ok(())
+            }
+            Ipv6LabeledEor => {
+                writeln!(f, " EoR: {}/{}", Afi::Ip6, Safi::MplsLabel)
+            }
             Vpnv4(vpnv4_nlris) => {
                 for vpnv4 in vpnv4_nlris.iter() {
                     writeln!(f, " {}:{}:{}", vpnv4.nlri.id, vpnv4.rd, vpnv4.nlri.prefix)?;
@@ -138,27 +366,18 @@ impl fmt::Display for MpNlriUnreachAttr {
             Vpnv4Eor => {
                 writeln!(f, " EoR: {}/{}", Afi::Ip, Safi::MplsVpn)
             }
+            Vpnv6(vpnv6_nlris) => {
+                for vpnv6 in vpnv6_nlris.iter() {
+                    writeln!(f, " {}:{}:{}", vpnv6.nlri.id, vpnv6.rd, vpnv6.nlri.prefix)?;
+                }
+                Ok(())
+            }
+            Vpnv6Eor => {
+                writeln!(f, " EoR: {}/{}", Afi::Ip6, Safi::MplsVpn)
+            }
             Evpn(evpn_routes) => {
                 for evpn in evpn_routes.iter() {
-                    match evpn {
-                        EvpnRoute::Mac(v) => {
-                            writeln!(
-                                f,
-                                " RD: {}, VNI: {}, MAC: {:02x}{:02x}:{:02x}{:02x}:{:02x}{:02x}",
-                                v.rd,
-                                v.vni,
-                                v.mac[0],
-                                v.mac[1],
-                                v.mac[2],
-                                v.mac[3],
-                                v.mac[4],
-                                v.mac[5],
-                            )?;
-                        }
-                        EvpnRoute::Multicast(v) => {
-                            writeln!(f, " [{}]{}", v.rd, v.addr)?;
-                        }
-                    }
+                    writeln!(f, " {}", evpn)?;
                 }
                 Ok(())
             }
@@ -174,6 +393,42 @@ impl fmt::Display for MpNlriUnreachAttr {
             Rtcv4Eor => {
                 writeln!(f, " EoR: {}/{}", Afi::Ip, Safi::Rtc)
             }
+            LinkState(nlris) => {
+                for nlri in nlris.iter() {
+                    writeln!(f, " {}", nlri)?;
+                }
+                Ok(())
+            }
+            LinkStateEor => {
+                writeln!(f, " EoR: {}/{}", Afi::LinkState, Safi::LinkState)
+            }
+            Flowspec(_, nlris) => {
+                for nlri in nlris.iter() {
+                    writeln!(f, " {}", nlri)?;
+                }
+                Ok(())
+            }
+            FlowspecEor(afi) => {
+                writeln!(f, " EoR: {}/{}", afi, Safi::Flowspec)
+            }
+            FlowspecVpn(_, nlris) => {
+                for nlri in nlris.iter() {
+                    writeln!(f, " {}", nlri)?;
+                }
+                Ok(())
+            }
+            FlowspecVpnEor(afi) => {
+                writeln!(f, " EoR: {}/{}", afi, Safi::FlowspecVpn)
+            }
+            SrPolicy(_, nlris) => {
+                for nlri in nlris.iter() {
+                    writeln!(f, " {}", nlri)?;
+                }
+                Ok(())
+            }
+            SrPolicyEor(afi) => {
+                writeln!(f, " EoR: {}/{}", afi, Safi::SrPolicy)
+            }
         }
     }
 }
@@ -183,3 +438,44 @@ impl fmt::Debug for MpNlriUnreachAttr {
         write!(f, "{self}")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+
+    #[test]
+    fn emit_roundtrip() {
+        let inputs: [&[u8]; 19] = [
+            &hex!("0001 01 18 0a0000"),
+            &hex!("0001 01"),
+            &hex!("0002 01 40 20010db800000000"),
+            &hex!("0019 46"),
+            &hex!("0019 46 03 11 0000fde800000064 00000000 20 c0a80001"),
+            &hex!("0001 84"),
+            &hex!("0001 80 70 800000 0000fde800000064 0a0000"),
+            &hex!("0002 80"),
+            &hex!("0001 04"),
+            &hex!("0001 04 30 800000 0a0000"),
+            &hex!("0002 04 38 800000 20010db8"),
+            &hex!("0002 80 78 800000 0000fde800000064 20010db8"),
+            &hex!("0001 85 08 01 18 0a0001 03 81 06"),
+            &hex!("4004 47"),
+            &hex!(
+                "4004 47 0003 001d 03 0000000000000000 0100 0008 0200 0004 0000fde8"
+                "0109 0004 18 0a0001"
+            ),
+            &hex!("0002 85"),
+            &hex!("0002 86 0f 0000fde800000064 01 20 00 20010db8"),
+            &hex!("0001 49"),
+            &hex!("0001 49 60 00000001 00000064 0a000001"),
+        ];
+        for input in inputs {
+            let (_, attr) = MpNlriUnreachAttr::parse_nlri_opt(input, &None).unwrap();
+            let mut buf = BytesMut::new();
+            attr.attr_emit(&mut buf);
+            assert_eq!(&buf[..3], &[0x80, 15, input.len() as u8]);
+            assert_eq!(&buf[3..], input);
+        }
+    }
+}
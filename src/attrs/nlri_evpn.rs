@@ -1,12 +1,15 @@
+use std::fmt;
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 
+use bytes::{BufMut, BytesMut};
+use ipnet::IpNet;
 use nom::IResult;
 use nom::bytes::complete::take;
 use nom::error::{ErrorKind, make_error};
-use nom::number::complete::{be_u8, be_u24, be_u32};
+use nom::number::complete::{be_u8, be_u24, be_u32, be_u128};
 use nom_derive::*;
 
-use crate::{ParseNlri, RouteDistinguisher, nlri_psize};
+use crate::{EmitNlri, Esi, ParseNlri, RouteDistinguisher, nlri_psize};
 
 #[derive(Debug, Clone)]
 pub enum EvpnRouteType {
@@ -14,6 +17,10 @@ pub enum EvpnRouteType {
     MacIpAdvRoute, // 2
     IncMulticast,  // 3
     EthernetSr,    // 4
+    IpPrefix,      // 5
+    Smet,          // 6
+    JoinSync,      // 7
+    LeaveSync,     // 8
     Unknown(u8),
 }
 
@@ -25,6 +32,10 @@ impl From<EvpnRouteType> for u8 {
             MacIpAdvRoute => 2,
             IncMulticast => 3,
             EthernetSr => 4,
+            IpPrefix => 5,
+            Smet => 6,
+            JoinSync => 7,
+            LeaveSync => 8,
             Unknown(val) => val,
         }
     }
@@ -38,6 +49,10 @@ impl From<u8> for EvpnRouteType {
             2 => MacIpAdvRoute,
             3 => IncMulticast,
             4 => EthernetSr,
+            5 => IpPrefix,
+            6 => Smet,
+            7 => JoinSync,
+            8 => LeaveSync,
             _ => Unknown(val),
         }
     }
@@ -52,27 +67,240 @@ pub struct Evpn {
 
 #[derive(Debug, Clone)]
 pub enum EvpnRoute {
+    EthernetAd(EvpnEthernetAd),
     Mac(EvpnMac),
     Multicast(EvpnMulticast),
+    EthernetSegment(EvpnEthernetSegment),
+    Prefix(EvpnPrefix),
+    Smet(EvpnSmet),
+    JoinSync(EvpnJoinSync),
+    LeaveSync(EvpnLeaveSync),
+    // Route type which is not supported. The route is kept as is.
+    Unknown { id: u32, typ: u8, data: Vec<u8> },
+}
+
+// Ethernet Auto-Discovery route (RFC7432 7.1).
+#[derive(Debug, Clone)]
+pub struct EvpnEthernetAd {
+    pub id: u32,
+    pub rd: RouteDistinguisher,
+    pub esi: Esi,
+    pub ether_tag: u32,
+    pub label: u32,
 }
 
 #[derive(Debug, Clone)]
 pub struct EvpnMac {
     pub id: u32,
     pub rd: RouteDistinguisher,
-    pub esi_type: u8,
+    pub esi: Esi,
     pub ether_tag: u32,
     pub mac: [u8; 6],
+    pub ip: Option<IpAddr>,
     pub vni: u32,
+    // Second label, L3 VNI for symmetric IRB (RFC9135).
+    pub label2: Option<u32>,
 }
 
 #[derive(Debug, Clone)]
 pub struct EvpnMulticast {
+    pub id: u32,
     pub rd: RouteDistinguisher,
     pub ether_tag: u32,
     pub addr: IpAddr,
 }
 
+// Ethernet Segment route (RFC7432 7.4).
+#[derive(Debug, Clone)]
+pub struct EvpnEthernetSegment {
+    pub id: u32,
+    pub rd: RouteDistinguisher,
+    pub esi: Esi,
+    pub addr: IpAddr,
+}
+
+// IP Prefix route (RFC9136 3.1).
+#[derive(Debug, Clone)]
+pub struct EvpnPrefix {
+    pub id: u32,
+    pub rd: RouteDistinguisher,
+    pub esi: Esi,
+    pub ether_tag: u32,
+    pub prefix: IpNet,
+    pub gateway: IpAddr,
+    pub label: u32,
+}
+
+// Flags of IGMP/MLD proxy routes (RFC9251 9).
+pub const EVPN_MCAST_FLAG_V1: u8 = 0x01;
+pub const EVPN_MCAST_FLAG_V2: u8 = 0x02;
+pub const EVPN_MCAST_FLAG_V3: u8 = 0x04;
+pub const EVPN_MCAST_FLAG_EXCLUDE: u8 = 0x08;
+
+// Multicast source and group with the originating router. Source is None for
+// (*,G).
+#[derive(Debug, Clone)]
+pub struct EvpnMcast {
+    pub source: Option<IpAddr>,
+    pub group: IpAddr,
+    pub originator: IpAddr,
+}
+
+// Selective Multicast Ethernet Tag route (RFC9251 9.1).
+#[derive(Debug, Clone)]
+pub struct EvpnSmet {
+    pub id: u32,
+    pub rd: RouteDistinguisher,
+    pub ether_tag: u32,
+    pub mcast: EvpnMcast,
+    pub flags: u8,
+}
+
+// Multicast Membership Report Synch route (RFC9251 9.2).
+#[derive(Debug, Clone)]
+pub struct EvpnJoinSync {
+    pub id: u32,
+    pub rd: RouteDistinguisher,
+    pub esi: Esi,
+    pub ether_tag: u32,
+    pub mcast: EvpnMcast,
+    pub flags: u8,
+}
+
+// Multicast Leave Synch route (RFC9251 9.3).
+#[derive(Debug, Clone)]
+pub struct EvpnLeaveSync {
+    pub id: u32,
+    pub rd: RouteDistinguisher,
+    pub esi: Esi,
+    pub ether_tag: u32,
+    pub mcast: EvpnMcast,
+    pub sync: u32,
+    pub max_response_time: u8,
+    pub flags: u8,
+}
+
+impl EvpnMcast {
+    fn parse_be(input: &[u8]) -> IResult<&[u8], Self> {
+        let (rest, source_len) = be_u8(input)?;
+        let (input, source) = if source_len == 0 {
+            (rest, None)
+        } else {
+            let (input, source) = parse_evpn_addr(input)?;
+            (input, Some(source))
+        };
+        let (input, group) = parse_evpn_addr(input)?;
+        let (input, originator) = parse_evpn_addr(input)?;
+        let mcast = EvpnMcast {
+            source,
+            group,
+            originator,
+        };
+        Ok((input, mcast))
+    }
+
+    fn emit(&self, buf: &mut BytesMut) {
+        match &self.source {
+            Some(source) => emit_evpn_addr(buf, source),
+            None => buf.put_u8(0),
+        }
+        emit_evpn_addr(buf, &self.group);
+        emit_evpn_addr(buf, &self.originator);
+    }
+}
+
+impl fmt::Display for EvpnMcast {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.source {
+            Some(source) => write!(f, "({},{})", source, self.group)?,
+            None => write!(f, "(*,{})", self.group)?,
+        }
+        write!(f, " from {}", self.originator)
+    }
+}
+
+// IP address with the length in bits.
+fn parse_evpn_addr(input: &[u8]) -> IResult<&[u8], IpAddr> {
+    let (input, addr_len) = be_u8(input)?;
+    match addr_len {
+        32 => {
+            let (input, val) = be_u32(input)?;
+            Ok((input, IpAddr::V4(Ipv4Addr::from(val))))
+        }
+        128 => {
+            let (input, val) = be_u128(input)?;
+            Ok((input, IpAddr::V6(Ipv6Addr::from(val))))
+        }
+        _ => Err(nom::Err::Error(make_error(input, ErrorKind::LengthValue))),
+    }
+}
+
+fn emit_evpn_addr(buf: &mut BytesMut, addr: &IpAddr) {
+    match addr {
+        IpAddr::V4(addr) => {
+            buf.put_u8(32);
+            buf.put(&addr.octets()[..]);
+        }
+        IpAddr::V6(addr) => {
+            buf.put_u8(128);
+            buf.put(&addr.octets()[..]);
+        }
+    }
+}
+
+fn emit_rd(buf: &mut BytesMut, rd: &RouteDistinguisher) {
+    buf.put_u16(rd.typ as u16);
+    buf.put(&rd.val[..]);
+}
+
+impl EvpnPrefix {
+    // Route length is 34 for IPv4 and 58 for IPv6 (RFC9136 3.1).
+    fn parse_be(input: &[u8], id: u32, length: u8) -> IResult<&[u8], Self> {
+        let (input, rd) = RouteDistinguisher::parse_be(input)?;
+        let (input, esi) = Esi::parse_be(input)?;
+        let (input, ether_tag) = be_u32(input)?;
+        let (input, plen) = be_u8(input)?;
+        let (input, prefix, gateway) = match length {
+            34 => {
+                let (input, addr) = be_u32(input)?;
+                let (input, gateway) = be_u32(input)?;
+                let Ok(prefix) = ipnet::Ipv4Net::new(Ipv4Addr::from(addr), plen) else {
+                    return Err(nom::Err::Error(make_error(input, ErrorKind::LengthValue)));
+                };
+                (
+                    input,
+                    IpNet::V4(prefix),
+                    IpAddr::V4(Ipv4Addr::from(gateway)),
+                )
+            }
+            58 => {
+                let (input, addr) = be_u128(input)?;
+                let (input, gateway) = be_u128(input)?;
+                let Ok(prefix) = ipnet::Ipv6Net::new(Ipv6Addr::from(addr), plen) else {
+                    return Err(nom::Err::Error(make_error(input, ErrorKind::LengthValue)));
+                };
+                (
+                    input,
+                    IpNet::V6(prefix),
+                    IpAddr::V6(Ipv6Addr::from(gateway)),
+                )
+            }
+            _ => return Err(nom::Err::Error(make_error(input, ErrorKind::LengthValue))),
+        };
+        let (input, label) = be_u24(input)?;
+        let evpn = EvpnPrefix {
+            id,
+            rd,
+            esi,
+            ether_tag,
+            prefix,
+            gateway,
+            label,
+        };
+        Ok((input, evpn))
+    }
+}
+
 impl Evpn {
     pub fn rd(&self) -> &RouteDistinguisher {
         &self.rd
@@ -83,40 +311,82 @@ impl ParseNlri<EvpnRoute> for EvpnRoute {
     fn parse_nlri(input: &[u8], addpath: bool) -> IResult<&[u8], EvpnRoute> {
         let (input, id) = if addpath { be_u32(input)? } else { (input, 0) };
         let (input, typ) = be_u8(input)?;
-        let route_type: EvpnRouteType = typ.into();
-        let (input, _length) = be_u8(input)?;
+        let (input, length) = be_u8(input)?;
 
+        // Each route is framed by the length so that unknown route type can
+        // be skipped (RFC7432 7).
+        let (input, route) = take(length).parse(input)?;
+        let (rest, evpn) = EvpnRoute::parse_route(route, id, typ, length)?;
+        if !rest.is_empty() {
+            return Err(nom::Err::Error(make_error(rest, ErrorKind::LengthValue)));
+        }
+        Ok((input, evpn))
+    }
+}
+
+impl EvpnRoute {
+    fn parse_route(input: &[u8], id: u32, typ: u8, length: u8) -> IResult<&[u8], EvpnRoute> {
         use EvpnRouteType::*;
-        match route_type {
+        match typ.into() {
+            EthernetAd => {
+                let (input, rd) = RouteDistinguisher::parse_be(input)?;
+                let (input, esi) = Esi::parse_be(input)?;
+                let (input, ether_tag) = be_u32(input)?;
+                let (input, label) = be_u24(input)?;
+                let evpn = EvpnEthernetAd {
+                    id,
+                    rd,
+                    esi,
+                    ether_tag,
+                    label,
+                };
+                Ok((input, EvpnRoute::EthernetAd(evpn)))
+            }
             MacIpAdvRoute => {
                 let (input, rd) = RouteDistinguisher::parse_be(input)?;
 
-                let (input, esi_type) = be_u8(input)?;
-                let (input, _esi) = take(9usize).parse(input)?;
+                let (input, esi) = Esi::parse_be(input)?;
                 let (input, ether_tag) = be_u32(input)?;
 
+                // RFC7432 7.2: MAC address length is always 48.
                 let (input, mac_len) = be_u8(input)?;
-                let mac_size = nlri_psize(mac_len);
-                if mac_size != 6 {
+                if mac_len != 48 {
                     return Err(nom::Err::Error(make_error(input, ErrorKind::LengthValue)));
                 }
                 let (input, mac) = take(6usize).parse(input)?;
                 let (input, ip_len) = be_u8(input)?;
+                let (input, ip) = match ip_len {
+                    0 => (input, None),
+                    32 => {
+                        let (input, val) = be_u32(input)?;
+                        (input, Some(IpAddr::V4(Ipv4Addr::from(val))))
+                    }
+                    128 => {
+                        let (input, val) = be_u128(input)?;
+                        (input, Some(IpAddr::V6(Ipv6Addr::from(val))))
+                    }
+                    _ => return Err(nom::Err::Error(make_error(input, ErrorKind::LengthValue))),
+                };
+                let (input, vni) = be_u24(input)?;
+
+                // RFC7432 7.2: Route length tells the second label.
                 let ip_size = nlri_psize(ip_len);
-                let (input, _) = if ip_size != 0 {
-                    take(ip_size).parse(input)?
+                let (input, label2) = if length as usize > 33 + ip_size {
+                    let (input, label2) = be_u24(input)?;
+                    (input, Some(label2))
                 } else {
-                    (input, &[] as &[u8])
+                    (input, None)
                 };
-                let (input, vni) = be_u24(input)?;
 
                 let mut evpn = EvpnMac {
                     id,
                     rd,
-                    esi_type,
+                    esi,
                     ether_tag,
                     mac: [0u8; 6],
+                    ip,
                     vni,
+                    label2,
                 };
                 evpn.mac.copy_from_slice(mac);
 
@@ -139,6 +409,7 @@ impl ParseNlri<EvpnRoute> for EvpnRoute {
                     (input, nhop)
                 };
                 let evpn = EvpnMulticast {
+                    id,
                     rd,
                     ether_tag,
                     addr,
@@ -146,7 +417,235 @@ impl ParseNlri<EvpnRoute> for EvpnRoute {
 
                 Ok((input, EvpnRoute::Multicast(evpn)))
             }
-            _ => Err(nom::Err::Error(make_error(input, ErrorKind::NoneOf))),
+            EthernetSr => {
+                let (input, rd) = RouteDistinguisher::parse_be(input)?;
+                let (input, esi) = Esi::parse_be(input)?;
+                let (input, addr) = parse_evpn_addr(input)?;
+                let evpn = EvpnEthernetSegment { id, rd, esi, addr };
+                Ok((input, EvpnRoute::EthernetSegment(evpn)))
+            }
+            IpPrefix => {
+                let (input, evpn) = EvpnPrefix::parse_be(input, id, length)?;
+                Ok((input, EvpnRoute::Prefix(evpn)))
+            }
+            Smet => {
+                let (input, rd) = RouteDistinguisher::parse_be(input)?;
+                let (input, ether_tag) = be_u32(input)?;
+                let (input, mcast) = EvpnMcast::parse_be(input)?;
+                let (input, flags) = be_u8(input)?;
+                let evpn = EvpnSmet {
+                    id,
+                    rd,
+                    ether_tag,
+                    mcast,
+                    flags,
+                };
+                Ok((input, EvpnRoute::Smet(evpn)))
+            }
+            JoinSync => {
+                let (input, rd) = RouteDistinguisher::parse_be(input)?;
+                let (input, esi) = Esi::parse_be(input)?;
+                let (input, ether_tag) = be_u32(input)?;
+                let (input, mcast) = EvpnMcast::parse_be(input)?;
+                let (input, flags) = be_u8(input)?;
+                let evpn = EvpnJoinSync {
+                    id,
+                    rd,
+                    esi,
+                    ether_tag,
+                    mcast,
+                    flags,
+                };
+                Ok((input, EvpnRoute::JoinSync(evpn)))
+            }
+            LeaveSync => {
+                let (input, rd) = RouteDistinguisher::parse_be(input)?;
+                let (input, esi) = Esi::parse_be(input)?;
+                let (input, ether_tag) = be_u32(input)?;
+                let (input, mcast) = EvpnMcast::parse_be(input)?;
+                let (input, sync) = be_u32(input)?;
+                let (input, max_response_time) = be_u8(input)?;
+                let (input, flags) = be_u8(input)?;
+                let evpn = EvpnLeaveSync {
+                    id,
+                    rd,
+                    esi,
+                    ether_tag,
+                    mcast,
+                    sync,
+                    max_response_time,
+                    flags,
+                };
+                Ok((input, EvpnRoute::LeaveSync(evpn)))
+            }
+            Unknown(_) => {
+                let evpn = EvpnRoute::Unknown {
+                    id,
+                    typ,
+                    data: input.to_vec(),
+                };
+                Ok((&input[input.len()..], evpn))
+            }
+        }
+    }
+}
+
+impl EmitNlri for EvpnRoute {
+    fn emit_nlri(&self, buf: &mut BytesMut) {
+        let (id, typ) = match self {
+            EvpnRoute::EthernetAd(v) => (v.id, EvpnRouteType::EthernetAd),
+            EvpnRoute::Mac(v) => (v.id, EvpnRouteType::MacIpAdvRoute),
+            EvpnRoute::Multicast(v) => (v.id, EvpnRouteType::IncMulticast),
+            EvpnRoute::EthernetSegment(v) => (v.id, EvpnRouteType::EthernetSr),
+            EvpnRoute::Prefix(v) => (v.id, EvpnRouteType::IpPrefix),
+            EvpnRoute::Smet(v) => (v.id, EvpnRouteType::Smet),
+            EvpnRoute::JoinSync(v) => (v.id, EvpnRouteType::JoinSync),
+            EvpnRoute::LeaveSync(v) => (v.id, EvpnRouteType::LeaveSync),
+            EvpnRoute::Unknown { id, typ, .. } => (*id, EvpnRouteType::Unknown(*typ)),
+        };
+        if id != 0 {
+            buf.put_u32(id);
+        }
+        let mut route = BytesMut::new();
+        match self {
+            EvpnRoute::EthernetAd(v) => {
+                emit_rd(&mut route, &v.rd);
+                v.esi.emit(&mut route);
+                route.put_u32(v.ether_tag);
+                route.put(&v.label.to_be_bytes()[1..]);
+            }
+            EvpnRoute::Mac(v) => {
+                emit_rd(&mut route, &v.rd);
+                v.esi.emit(&mut route);
+                route.put_u32(v.ether_tag);
+                route.put_u8(48);
+                route.put(&v.mac[..]);
+                match v.ip {
+                    Some(addr) => emit_evpn_addr(&mut route, &addr),
+                    None => route.put_u8(0),
+                }
+                route.put(&v.vni.to_be_bytes()[1..]);
+                if let Some(label2) = v.label2 {
+                    route.put(&label2.to_be_bytes()[1..]);
+                }
+            }
+            EvpnRoute::Multicast(v) => {
+                emit_rd(&mut route, &v.rd);
+                route.put_u32(v.ether_tag);
+                emit_evpn_addr(&mut route, &v.addr);
+            }
+            EvpnRoute::EthernetSegment(v) => {
+                emit_rd(&mut route, &v.rd);
+                v.esi.emit(&mut route);
+                emit_evpn_addr(&mut route, &v.addr);
+            }
+            EvpnRoute::Prefix(v) => {
+                emit_rd(&mut route, &v.rd);
+                v.esi.emit(&mut route);
+                route.put_u32(v.ether_tag);
+                route.put_u8(v.prefix.prefix_len());
+                match v.prefix.addr() {
+                    IpAddr::V4(addr) => route.put(&addr.octets()[..]),
+                    IpAddr::V6(addr) => route.put(&addr.octets()[..]),
+                }
+                match v.gateway {
+                    IpAddr::V4(addr) => route.put(&addr.octets()[..]),
+                    IpAddr::V6(addr) => route.put(&addr.octets()[..]),
+                }
+                route.put(&v.label.to_be_bytes()[1..]);
+            }
+            EvpnRoute::Smet(v) => {
+                emit_rd(&mut route, &v.rd);
+                route.put_u32(v.ether_tag);
+                v.mcast.emit(&mut route);
+                route.put_u8(v.flags);
+            }
+            EvpnRoute::JoinSync(v) => {
+                emit_rd(&mut route, &v.rd);
+                v.esi.emit(&mut route);
+                route.put_u32(v.ether_tag);
+                v.mcast.emit(&mut route);
+                route.put_u8(v.flags);
+            }
+            EvpnRoute::LeaveSync(v) => {
+                emit_rd(&mut route, &v.rd);
+                v.esi.emit(&mut route);
+                route.put_u32(v.ether_tag);
+                v.mcast.emit(&mut route);
+                route.put_u32(v.sync);
+                route.put_u8(v.max_response_time);
+                route.put_u8(v.flags);
+            }
+            EvpnRoute::Unknown { data, .. } => {
+                route.put(&data[..]);
+            }
+        }
+        buf.put_u8(typ.into());
+        buf.put_u8(route.len() as u8);
+        buf.put(&route[..]);
+    }
+}
+
+impl fmt::Display for EvpnRoute {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EvpnRoute::EthernetAd(v) => {
+                write!(
+                    f,
+                    "[1][{}] ESI:{}, Tag:{}, Label:{}",
+                    v.rd, v.esi, v.ether_tag, v.label
+                )
+            }
+            EvpnRoute::Mac(v) => {
+                write!(
+                    f,
+                    "[2][{}] VNI:{}, MAC:{:02x}{:02x}:{:02x}{:02x}:{:02x}{:02x}",
+                    v.rd, v.vni, v.mac[0], v.mac[1], v.mac[2], v.mac[3], v.mac[4], v.mac[5],
+                )?;
+                if let Some(ip) = v.ip {
+                    write!(f, ", IP:{}", ip)?;
+                }
+                if let Some(label2) = v.label2 {
+                    write!(f, ", L3VNI:{}", label2)?;
+                }
+                if !v.esi.is_zero() {
+                    write!(f, ", ESI:{}", v.esi)?;
+                }
+                Ok(())
+            }
+            EvpnRoute::Multicast(v) => {
+                write!(f, "[3][{}] {}:{}", v.rd, v.ether_tag, v.addr)
+            }
+            EvpnRoute::EthernetSegment(v) => {
+                write!(f, "[4][{}] ESI:{}, {}", v.rd, v.esi, v.addr)
+            }
+            EvpnRoute::Prefix(v) => {
+                write!(
+                    f,
+                    "[5][{}] {} via {}, Tag:{}, Label:{}",
+                    v.rd, v.prefix, v.gateway, v.ether_tag, v.label
+                )
+            }
+            EvpnRoute::Smet(v) => {
+                write!(f, "[6][{}] {}:{}", v.rd, v.ether_tag, v.mcast)
+            }
+            EvpnRoute::JoinSync(v) => {
+                write!(
+                    f,
+                    "[7][{}] ESI:{}, {}:{}",
+                    v.rd, v.esi, v.ether_tag, v.mcast
+                )
+            }
+            EvpnRoute::LeaveSync(v) => {
+                write!(
+                    f,
+                    "[8][{}] ESI:{}, {}:{}, Sync:{}",
+                    v.rd, v.esi, v.ether_tag, v.mcast, v.sync
+                )
+            }
+            EvpnRoute::Unknown { typ, data, .. } => {
+                write!(f, "[{}] Unknown length:{}", typ, data.len())
+            }
         }
     }
 }
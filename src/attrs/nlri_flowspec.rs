@@ -0,0 +1,739 @@
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::str::FromStr;
+
+use bytes::{BufMut, BytesMut};
+use ipnet::{IpNet, Ipv4Net, Ipv6Net};
+use nom::IResult;
+use nom::bytes::complete::take;
+use nom::error::{ErrorKind, make_error};
+use nom::number::complete::be_u8;
+use nom_derive::*;
+
+use crate::{Afi, EmitNlri, RouteDistinguisher, nlri_psize};
+
+// Flow Specification component types (RFC8955 4.2.2, RFC8956 3).
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FlowspecType {
+    DstPrefix = 1,
+    SrcPrefix = 2,
+    IpProto = 3,
+    Port = 4,
+    DstPort = 5,
+    SrcPort = 6,
+    IcmpType = 7,
+    IcmpCode = 8,
+    TcpFlags = 9,
+    PacketLength = 10,
+    Dscp = 11,
+    Fragment = 12,
+    FlowLabel = 13,
+}
+
+impl TryFrom<u8> for FlowspecType {
+    type Error = ();
+
+    fn try_from(val: u8) -> Result<Self, Self::Error> {
+        use FlowspecType::*;
+        let typ = match val {
+            1 => DstPrefix,
+            2 => SrcPrefix,
+            3 => IpProto,
+            4 => Port,
+            5 => DstPort,
+            6 => SrcPort,
+            7 => IcmpType,
+            8 => IcmpCode,
+            9 => TcpFlags,
+            10 => PacketLength,
+            11 => Dscp,
+            12 => Fragment,
+            13 => FlowLabel,
+            _ => return Err(()),
+        };
+        Ok(typ)
+    }
+}
+
+impl FlowspecType {
+    pub fn keyword(&self) -> &'static str {
+        use FlowspecType::*;
+        match self {
+            DstPrefix => "dst",
+            SrcPrefix => "src",
+            IpProto => "proto",
+            Port => "port",
+            DstPort => "dst-port",
+            SrcPort => "src-port",
+            IcmpType => "icmp-type",
+            IcmpCode => "icmp-code",
+            TcpFlags => "tcp-flags",
+            PacketLength => "pkt-len",
+            Dscp => "dscp",
+            Fragment => "fragment",
+            FlowLabel => "flow-label",
+        }
+    }
+
+    fn from_keyword(s: &str) -> Option<Self> {
+        (1..=13)
+            .filter_map(|v| FlowspecType::try_from(v).ok())
+            .find(|typ| typ.keyword() == s)
+    }
+}
+
+// Operator byte (RFC8955 4.2.1).
+const OP_END: u8 = 0x80;
+const OP_AND: u8 = 0x40;
+const OP_LEN: u8 = 0x30;
+const OP_LT: u8 = 0x04;
+const OP_GT: u8 = 0x02;
+const OP_EQ: u8 = 0x01;
+const OP_NOT: u8 = 0x02;
+const OP_MATCH: u8 = 0x01;
+
+// Numeric operator and value. `and` binds the term to the previous one.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct NumericOp {
+    pub and: bool,
+    pub lt: bool,
+    pub gt: bool,
+    pub eq: bool,
+    pub value: u64,
+}
+
+// Bitmask operator and value.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct BitmaskOp {
+    pub and: bool,
+    pub not: bool,
+    pub matched: bool,
+    pub value: u64,
+}
+
+// Operator and value without the end-of-list bit.
+fn parse_op(input: &[u8]) -> IResult<&[u8], (bool, u8, u64)> {
+    let (input, op) = be_u8(input)?;
+    let len = 1usize << ((op & OP_LEN) >> 4);
+    let (input, val) = take(len).parse(input)?;
+    let value = val.iter().fold(0u64, |acc, v| (acc << 8) | *v as u64);
+    Ok((input, (op & OP_END != 0, op, value)))
+}
+
+fn parse_ops<T>(input: &[u8], f: impl Fn(u8, u64) -> T) -> IResult<&[u8], Vec<T>> {
+    let mut input = input;
+    let mut ops = Vec::new();
+    loop {
+        let (remaining, (end, op, value)) = parse_op(input)?;
+        input = remaining;
+        ops.push(f(op, value));
+        if end {
+            return Ok((input, ops));
+        }
+    }
+}
+
+// Value is encoded in the shortest length.
+fn emit_op(buf: &mut BytesMut, op: u8, value: u64, end: bool) {
+    let (len, size) = match value {
+        0..=0xff => (0u8, 1usize),
+        0x100..=0xffff => (1, 2),
+        0x1_0000..=0xffff_ffff => (2, 4),
+        _ => (3, 8),
+    };
+    let op = op | (len << 4) | if end { OP_END } else { 0 };
+    buf.put_u8(op);
+    buf.put(&value.to_be_bytes()[8 - size..]);
+}
+
+impl NumericOp {
+    fn from_op(op: u8, value: u64) -> Self {
+        Self {
+            and: op & OP_AND != 0,
+            lt: op & OP_LT != 0,
+            gt: op & OP_GT != 0,
+            eq: op & OP_EQ != 0,
+            value,
+        }
+    }
+
+    fn op(&self) -> u8 {
+        let mut op = 0;
+        if self.and {
+            op |= OP_AND;
+        }
+        if self.lt {
+            op |= OP_LT;
+        }
+        if self.gt {
+            op |= OP_GT;
+        }
+        if self.eq {
+            op |= OP_EQ;
+        }
+        op
+    }
+
+    fn symbol(&self) -> &'static str {
+        match (self.lt, self.gt, self.eq) {
+            (false, false, false) => "false",
+            (false, false, true) => "=",
+            (false, true, false) => ">",
+            (false, true, true) => ">=",
+            (true, false, false) => "<",
+            (true, false, true) => "<=",
+            (true, true, false) => "!=",
+            (true, true, true) => "true",
+        }
+    }
+}
+
+impl BitmaskOp {
+    fn from_op(op: u8, value: u64) -> Self {
+        Self {
+            and: op & OP_AND != 0,
+            not: op & OP_NOT != 0,
+            matched: op & OP_MATCH != 0,
+            value,
+        }
+    }
+
+    fn op(&self) -> u8 {
+        let mut op = 0;
+        if self.and {
+            op |= OP_AND;
+        }
+        if self.not {
+            op |= OP_NOT;
+        }
+        if self.matched {
+            op |= OP_MATCH;
+        }
+        op
+    }
+
+    fn symbol(&self) -> &'static str {
+        match (self.not, self.matched) {
+            (false, false) => "",
+            (false, true) => "=",
+            (true, false) => "!",
+            (true, true) => "!=",
+        }
+    }
+}
+
+// Prefix component. `offset` is used only for IPv6 (RFC8956 3.1).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FlowspecPrefix {
+    pub prefix: IpNet,
+    pub offset: u8,
+}
+
+impl FlowspecPrefix {
+    fn parse_be(input: &[u8], afi: Afi) -> IResult<&[u8], Self> {
+        let (input, plen) = be_u8(input)?;
+        if afi == Afi::Ip6 {
+            let (input, offset) = be_u8(input)?;
+            if plen > 128 || offset > plen {
+                return Err(nom::Err::Error(make_error(input, ErrorKind::LengthValue)));
+            }
+            // Pattern is the bits from offset to prefix length.
+            let (input, pattern) = take(nlri_psize(plen - offset)).parse(input)?;
+            let mut octets = [0u8; 16];
+            octets[..pattern.len()].copy_from_slice(pattern);
+            // Offset can be 128 with empty pattern.
+            let addr = u128::from_be_bytes(octets)
+                .checked_shr(offset.into())
+                .unwrap_or(0);
+            let prefix = Ipv6Net::new(Ipv6Addr::from(addr), plen).expect("Ipv6Net create error");
+            let prefix = FlowspecPrefix {
+                prefix: IpNet::V6(prefix),
+                offset,
+            };
+            Ok((input, prefix))
+        } else {
+            if plen > 32 {
+                return Err(nom::Err::Error(make_error(input, ErrorKind::LengthValue)));
+            }
+            let (input, val) = take(nlri_psize(plen)).parse(input)?;
+            let mut octets = [0u8; 4];
+            octets[..val.len()].copy_from_slice(val);
+            let prefix = Ipv4Net::new(Ipv4Addr::from(octets), plen).expect("Ipv4Net create error");
+            let prefix = FlowspecPrefix {
+                prefix: IpNet::V4(prefix),
+                offset: 0,
+            };
+            Ok((input, prefix))
+        }
+    }
+
+    fn emit(&self, buf: &mut BytesMut) {
+        let plen = self.prefix.prefix_len();
+        buf.put_u8(plen);
+        match self.prefix.addr() {
+            IpAddr::V4(addr) => {
+                buf.put(&addr.octets()[..nlri_psize(plen)]);
+            }
+            IpAddr::V6(addr) => {
+                buf.put_u8(self.offset);
+                let pattern = u128::from(addr)
+                    .checked_shl(self.offset.into())
+                    .unwrap_or(0);
+                let psize = nlri_psize(plen - self.offset);
+                buf.put(&pattern.to_be_bytes()[..psize]);
+            }
+        }
+    }
+}
+
+impl fmt::Display for FlowspecPrefix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.prefix)?;
+        if self.offset != 0 {
+            write!(f, "@{}", self.offset)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for FlowspecPrefix {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (prefix, offset) = match s.split_once('@') {
+            Some((prefix, offset)) => (prefix, offset.parse::<u8>().map_err(|_| ())?),
+            None => (s, 0),
+        };
+        let prefix: IpNet = prefix.parse().map_err(|_| ())?;
+        if offset > prefix.prefix_len() || (offset != 0 && matches!(prefix, IpNet::V4(_))) {
+            return Err(());
+        }
+        Ok(Self { prefix, offset })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FlowspecComponent {
+    DstPrefix(FlowspecPrefix),
+    SrcPrefix(FlowspecPrefix),
+    // IP protocol for IPv4 and next header for IPv6.
+    IpProto(Vec<NumericOp>),
+    Port(Vec<NumericOp>),
+    DstPort(Vec<NumericOp>),
+    SrcPort(Vec<NumericOp>),
+    IcmpType(Vec<NumericOp>),
+    IcmpCode(Vec<NumericOp>),
+    TcpFlags(Vec<BitmaskOp>),
+    PacketLength(Vec<NumericOp>),
+    Dscp(Vec<NumericOp>),
+    Fragment(Vec<BitmaskOp>),
+    FlowLabel(Vec<NumericOp>),
+}
+
+// Fragment bitmask (RFC8955 4.2.2.12).
+pub const FLOWSPEC_FRAG_DONT: u64 = 0x01;
+pub const FLOWSPEC_FRAG_IS: u64 = 0x02;
+pub const FLOWSPEC_FRAG_FIRST: u64 = 0x04;
+pub const FLOWSPEC_FRAG_LAST: u64 = 0x08;
+
+impl FlowspecComponent {
+    pub fn typ(&self) -> FlowspecType {
+        use FlowspecComponent::*;
+        match self {
+            DstPrefix(_) => FlowspecType::DstPrefix,
+            SrcPrefix(_) => FlowspecType::SrcPrefix,
+            IpProto(_) => FlowspecType::IpProto,
+            Port(_) => FlowspecType::Port,
+            DstPort(_) => FlowspecType::DstPort,
+            SrcPort(_) => FlowspecType::SrcPort,
+            IcmpType(_) => FlowspecType::IcmpType,
+            IcmpCode(_) => FlowspecType::IcmpCode,
+            TcpFlags(_) => FlowspecType::TcpFlags,
+            PacketLength(_) => FlowspecType::PacketLength,
+            Dscp(_) => FlowspecType::Dscp,
+            Fragment(_) => FlowspecType::Fragment,
+            FlowLabel(_) => FlowspecType::FlowLabel,
+        }
+    }
+
+    fn parse_be(input: &[u8], afi: Afi) -> IResult<&[u8], Self> {
+        use FlowspecComponent::*;
+        let (input, typ) = be_u8(input)?;
+        let Ok(typ) = FlowspecType::try_from(typ) else {
+            return Err(nom::Err::Error(make_error(input, ErrorKind::NoneOf)));
+        };
+        let numeric = |input| parse_ops(input, NumericOp::from_op);
+        let bitmask = |input| parse_ops(input, BitmaskOp::from_op);
+        let (input, component) = match typ {
+            FlowspecType::DstPrefix => {
+                let (input, prefix) = FlowspecPrefix::parse_be(input, afi)?;
+                (input, DstPrefix(prefix))
+            }
+            FlowspecType::SrcPrefix => {
+                let (input, prefix) = FlowspecPrefix::parse_be(input, afi)?;
+                (input, SrcPrefix(prefix))
+            }
+            FlowspecType::IpProto => numeric(input).map(|(i, v)| (i, IpProto(v)))?,
+            FlowspecType::Port => numeric(input).map(|(i, v)| (i, Port(v)))?,
+            FlowspecType::DstPort => numeric(input).map(|(i, v)| (i, DstPort(v)))?,
+            FlowspecType::SrcPort => numeric(input).map(|(i, v)| (i, SrcPort(v)))?,
+            FlowspecType::IcmpType => numeric(input).map(|(i, v)| (i, IcmpType(v)))?,
+            FlowspecType::IcmpCode => numeric(input).map(|(i, v)| (i, IcmpCode(v)))?,
+            FlowspecType::TcpFlags => bitmask(input).map(|(i, v)| (i, TcpFlags(v)))?,
+            FlowspecType::PacketLength => numeric(input).map(|(i, v)| (i, PacketLength(v)))?,
+            FlowspecType::Dscp => numeric(input).map(|(i, v)| (i, Dscp(v)))?,
+            FlowspecType::Fragment => bitmask(input).map(|(i, v)| (i, Fragment(v)))?,
+            FlowspecType::FlowLabel => numeric(input).map(|(i, v)| (i, FlowLabel(v)))?,
+        };
+        Ok((input, component))
+    }
+
+    fn emit(&self, buf: &mut BytesMut) {
+        use FlowspecComponent::*;
+        buf.put_u8(self.typ() as u8);
+        match self {
+            DstPrefix(v) | SrcPrefix(v) => v.emit(buf),
+            IpProto(ops) | Port(ops) | DstPort(ops) | SrcPort(ops) | IcmpType(ops)
+            | IcmpCode(ops) | PacketLength(ops) | Dscp(ops) | FlowLabel(ops) => {
+                for (i, op) in ops.iter().enumerate() {
+                    emit_op(buf, op.op(), op.value, i + 1 == ops.len());
+                }
+            }
+            TcpFlags(ops) | Fragment(ops) => {
+                for (i, op) in ops.iter().enumerate() {
+                    emit_op(buf, op.op(), op.value, i + 1 == ops.len());
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for FlowspecComponent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use FlowspecComponent::*;
+        write!(f, "{} ", self.typ().keyword())?;
+        match self {
+            DstPrefix(v) | SrcPrefix(v) => write!(f, "{}", v),
+            IpProto(ops) | Port(ops) | DstPort(ops) | SrcPort(ops) | IcmpType(ops)
+            | IcmpCode(ops) | PacketLength(ops) | Dscp(ops) | FlowLabel(ops) => {
+                for (i, op) in ops.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, "{}", if op.and { "&" } else { "|" })?;
+                    }
+                    write!(f, "{}{}", op.symbol(), op.value)?;
+                }
+                Ok(())
+            }
+            TcpFlags(ops) | Fragment(ops) => {
+                for (i, op) in ops.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, "{}", if op.and { "&" } else { "|" })?;
+                    }
+                    write!(f, "{}0x{:02x}", op.symbol(), op.value)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+// Split operator terms by "&" and "|". Returns AND flag, operator symbol
+// and value of each term.
+fn split_terms(s: &str, symbols: &[&'static str]) -> Result<Vec<(bool, &'static str, u64)>, ()> {
+    let mut terms = Vec::new();
+    let mut and = false;
+    let mut rest = s;
+    loop {
+        let end = rest.find(['&', '|']).unwrap_or(rest.len());
+        let term = &rest[..end];
+        let symbol = symbols
+            .iter()
+            .find(|symbol| term.starts_with(**symbol))
+            .ok_or(())?;
+        let value = &term[symbol.len()..];
+        let value = match value.strip_prefix("0x") {
+            Some(hex) => u64::from_str_radix(hex, 16),
+            None => value.parse::<u64>(),
+        }
+        .map_err(|_| ())?;
+        terms.push((and, *symbol, value));
+        if end == rest.len() {
+            return Ok(terms);
+        }
+        and = rest[end..].starts_with('&');
+        rest = &rest[end + 1..];
+    }
+}
+
+fn numeric_from_str(s: &str) -> Result<Vec<NumericOp>, ()> {
+    // Longer symbols first.
+    let symbols = ["false", "true", ">=", "<=", "!=", "=", ">", "<"];
+    let terms = split_terms(s, &symbols)?;
+    let ops = terms
+        .into_iter()
+        .map(|(and, symbol, value)| {
+            let (lt, gt, eq) = match symbol {
+                "false" => (false, false, false),
+                "true" => (true, true, true),
+                ">=" => (false, true, true),
+                "<=" => (true, false, true),
+                "!=" => (true, true, false),
+                "=" => (false, false, true),
+                ">" => (false, true, false),
+                _ => (true, false, false),
+            };
+            NumericOp {
+                and,
+                lt,
+                gt,
+                eq,
+                value,
+            }
+        })
+        .collect();
+    Ok(ops)
+}
+
+fn bitmask_from_str(s: &str) -> Result<Vec<BitmaskOp>, ()> {
+    let symbols = ["!=", "!", "=", ""];
+    let terms = split_terms(s, &symbols)?;
+    let ops = terms
+        .into_iter()
+        .map(|(and, symbol, value)| BitmaskOp {
+            and,
+            not: symbol.starts_with('!'),
+            matched: symbol.ends_with('='),
+            value,
+        })
+        .collect();
+    Ok(ops)
+}
+
+// Flow Specification NLRI. Components are in ascending order of the type.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FlowspecNlri {
+    pub components: Vec<FlowspecComponent>,
+}
+
+// NLRI length is 1 octet when it is less than 240, otherwise 2 octets with
+// 0xf in the first nibble (RFC8955 4.1).
+fn parse_flowspec_len(input: &[u8]) -> IResult<&[u8], usize> {
+    let (input, len) = be_u8(input)?;
+    if len < 0xf0 {
+        return Ok((input, len as usize));
+    }
+    let (input, low) = be_u8(input)?;
+    Ok((input, (((len & 0x0f) as usize) << 8) | low as usize))
+}
+
+// NLRI longer than 4095 octets can not be encoded so it is not emitted.
+fn emit_flowspec(buf: &mut BytesMut, nlri: &[u8]) {
+    let len = nlri.len();
+    if len < 0xf0 {
+        buf.put_u8(len as u8);
+    } else if len <= 0xfff {
+        buf.put_u16(0xf000 | len as u16);
+    } else {
+        return;
+    }
+    buf.put(nlri);
+}
+
+impl FlowspecNlri {
+    fn parse_components(input: &[u8], afi: Afi) -> IResult<&[u8], Self> {
+        let mut input = input;
+        let mut nlri = FlowspecNlri::default();
+        while !input.is_empty() {
+            let (remaining, component) = FlowspecComponent::parse_be(input, afi)?;
+            // Components must be in strictly ascending order.
+            if let Some(last) = nlri.components.last()
+                && last.typ() >= component.typ()
+            {
+                return Err(nom::Err::Error(make_error(input, ErrorKind::Verify)));
+            }
+            nlri.components.push(component);
+            input = remaining;
+        }
+        Ok((input, nlri))
+    }
+
+    pub fn parse_nlri(input: &[u8], afi: Afi) -> IResult<&[u8], Self> {
+        let (input, len) = parse_flowspec_len(input)?;
+        let (input, nlri) = take(len).parse(input)?;
+        let (_, nlri) = Self::parse_components(nlri, afi)?;
+        Ok((input, nlri))
+    }
+
+    fn emit_components(&self, buf: &mut BytesMut) {
+        for component in self.components.iter() {
+            component.emit(buf);
+        }
+    }
+}
+
+impl EmitNlri for FlowspecNlri {
+    fn emit_nlri(&self, buf: &mut BytesMut) {
+        let mut nlri = BytesMut::new();
+        self.emit_components(&mut nlri);
+        emit_flowspec(buf, &nlri);
+    }
+}
+
+impl fmt::Display for FlowspecNlri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, component) in self.components.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}", component)?;
+        }
+        Ok(())
+    }
+}
+
+// Keyword and value pairs, for example:
+// dst 10.0.0.0/24 proto =6 dst-port =80|=443 tcp-flags =0x02
+impl FromStr for FlowspecNlri {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use FlowspecComponent::*;
+        let mut nlri = FlowspecNlri::default();
+        let mut tokens = s.split_whitespace();
+        while let Some(keyword) = tokens.next() {
+            let typ = FlowspecType::from_keyword(keyword).ok_or(())?;
+            let value = tokens.next().ok_or(())?;
+            let component = match typ {
+                FlowspecType::DstPrefix => DstPrefix(value.parse()?),
+                FlowspecType::SrcPrefix => SrcPrefix(value.parse()?),
+                FlowspecType::IpProto => IpProto(numeric_from_str(value)?),
+                FlowspecType::Port => Port(numeric_from_str(value)?),
+                FlowspecType::DstPort => DstPort(numeric_from_str(value)?),
+                FlowspecType::SrcPort => SrcPort(numeric_from_str(value)?),
+                FlowspecType::IcmpType => IcmpType(numeric_from_str(value)?),
+                FlowspecType::IcmpCode => IcmpCode(numeric_from_str(value)?),
+                FlowspecType::TcpFlags => TcpFlags(bitmask_from_str(value)?),
+                FlowspecType::PacketLength => PacketLength(numeric_from_str(value)?),
+                FlowspecType::Dscp => Dscp(numeric_from_str(value)?),
+                FlowspecType::Fragment => Fragment(bitmask_from_str(value)?),
+                FlowspecType::FlowLabel => FlowLabel(numeric_from_str(value)?),
+            };
+            if nlri.components.iter().any(|c| c.typ() == typ) {
+                return Err(());
+            }
+            nlri.components.push(component);
+        }
+        nlri.components.sort_by_key(|c| c.typ());
+        Ok(nlri)
+    }
+}
+
+// VPN Flow Specification NLRI (RFC8955 8). RD is included in the NLRI
+// length.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlowspecVpnNlri {
+    pub rd: RouteDistinguisher,
+    pub nlri: FlowspecNlri,
+}
+
+impl FlowspecVpnNlri {
+    pub fn parse_nlri(input: &[u8], afi: Afi) -> IResult<&[u8], Self> {
+        let (input, len) = parse_flowspec_len(input)?;
+        let (input, nlri) = take(len).parse(input)?;
+        let (nlri, rd) = RouteDistinguisher::parse_be(nlri)?;
+        let (_, nlri) = FlowspecNlri::parse_components(nlri, afi)?;
+        Ok((input, Self { rd, nlri }))
+    }
+}
+
+impl EmitNlri for FlowspecVpnNlri {
+    fn emit_nlri(&self, buf: &mut BytesMut) {
+        let mut nlri = BytesMut::new();
+        nlri.put_u16(self.rd.typ as u16);
+        nlri.put(&self.rd.val[..]);
+        self.nlri.emit_components(&mut nlri);
+        emit_flowspec(buf, &nlri);
+    }
+}
+
+impl fmt::Display for FlowspecVpnNlri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}", self.rd, self.nlri)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+
+    #[test]
+    fn flowspec_roundtrip() {
+        // RFC8955 4.2.3: Destination 10.0.1.0/24, protocol TCP and
+        // destination port 80 or 8080.
+        let input = hex!("0e 01 18 0a0001 03 81 06 05 01 50 91 1f90");
+        let (rest, nlri) = FlowspecNlri::parse_nlri(&input, Afi::Ip).unwrap();
+        assert!(rest.is_empty());
+        assert_eq!(
+            nlri.to_string(),
+            "dst 10.0.1.0/24 proto =6 dst-port =80|=8080"
+        );
+        let mut buf = BytesMut::new();
+        nlri.emit_nlri(&mut buf);
+        assert_eq!(&buf[..], &input[..]);
+
+        let parsed: FlowspecNlri = "dst-port =80|=8080 proto =6 dst 10.0.1.0/24"
+            .parse()
+            .unwrap();
+        assert_eq!(parsed, nlri);
+
+        // IPv6 destination 2001:db8::/64, source with 16 bits offset,
+        // packet length >= 1000 and <= 2000, TCP SYN without ACK.
+        let nlri: FlowspecNlri =
+            "dst 2001:db8::/64 src 0:db8:1::/48@16 pkt-len >=1000&<=2000 tcp-flags =0x02&!0x10"
+                .parse()
+                .unwrap();
+        let mut buf = BytesMut::new();
+        nlri.emit_nlri(&mut buf);
+        let (_, parsed) = FlowspecNlri::parse_nlri(&buf, Afi::Ip6).unwrap();
+        assert_eq!(parsed, nlri);
+        assert_eq!(
+            parsed.to_string(),
+            "dst 2001:db8::/64 src 0:db8:1::/48@16 tcp-flags =0x02&!0x10 pkt-len >=1000&<=2000"
+        );
+
+        let vpn = FlowspecVpnNlri {
+            rd: "65000:100".parse().unwrap(),
+            nlri,
+        };
+        let mut buf = BytesMut::new();
+        vpn.emit_nlri(&mut buf);
+        let (_, parsed) = FlowspecVpnNlri::parse_nlri(&buf, Afi::Ip6).unwrap();
+        assert_eq!(parsed, vpn);
+    }
+
+    #[test]
+    fn flowspec_prefix_offset() {
+        // IPv6 prefix length 128 with offset 128 has empty pattern.
+        let input = hex!("03 01 80 80");
+        let (_, nlri) = FlowspecNlri::parse_nlri(&input, Afi::Ip6).unwrap();
+        assert_eq!(nlri.to_string(), "dst ::/128@128");
+        let mut buf = BytesMut::new();
+        nlri.emit_nlri(&mut buf);
+        assert_eq!(&buf[..], &input[..]);
+
+        let parsed: FlowspecNlri = "dst ::/128@128".parse().unwrap();
+        assert_eq!(parsed, nlri);
+    }
+
+    #[test]
+    fn flowspec_length_limit() {
+        // NLRI longer than 4095 octets is not emitted.
+        let ports = vec!["=1000"; 2000].join("|");
+        let nlri: FlowspecNlri = format!("port {}", ports).parse().unwrap();
+        let mut buf = BytesMut::new();
+        nlri.emit_nlri(&mut buf);
+        assert!(buf.is_empty());
+    }
+}
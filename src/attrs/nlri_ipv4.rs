@@ -1,5 +1,6 @@
 use std::net::Ipv4Addr;
 
+use bytes::{BufMut, BytesMut};
 use ipnet::Ipv4Net;
 use nom::IResult;
 use nom::bytes::complete::take;
@@ -7,7 +8,7 @@ use nom::error::{ErrorKind, make_error};
 use nom::number::complete::{be_u8, be_u32};
 use nom_derive::*;
 
-use crate::{ParseNlri, many0, nlri_psize};
+use crate::{EmitNlri, ParseNlri, many0, nlri_psize};
 
 #[derive(Debug, Clone)]
 pub struct Ipv4Nlri {
@@ -19,6 +20,9 @@ impl ParseNlri<Ipv4Nlri> for Ipv4Nlri {
     fn parse_nlri(input: &[u8], add_path: bool) -> IResult<&[u8], Ipv4Nlri> {
         let (input, id) = if add_path { be_u32(input)? } else { (input, 0) };
         let (input, plen) = be_u8(input)?;
+        if plen > 32 {
+            return Err(nom::Err::Error(make_error(input, ErrorKind::Verify)));
+        }
         let psize = nlri_psize(plen);
         if input.len() < psize {
             return Err(nom::Err::Error(make_error(input, ErrorKind::Eof)));
@@ -32,11 +36,25 @@ impl ParseNlri<Ipv4Nlri> for Ipv4Nlri {
     }
 }
 
+impl EmitNlri for Ipv4Nlri {
+    fn emit_nlri(&self, buf: &mut BytesMut) {
+        if self.id != 0 {
+            buf.put_u32(self.id);
+        }
+        buf.put_u8(self.prefix.prefix_len());
+        let psize = nlri_psize(self.prefix.prefix_len());
+        buf.put(&self.prefix.addr().octets()[0..psize]);
+    }
+}
+
 pub fn parse_bgp_nlri_ipv4(
     input: &[u8],
     length: u16,
     add_path: bool,
 ) -> IResult<&[u8], Vec<Ipv4Nlri>> {
+    if input.len() < length as usize {
+        return Err(nom::Err::Error(make_error(input, ErrorKind::Eof)));
+    }
     let (nlri, input) = input.split_at(length as usize);
     let (_, nlris) = many0(|i| Ipv4Nlri::parse_nlri(i, add_path)).parse(nlri)?;
     Ok((input, nlris))
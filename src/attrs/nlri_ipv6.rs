@@ -1,5 +1,6 @@
 use std::net::Ipv6Addr;
 
+use bytes::{BufMut, BytesMut};
 use ipnet::Ipv6Net;
 use nom::IResult;
 use nom::bytes::complete::take;
@@ -7,7 +8,7 @@ use nom::error::{ErrorKind, make_error};
 use nom::number::complete::{be_u8, be_u32};
 use nom_derive::*;
 
-use crate::{ParseBe, ParseNlri, nlri_psize};
+use crate::{EmitNlri, ParseBe, ParseNlri, nlri_psize};
 
 #[derive(Debug, Clone)]
 pub struct Ipv6Nlri {
@@ -32,6 +33,17 @@ impl ParseNlri<Ipv6Nlri> for Ipv6Nlri {
     }
 }
 
+impl EmitNlri for Ipv6Nlri {
+    fn emit_nlri(&self, buf: &mut BytesMut) {
+        if self.id != 0 {
+            buf.put_u32(self.id);
+        }
+        buf.put_u8(self.prefix.prefix_len());
+        let psize = nlri_psize(self.prefix.prefix_len());
+        buf.put(&self.prefix.addr().octets()[0..psize]);
+    }
+}
+
 impl ParseBe<Ipv6Net> for Ipv6Net {
     fn parse_be(input: &[u8]) -> IResult<&[u8], Ipv6Net> {
         let (input, plen) = be_u8(input)?;
@@ -0,0 +1,291 @@
+use std::fmt;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use bytes::{BufMut, BytesMut};
+use ipnet::{Ipv4Net, Ipv6Net};
+use nom::IResult;
+use nom::bytes::complete::take;
+use nom::error::{ErrorKind, make_error};
+use nom::number::complete::{be_u8, be_u32};
+use nom_derive::*;
+
+use crate::{EmitNlri, Ipv4Nlri, Ipv6Nlri, Label, ParseNlri, nlri_psize};
+
+// Labeled unicast NLRI (RFC8277). Labels are followed by the prefix and the
+// prefix length includes 24 bits for each label.
+
+// Parse label stack up to `max` labels. RFC8277 2.2: The stack ends with the
+// label which has Bottom of Stack bit. The withdraw label 0x800000 also ends
+// the stack.
+fn parse_labels(input: &[u8], max: usize) -> IResult<&[u8], Vec<Label>> {
+    let mut input = input;
+    let mut labels = Vec::new();
+    while labels.len() < max {
+        let (remaining, label) = take(3usize).parse(input)?;
+        input = remaining;
+        let label = Label::from(label);
+        labels.push(label);
+        if label.bos || label.is_withdraw() {
+            return Ok((input, labels));
+        }
+    }
+    Err(nom::Err::Error(make_error(input, ErrorKind::LengthValue)))
+}
+
+// Path Identifier, prefix length and labels. `withdraw` has single label
+// field (RFC8277 2.4). The number of labels is limited by the prefix length
+// and `count`. Returns the prefix length without labels.
+fn parse_header(
+    input: &[u8],
+    add_path: bool,
+    withdraw: bool,
+    count: usize,
+) -> IResult<&[u8], (u32, u8, Vec<Label>)> {
+    let (input, id) = if add_path { be_u32(input)? } else { (input, 0) };
+    let (input, plen) = be_u8(input)?;
+    if input.len() < nlri_psize(plen) {
+        return Err(nom::Err::Error(make_error(input, ErrorKind::Eof)));
+    }
+    let (input, labels) = if withdraw {
+        let (input, label) = take(3usize).parse(input)?;
+        (input, vec![Label::from(label)])
+    } else {
+        parse_labels(input, count.min(plen as usize / 24))?
+    };
+    let Some(plen) = (plen as usize).checked_sub(labels.len() * 24) else {
+        return Err(nom::Err::Error(make_error(input, ErrorKind::LengthValue)));
+    };
+    Ok((input, (id, plen as u8, labels)))
+}
+
+// Labels which do not fit in the prefix length are not encoded.
+fn emit_header(buf: &mut BytesMut, id: u32, plen: u8, labels: &[Label]) {
+    if id != 0 {
+        buf.put_u32(id);
+    }
+    let count = labels.len().min((u8::MAX - plen) as usize / 24);
+    buf.put_u8(plen + count as u8 * 24);
+    for label in labels[..count].iter() {
+        buf.put(&label.to_bytes()[..]);
+    }
+}
+
+// RFC8277 2.1: Number of labels sent is limited by Multiple Labels
+// capability. At least one label is kept and the last one has Bottom of
+// Stack bit.
+fn truncate_labels(labels: &mut Vec<Label>, count: u8) {
+    if labels.len() > count as usize {
+        labels.truncate(count.max(1) as usize);
+        if let Some(label) = labels.last_mut() {
+            label.bos = true;
+        }
+    }
+}
+
+fn fmt_labels(f: &mut fmt::Formatter<'_>, labels: &[Label]) -> fmt::Result {
+    for (i, label) in labels.iter().enumerate() {
+        if i > 0 {
+            write!(f, "/")?;
+        }
+        write!(f, "{}", label.label)?;
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone)]
+pub struct LabeledIpv4Nlri {
+    pub labels: Vec<Label>,
+    pub nlri: Ipv4Nlri,
+}
+
+impl LabeledIpv4Nlri {
+    fn parse(input: &[u8], add_path: bool, withdraw: bool, count: usize) -> IResult<&[u8], Self> {
+        let (input, (id, plen, labels)) = parse_header(input, add_path, withdraw, count)?;
+        if plen > 32 {
+            return Err(nom::Err::Error(make_error(input, ErrorKind::LengthValue)));
+        }
+        let psize = nlri_psize(plen);
+        if input.len() < psize {
+            return Err(nom::Err::Error(make_error(input, ErrorKind::Eof)));
+        }
+        let mut paddr = [0u8; 4];
+        paddr[..psize].copy_from_slice(&input[..psize]);
+        let (input, _) = take(psize).parse(input)?;
+        let prefix = Ipv4Net::new(Ipv4Addr::from(paddr), plen).expect("Ipv4Net create error");
+        let nlri = Ipv4Nlri { id, prefix };
+        Ok((input, Self { labels, nlri }))
+    }
+
+    // NLRI with up to `count` labels which is negotiated by Multiple Labels
+    // capability.
+    pub fn parse_labeled(input: &[u8], add_path: bool, count: u8) -> IResult<&[u8], Self> {
+        Self::parse(input, add_path, false, count.into())
+    }
+
+    // Limit labels to `count` which is negotiated by Multiple Labels
+    // capability.
+    pub fn truncate_labels(&mut self, count: u8) {
+        truncate_labels(&mut self.labels, count);
+    }
+
+    // NLRI in MP_UNREACH_NLRI.
+    pub fn parse_withdraw(input: &[u8], add_path: bool) -> IResult<&[u8], Self> {
+        Self::parse(input, add_path, true, 1)
+    }
+
+    // Same prefix with the withdraw label.
+    pub fn to_withdraw(&self) -> Self {
+        Self {
+            labels: vec![Label::withdraw()],
+            nlri: self.nlri.clone(),
+        }
+    }
+}
+
+impl ParseNlri<LabeledIpv4Nlri> for LabeledIpv4Nlri {
+    fn parse_nlri(input: &[u8], add_path: bool) -> IResult<&[u8], LabeledIpv4Nlri> {
+        Self::parse(input, add_path, false, usize::MAX)
+    }
+}
+
+impl EmitNlri for LabeledIpv4Nlri {
+    fn emit_nlri(&self, buf: &mut BytesMut) {
+        let plen = self.nlri.prefix.prefix_len();
+        emit_header(buf, self.nlri.id, plen, &self.labels);
+        buf.put(&self.nlri.prefix.addr().octets()[0..nlri_psize(plen)]);
+    }
+}
+
+impl fmt::Display for LabeledIpv4Nlri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{} label: ", self.nlri.id, self.nlri.prefix)?;
+        fmt_labels(f, &self.labels)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LabeledIpv6Nlri {
+    pub labels: Vec<Label>,
+    pub nlri: Ipv6Nlri,
+}
+
+impl LabeledIpv6Nlri {
+    fn parse(input: &[u8], add_path: bool, withdraw: bool, count: usize) -> IResult<&[u8], Self> {
+        let (input, (id, plen, labels)) = parse_header(input, add_path, withdraw, count)?;
+        if plen > 128 {
+            return Err(nom::Err::Error(make_error(input, ErrorKind::LengthValue)));
+        }
+        let psize = nlri_psize(plen);
+        if input.len() < psize {
+            return Err(nom::Err::Error(make_error(input, ErrorKind::Eof)));
+        }
+        let mut paddr = [0u8; 16];
+        paddr[..psize].copy_from_slice(&input[..psize]);
+        let (input, _) = take(psize).parse(input)?;
+        let prefix = Ipv6Net::new(Ipv6Addr::from(paddr), plen).expect("Ipv6Net create error");
+        let nlri = Ipv6Nlri { id, prefix };
+        Ok((input, Self { labels, nlri }))
+    }
+
+    // NLRI with up to `count` labels which is negotiated by Multiple Labels
+    // capability.
+    pub fn parse_labeled(input: &[u8], add_path: bool, count: u8) -> IResult<&[u8], Self> {
+        Self::parse(input, add_path, false, count.into())
+    }
+
+    // Limit labels to `count` which is negotiated by Multiple Labels
+    // capability.
+    pub fn truncate_labels(&mut self, count: u8) {
+        truncate_labels(&mut self.labels, count);
+    }
+
+    // NLRI in MP_UNREACH_NLRI.
+    pub fn parse_withdraw(input: &[u8], add_path: bool) -> IResult<&[u8], Self> {
+        Self::parse(input, add_path, true, 1)
+    }
+
+    // Same prefix with the withdraw label.
+    pub fn to_withdraw(&self) -> Self {
+        Self {
+            labels: vec![Label::withdraw()],
+            nlri: self.nlri.clone(),
+        }
+    }
+}
+
+impl ParseNlri<LabeledIpv6Nlri> for LabeledIpv6Nlri {
+    fn parse_nlri(input: &[u8], add_path: bool) -> IResult<&[u8], LabeledIpv6Nlri> {
+        Self::parse(input, add_path, false, usize::MAX)
+    }
+}
+
+impl EmitNlri for LabeledIpv6Nlri {
+    fn emit_nlri(&self, buf: &mut BytesMut) {
+        let plen = self.nlri.prefix.prefix_len();
+        emit_header(buf, self.nlri.id, plen, &self.labels);
+        buf.put(&self.nlri.prefix.addr().octets()[0..nlri_psize(plen)]);
+    }
+}
+
+impl fmt::Display for LabeledIpv6Nlri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{} label: ", self.nlri.id, self.nlri.prefix)?;
+        fmt_labels(f, &self.labels)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+
+    #[test]
+    fn label_stack() {
+        // Two labels 16 and 17, 10.0.0.0/24.
+        let input = hex!("48 000100 000111 0a0000");
+        let (rest, nlri) = LabeledIpv4Nlri::parse_nlri(&input, false).unwrap();
+        assert!(rest.is_empty());
+        assert_eq!(nlri.labels.len(), 2);
+        assert_eq!(nlri.labels[1].label, 17);
+        assert_eq!(nlri.nlri.prefix.to_string(), "10.0.0.0/24");
+
+        let mut buf = BytesMut::new();
+        nlri.emit_nlri(&mut buf);
+        assert_eq!(&buf[..], &input[..]);
+
+        // Withdraw has a single label field.
+        let mut buf = BytesMut::new();
+        nlri.to_withdraw().emit_nlri(&mut buf);
+        assert_eq!(&buf[..], &hex!("30 800000 0a0000"));
+        let (_, withdraw) = LabeledIpv4Nlri::parse_withdraw(&buf, false).unwrap();
+        assert!(withdraw.labels[0].is_withdraw());
+        assert_eq!(withdraw.nlri.prefix, nlri.nlri.prefix);
+
+        // Negotiated number of labels.
+        assert!(LabeledIpv4Nlri::parse_labeled(&input, false, 1).is_err());
+        assert!(LabeledIpv4Nlri::parse_labeled(&input, false, 2).is_ok());
+    }
+
+    #[test]
+    fn label_stack_overflow() {
+        // 11 labels without Bottom of Stack exceed the prefix length.
+        let mut input = vec![0xff];
+        for _ in 0..11 {
+            input.extend_from_slice(&hex!("000100"));
+        }
+        assert!(LabeledIpv4Nlri::parse_nlri(&input, false).is_err());
+
+        // Only 10 labels fit in the prefix length.
+        let nlri = LabeledIpv4Nlri {
+            labels: vec![Label::from(&hex!("000100")[..]); 11],
+            nlri: Ipv4Nlri {
+                id: 0,
+                prefix: "0.0.0.0/0".parse().unwrap(),
+            },
+        };
+        let mut buf = BytesMut::new();
+        nlri.emit_nlri(&mut buf);
+        assert_eq!(buf[0], 240);
+        assert_eq!(buf.len(), 31);
+    }
+}
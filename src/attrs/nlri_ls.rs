@@ -0,0 +1,803 @@
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use bytes::{BufMut, BytesMut};
+use ipnet::{IpNet, Ipv4Net, Ipv6Net};
+use nom::IResult;
+use nom::bytes::complete::take;
+use nom::error::{ErrorKind, make_error};
+use nom::number::complete::{be_u8, be_u16, be_u64};
+use nom_derive::*;
+
+use crate::{EmitNlri, nlri_psize};
+
+// BGP-LS NLRI types (RFC9552 5.2, RFC9514 6).
+const NLRI_NODE: u16 = 1;
+const NLRI_LINK: u16 = 2;
+const NLRI_IPV4_PREFIX: u16 = 3;
+const NLRI_IPV6_PREFIX: u16 = 4;
+const NLRI_SRV6_SID: u16 = 6;
+
+// Node descriptor TLVs.
+const LOCAL_NODE: u16 = 256;
+const REMOTE_NODE: u16 = 257;
+const AS_NUMBER: u16 = 512;
+const BGP_LS_ID: u16 = 513;
+const OSPF_AREA_ID: u16 = 514;
+const IGP_ROUTER_ID: u16 = 515;
+const BGP_ROUTER_ID: u16 = 516;
+const MEMBER_ASN: u16 = 517;
+
+// Link descriptor TLVs.
+const LINK_ID: u16 = 258;
+const IPV4_INTF_ADDR: u16 = 259;
+const IPV4_NEIGH_ADDR: u16 = 260;
+const IPV6_INTF_ADDR: u16 = 261;
+const IPV6_NEIGH_ADDR: u16 = 262;
+const MT_ID: u16 = 263;
+
+// Prefix descriptor TLVs.
+const OSPF_ROUTE_TYPE: u16 = 264;
+const IP_REACH: u16 = 265;
+
+// SRv6 SID descriptor TLV.
+const SRV6_SID_INFO: u16 = 518;
+
+type TlvError<'a> = nom::Err<nom::error::Error<&'a [u8]>>;
+
+// TLV with 2 octets type and 2 octets length which is used in both NLRI and
+// BGP-LS Attribute. Unknown TLVs are kept as is.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LsTlv {
+    pub typ: u16,
+    pub value: Vec<u8>,
+}
+
+impl LsTlv {
+    pub fn new(typ: u16, value: &[u8]) -> Self {
+        Self {
+            typ,
+            value: value.to_vec(),
+        }
+    }
+
+    pub fn emit(&self, buf: &mut BytesMut) {
+        emit_ls_tlv(buf, self.typ, |buf| buf.put(&self.value[..]));
+    }
+}
+
+impl fmt::Display for LsTlv {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "tlv {} 0x", self.typ)?;
+        for v in self.value.iter() {
+            write!(f, "{:02x}", v)?;
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn parse_ls_tlv(input: &[u8]) -> IResult<&[u8], (u16, &[u8])> {
+    let (input, typ) = be_u16(input)?;
+    let (input, len) = be_u16(input)?;
+    let (input, value) = take(len).parse(input)?;
+    Ok((input, (typ, value)))
+}
+
+// Type and length followed by the value emitted by `f`.
+pub(crate) fn emit_ls_tlv(buf: &mut BytesMut, typ: u16, f: impl FnOnce(&mut BytesMut)) {
+    buf.put_u16(typ);
+    let pos = buf.len();
+    buf.put_u16(0);
+    f(buf);
+    let len = (buf.len() - pos - 2) as u16;
+    buf[pos..pos + 2].copy_from_slice(&len.to_be_bytes());
+}
+
+// RFC9552 5.1: TLVs in the NLRI are ordered by the type. Known TLVs emitted
+// by `f` are merged with `unknown` TLVs.
+fn emit_ls_sorted(buf: &mut BytesMut, unknown: &[LsTlv], f: impl FnOnce(&mut BytesMut)) {
+    let mut known = BytesMut::new();
+    f(&mut known);
+    let mut tlvs = Vec::new();
+    let mut input = &known[..];
+    while let Ok((remaining, tlv)) = parse_ls_tlv(input) {
+        tlvs.push(tlv);
+        input = remaining;
+    }
+    tlvs.extend(unknown.iter().map(|tlv| (tlv.typ, &tlv.value[..])));
+    tlvs.sort_by_key(|(typ, _)| *typ);
+    for (typ, value) in tlvs.into_iter() {
+        emit_ls_tlv(buf, typ, |buf| buf.put(value));
+    }
+}
+
+// Value of the fixed length TLV.
+pub(crate) fn ls_fixed<const N: usize>(value: &[u8]) -> Result<[u8; N], TlvError<'_>> {
+    value
+        .try_into()
+        .map_err(|_| nom::Err::Error(make_error(value, ErrorKind::LengthValue)))
+}
+
+// Multi-Topology ID list. Each entry is 2 octets.
+fn parse_mt_id(value: &[u8]) -> Result<Vec<u16>, TlvError<'_>> {
+    if !value.len().is_multiple_of(2) {
+        return Err(nom::Err::Error(make_error(value, ErrorKind::LengthValue)));
+    }
+    Ok(value
+        .chunks(2)
+        .map(|v| u16::from_be_bytes([v[0], v[1]]))
+        .collect())
+}
+
+fn emit_mt_id(buf: &mut BytesMut, mt_id: &[u16]) {
+    if !mt_id.is_empty() {
+        emit_ls_tlv(buf, MT_ID, |buf| {
+            for id in mt_id.iter() {
+                buf.put_u16(*id);
+            }
+        });
+    }
+}
+
+// Protocol-ID (RFC9552 5.2).
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LsProtocol {
+    IsisL1 = 1,
+    IsisL2 = 2,
+    Ospfv2 = 3,
+    Direct = 4,
+    Static = 5,
+    Ospfv3 = 6,
+    Bgp = 7,
+    Unknown(u8),
+}
+
+impl From<u8> for LsProtocol {
+    fn from(val: u8) -> Self {
+        use LsProtocol::*;
+        match val {
+            1 => IsisL1,
+            2 => IsisL2,
+            3 => Ospfv2,
+            4 => Direct,
+            5 => Static,
+            6 => Ospfv3,
+            7 => Bgp,
+            v => Unknown(v),
+        }
+    }
+}
+
+impl From<LsProtocol> for u8 {
+    fn from(protocol: LsProtocol) -> Self {
+        use LsProtocol::*;
+        match protocol {
+            IsisL1 => 1,
+            IsisL2 => 2,
+            Ospfv2 => 3,
+            Direct => 4,
+            Static => 5,
+            Ospfv3 => 6,
+            Bgp => 7,
+            Unknown(v) => v,
+        }
+    }
+}
+
+impl fmt::Display for LsProtocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use LsProtocol::*;
+        match self {
+            IsisL1 => write!(f, "isis-l1"),
+            IsisL2 => write!(f, "isis-l2"),
+            Ospfv2 => write!(f, "ospfv2"),
+            Direct => write!(f, "direct"),
+            Static => write!(f, "static"),
+            Ospfv3 => write!(f, "ospfv3"),
+            Bgp => write!(f, "bgp"),
+            Unknown(v) => write!(f, "unknown({v})"),
+        }
+    }
+}
+
+// IGP Router-ID. IS-IS System ID with optional PSN, or OSPF Router ID with
+// optional DR interface address.
+fn fmt_router_id(f: &mut fmt::Formatter<'_>, id: &[u8]) -> fmt::Result {
+    match id.len() {
+        4 => write!(f, "{}", Ipv4Addr::new(id[0], id[1], id[2], id[3])),
+        6 | 7 => {
+            write!(
+                f,
+                "{:02x}{:02x}.{:02x}{:02x}.{:02x}{:02x}",
+                id[0], id[1], id[2], id[3], id[4], id[5]
+            )?;
+            if id.len() == 7 {
+                write!(f, ".{:02x}", id[6])?;
+            }
+            Ok(())
+        }
+        8 => write!(
+            f,
+            "{}:{}",
+            Ipv4Addr::new(id[0], id[1], id[2], id[3]),
+            Ipv4Addr::new(id[4], id[5], id[6], id[7])
+        ),
+        _ => {
+            write!(f, "0x")?;
+            for v in id.iter() {
+                write!(f, "{:02x}", v)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+// Local and Remote Node Descriptors (RFC9552 5.2.1.4, RFC9086 4.1).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LsNodeDescriptor {
+    pub asn: Option<u32>,
+    pub bgp_ls_id: Option<u32>,
+    pub ospf_area_id: Option<u32>,
+    pub igp_router_id: Option<Vec<u8>>,
+    pub bgp_router_id: Option<Ipv4Addr>,
+    pub member_asn: Option<u32>,
+    pub unknown: Vec<LsTlv>,
+}
+
+impl LsNodeDescriptor {
+    // Node Descriptors TLV of `typ`.
+    fn parse_tlv(input: &[u8], typ: u16) -> IResult<&[u8], Self> {
+        let (input, (tlv_type, value)) = parse_ls_tlv(input)?;
+        if tlv_type != typ {
+            return Err(nom::Err::Error(make_error(input, ErrorKind::Tag)));
+        }
+        let mut value = value;
+        let mut desc = Self::default();
+        while !value.is_empty() {
+            let (remaining, (typ, v)) = parse_ls_tlv(value)?;
+            match typ {
+                AS_NUMBER => desc.asn = Some(u32::from_be_bytes(ls_fixed(v)?)),
+                BGP_LS_ID => desc.bgp_ls_id = Some(u32::from_be_bytes(ls_fixed(v)?)),
+                OSPF_AREA_ID => desc.ospf_area_id = Some(u32::from_be_bytes(ls_fixed(v)?)),
+                IGP_ROUTER_ID => desc.igp_router_id = Some(v.to_vec()),
+                BGP_ROUTER_ID => desc.bgp_router_id = Some(Ipv4Addr::from(ls_fixed::<4>(v)?)),
+                MEMBER_ASN => desc.member_asn = Some(u32::from_be_bytes(ls_fixed(v)?)),
+                _ => desc.unknown.push(LsTlv::new(typ, v)),
+            }
+            value = remaining;
+        }
+        Ok((input, desc))
+    }
+
+    fn emit_tlv(&self, buf: &mut BytesMut, typ: u16) {
+        emit_ls_tlv(buf, typ, |buf| {
+            emit_ls_sorted(buf, &self.unknown, |buf| {
+                if let Some(v) = self.asn {
+                    emit_ls_tlv(buf, AS_NUMBER, |buf| buf.put_u32(v));
+                }
+                if let Some(v) = self.bgp_ls_id {
+                    emit_ls_tlv(buf, BGP_LS_ID, |buf| buf.put_u32(v));
+                }
+                if let Some(v) = self.ospf_area_id {
+                    emit_ls_tlv(buf, OSPF_AREA_ID, |buf| buf.put_u32(v));
+                }
+                if let Some(v) = &self.igp_router_id {
+                    emit_ls_tlv(buf, IGP_ROUTER_ID, |buf| buf.put(&v[..]));
+                }
+                if let Some(v) = self.bgp_router_id {
+                    emit_ls_tlv(buf, BGP_ROUTER_ID, |buf| buf.put(&v.octets()[..]));
+                }
+                if let Some(v) = self.member_asn {
+                    emit_ls_tlv(buf, MEMBER_ASN, |buf| buf.put_u32(v));
+                }
+            });
+        });
+    }
+}
+
+impl fmt::Display for LsNodeDescriptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[")?;
+        if let Some(v) = self.asn {
+            write!(f, " as {v}")?;
+        }
+        if let Some(v) = self.bgp_ls_id {
+            write!(f, " bgp-ls-id {v}")?;
+        }
+        if let Some(v) = self.ospf_area_id {
+            write!(f, " area {}", Ipv4Addr::from(v))?;
+        }
+        if let Some(v) = &self.igp_router_id {
+            write!(f, " router-id ")?;
+            fmt_router_id(f, v)?;
+        }
+        if let Some(v) = self.bgp_router_id {
+            write!(f, " bgp-router-id {v}")?;
+        }
+        if let Some(v) = self.member_asn {
+            write!(f, " member-as {v}")?;
+        }
+        for tlv in self.unknown.iter() {
+            write!(f, " {tlv}")?;
+        }
+        write!(f, " ]")
+    }
+}
+
+// Link Descriptors (RFC9552 5.2.2).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LsLinkDescriptor {
+    // Link Local and Remote Identifiers.
+    pub link_id: Option<(u32, u32)>,
+    pub ipv4_intf_addr: Option<Ipv4Addr>,
+    pub ipv4_neigh_addr: Option<Ipv4Addr>,
+    pub ipv6_intf_addr: Option<Ipv6Addr>,
+    pub ipv6_neigh_addr: Option<Ipv6Addr>,
+    pub mt_id: Vec<u16>,
+    pub unknown: Vec<LsTlv>,
+}
+
+impl LsLinkDescriptor {
+    fn parse_be(input: &[u8]) -> IResult<&[u8], Self> {
+        let mut input = input;
+        let mut desc = Self::default();
+        while !input.is_empty() {
+            let (remaining, (typ, v)) = parse_ls_tlv(input)?;
+            match typ {
+                LINK_ID => {
+                    let v: [u8; 8] = ls_fixed(v)?;
+                    let local = u32::from_be_bytes([v[0], v[1], v[2], v[3]]);
+                    let remote = u32::from_be_bytes([v[4], v[5], v[6], v[7]]);
+                    desc.link_id = Some((local, remote));
+                }
+                IPV4_INTF_ADDR => desc.ipv4_intf_addr = Some(Ipv4Addr::from(ls_fixed::<4>(v)?)),
+                IPV4_NEIGH_ADDR => desc.ipv4_neigh_addr = Some(Ipv4Addr::from(ls_fixed::<4>(v)?)),
+                IPV6_INTF_ADDR => desc.ipv6_intf_addr = Some(Ipv6Addr::from(ls_fixed::<16>(v)?)),
+                IPV6_NEIGH_ADDR => desc.ipv6_neigh_addr = Some(Ipv6Addr::from(ls_fixed::<16>(v)?)),
+                MT_ID => desc.mt_id = parse_mt_id(v)?,
+                _ => desc.unknown.push(LsTlv::new(typ, v)),
+            }
+            input = remaining;
+        }
+        Ok((input, desc))
+    }
+
+    fn emit(&self, buf: &mut BytesMut) {
+        emit_ls_sorted(buf, &self.unknown, |buf| {
+            if let Some((local, remote)) = self.link_id {
+                emit_ls_tlv(buf, LINK_ID, |buf| {
+                    buf.put_u32(local);
+                    buf.put_u32(remote);
+                });
+            }
+            if let Some(v) = self.ipv4_intf_addr {
+                emit_ls_tlv(buf, IPV4_INTF_ADDR, |buf| buf.put(&v.octets()[..]));
+            }
+            if let Some(v) = self.ipv4_neigh_addr {
+                emit_ls_tlv(buf, IPV4_NEIGH_ADDR, |buf| buf.put(&v.octets()[..]));
+            }
+            if let Some(v) = self.ipv6_intf_addr {
+                emit_ls_tlv(buf, IPV6_INTF_ADDR, |buf| buf.put(&v.octets()[..]));
+            }
+            if let Some(v) = self.ipv6_neigh_addr {
+                emit_ls_tlv(buf, IPV6_NEIGH_ADDR, |buf| buf.put(&v.octets()[..]));
+            }
+            emit_mt_id(buf, &self.mt_id);
+        });
+    }
+}
+
+fn fmt_mt_id(f: &mut fmt::Formatter<'_>, mt_id: &[u16]) -> fmt::Result {
+    if !mt_id.is_empty() {
+        write!(f, " mt-id")?;
+        for id in mt_id.iter() {
+            write!(f, " {id}")?;
+        }
+    }
+    Ok(())
+}
+
+impl fmt::Display for LsLinkDescriptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[")?;
+        if let Some((local, remote)) = self.link_id {
+            write!(f, " link-id {local}/{remote}")?;
+        }
+        if let Some(v) = self.ipv4_intf_addr {
+            write!(f, " local {v}")?;
+        }
+        if let Some(v) = self.ipv4_neigh_addr {
+            write!(f, " remote {v}")?;
+        }
+        if let Some(v) = self.ipv6_intf_addr {
+            write!(f, " local {v}")?;
+        }
+        if let Some(v) = self.ipv6_neigh_addr {
+            write!(f, " remote {v}")?;
+        }
+        fmt_mt_id(f, &self.mt_id)?;
+        for tlv in self.unknown.iter() {
+            write!(f, " {tlv}")?;
+        }
+        write!(f, " ]")
+    }
+}
+
+// Prefix Descriptors (RFC9552 5.2.3). IP Reachability Information is
+// mandatory.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LsPrefixDescriptor {
+    pub mt_id: Vec<u16>,
+    pub ospf_route_type: Option<u8>,
+    pub prefix: IpNet,
+    pub unknown: Vec<LsTlv>,
+}
+
+impl LsPrefixDescriptor {
+    fn parse_be(input: &[u8], ipv6: bool) -> IResult<&[u8], Self> {
+        let mut input = input;
+        let mut desc = Self::default();
+        let mut prefix = None;
+        while !input.is_empty() {
+            let (remaining, (typ, v)) = parse_ls_tlv(input)?;
+            match typ {
+                MT_ID => desc.mt_id = parse_mt_id(v)?,
+                OSPF_ROUTE_TYPE => desc.ospf_route_type = Some(ls_fixed::<1>(v)?[0]),
+                IP_REACH => prefix = Some(parse_ip_reach(v, ipv6)?),
+                _ => desc.unknown.push(LsTlv::new(typ, v)),
+            }
+            input = remaining;
+        }
+        let Some(prefix) = prefix else {
+            return Err(nom::Err::Error(make_error(input, ErrorKind::Verify)));
+        };
+        desc.prefix = prefix;
+        Ok((input, desc))
+    }
+
+    fn emit(&self, buf: &mut BytesMut) {
+        emit_ls_sorted(buf, &self.unknown, |buf| {
+            emit_mt_id(buf, &self.mt_id);
+            if let Some(v) = self.ospf_route_type {
+                emit_ls_tlv(buf, OSPF_ROUTE_TYPE, |buf| buf.put_u8(v));
+            }
+            emit_ls_tlv(buf, IP_REACH, |buf| {
+                let plen = self.prefix.prefix_len();
+                buf.put_u8(plen);
+                match self.prefix.addr() {
+                    IpAddr::V4(addr) => buf.put(&addr.octets()[..nlri_psize(plen)]),
+                    IpAddr::V6(addr) => buf.put(&addr.octets()[..nlri_psize(plen)]),
+                }
+            });
+        });
+    }
+}
+
+// Prefix length and the significant octets of the prefix.
+fn parse_ip_reach(value: &[u8], ipv6: bool) -> Result<IpNet, TlvError<'_>> {
+    let (value, plen) = be_u8(value)?;
+    let max = if ipv6 { 128 } else { 32 };
+    if plen > max || value.len() != nlri_psize(plen) {
+        return Err(nom::Err::Error(make_error(value, ErrorKind::LengthValue)));
+    }
+    let prefix = if ipv6 {
+        let mut octets = [0u8; 16];
+        octets[..value.len()].copy_from_slice(value);
+        IpNet::V6(Ipv6Net::new(Ipv6Addr::from(octets), plen).expect("Ipv6Net create error"))
+    } else {
+        let mut octets = [0u8; 4];
+        octets[..value.len()].copy_from_slice(value);
+        IpNet::V4(Ipv4Net::new(Ipv4Addr::from(octets), plen).expect("Ipv4Net create error"))
+    };
+    Ok(prefix)
+}
+
+impl fmt::Display for LsPrefixDescriptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[ {}", self.prefix)?;
+        if let Some(v) = self.ospf_route_type {
+            write!(f, " route-type {v}")?;
+        }
+        fmt_mt_id(f, &self.mt_id)?;
+        for tlv in self.unknown.iter() {
+            write!(f, " {tlv}")?;
+        }
+        write!(f, " ]")
+    }
+}
+
+// SRv6 SID Descriptors (RFC9514 6.1). SRv6 SID Information is mandatory.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LsSrv6SidDescriptor {
+    pub mt_id: Vec<u16>,
+    pub sid: Ipv6Addr,
+    pub unknown: Vec<LsTlv>,
+}
+
+impl LsSrv6SidDescriptor {
+    fn parse_be(input: &[u8]) -> IResult<&[u8], Self> {
+        let mut input = input;
+        let mut mt_id = Vec::new();
+        let mut sid = None;
+        let mut unknown = Vec::new();
+        while !input.is_empty() {
+            let (remaining, (typ, v)) = parse_ls_tlv(input)?;
+            match typ {
+                MT_ID => mt_id = parse_mt_id(v)?,
+                SRV6_SID_INFO => sid = Some(Ipv6Addr::from(ls_fixed::<16>(v)?)),
+                _ => unknown.push(LsTlv::new(typ, v)),
+            }
+            input = remaining;
+        }
+        let Some(sid) = sid else {
+            return Err(nom::Err::Error(make_error(input, ErrorKind::Verify)));
+        };
+        Ok((
+            input,
+            Self {
+                mt_id,
+                sid,
+                unknown,
+            },
+        ))
+    }
+
+    fn emit(&self, buf: &mut BytesMut) {
+        emit_ls_sorted(buf, &self.unknown, |buf| {
+            emit_mt_id(buf, &self.mt_id);
+            emit_ls_tlv(buf, SRV6_SID_INFO, |buf| buf.put(&self.sid.octets()[..]));
+        });
+    }
+}
+
+impl fmt::Display for LsSrv6SidDescriptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[ {}", self.sid)?;
+        fmt_mt_id(f, &self.mt_id)?;
+        for tlv in self.unknown.iter() {
+            write!(f, " {tlv}")?;
+        }
+        write!(f, " ]")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LsNode {
+    pub protocol: LsProtocol,
+    pub identifier: u64,
+    pub local: LsNodeDescriptor,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LsLink {
+    pub protocol: LsProtocol,
+    pub identifier: u64,
+    pub local: LsNodeDescriptor,
+    pub remote: LsNodeDescriptor,
+    pub link: LsLinkDescriptor,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LsPrefix {
+    pub protocol: LsProtocol,
+    pub identifier: u64,
+    pub local: LsNodeDescriptor,
+    pub prefix: LsPrefixDescriptor,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LsSrv6Sid {
+    pub protocol: LsProtocol,
+    pub identifier: u64,
+    pub local: LsNodeDescriptor,
+    pub sid: LsSrv6SidDescriptor,
+}
+
+// BGP-LS NLRI. Unknown NLRI type is kept as is.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LsNlri {
+    Node(LsNode),
+    Link(LsLink),
+    Ipv4Prefix(LsPrefix),
+    Ipv6Prefix(LsPrefix),
+    Srv6Sid(LsSrv6Sid),
+    Unknown { typ: u16, data: Vec<u8> },
+}
+
+impl LsNlri {
+    pub fn nlri_type(&self) -> u16 {
+        match self {
+            LsNlri::Node(_) => NLRI_NODE,
+            LsNlri::Link(_) => NLRI_LINK,
+            LsNlri::Ipv4Prefix(_) => NLRI_IPV4_PREFIX,
+            LsNlri::Ipv6Prefix(_) => NLRI_IPV6_PREFIX,
+            LsNlri::Srv6Sid(_) => NLRI_SRV6_SID,
+            LsNlri::Unknown { typ, .. } => *typ,
+        }
+    }
+
+    pub fn parse_be(input: &[u8]) -> IResult<&[u8], Self> {
+        let (input, typ) = be_u16(input)?;
+        let (input, len) = be_u16(input)?;
+        let (input, nlri) = take(len).parse(input)?;
+        if !matches!(
+            typ,
+            NLRI_NODE | NLRI_LINK | NLRI_IPV4_PREFIX | NLRI_IPV6_PREFIX | NLRI_SRV6_SID
+        ) {
+            let data = nlri.to_vec();
+            return Ok((input, LsNlri::Unknown { typ, data }));
+        }
+        let (nlri, protocol) = be_u8(nlri)?;
+        let protocol = LsProtocol::from(protocol);
+        let (nlri, identifier) = be_u64(nlri)?;
+        let (nlri, local) = LsNodeDescriptor::parse_tlv(nlri, LOCAL_NODE)?;
+        let ls = match typ {
+            NLRI_NODE => LsNlri::Node(LsNode {
+                protocol,
+                identifier,
+                local,
+            }),
+            NLRI_LINK => {
+                let (nlri, remote) = LsNodeDescriptor::parse_tlv(nlri, REMOTE_NODE)?;
+                let (_, link) = LsLinkDescriptor::parse_be(nlri)?;
+                LsNlri::Link(LsLink {
+                    protocol,
+                    identifier,
+                    local,
+                    remote,
+                    link,
+                })
+            }
+            NLRI_IPV4_PREFIX | NLRI_IPV6_PREFIX => {
+                let ipv6 = typ == NLRI_IPV6_PREFIX;
+                let (_, prefix) = LsPrefixDescriptor::parse_be(nlri, ipv6)?;
+                let prefix = LsPrefix {
+                    protocol,
+                    identifier,
+                    local,
+                    prefix,
+                };
+                if ipv6 {
+                    LsNlri::Ipv6Prefix(prefix)
+                } else {
+                    LsNlri::Ipv4Prefix(prefix)
+                }
+            }
+            _ => {
+                let (_, sid) = LsSrv6SidDescriptor::parse_be(nlri)?;
+                LsNlri::Srv6Sid(LsSrv6Sid {
+                    protocol,
+                    identifier,
+                    local,
+                    sid,
+                })
+            }
+        };
+        Ok((input, ls))
+    }
+}
+
+fn emit_header(buf: &mut BytesMut, protocol: LsProtocol, identifier: u64) {
+    buf.put_u8(protocol.into());
+    buf.put_u64(identifier);
+}
+
+impl EmitNlri for LsNlri {
+    fn emit_nlri(&self, buf: &mut BytesMut) {
+        emit_ls_tlv(buf, self.nlri_type(), |buf| match self {
+            LsNlri::Node(v) => {
+                emit_header(buf, v.protocol, v.identifier);
+                v.local.emit_tlv(buf, LOCAL_NODE);
+            }
+            LsNlri::Link(v) => {
+                emit_header(buf, v.protocol, v.identifier);
+                v.local.emit_tlv(buf, LOCAL_NODE);
+                v.remote.emit_tlv(buf, REMOTE_NODE);
+                v.link.emit(buf);
+            }
+            LsNlri::Ipv4Prefix(v) | LsNlri::Ipv6Prefix(v) => {
+                emit_header(buf, v.protocol, v.identifier);
+                v.local.emit_tlv(buf, LOCAL_NODE);
+                v.prefix.emit(buf);
+            }
+            LsNlri::Srv6Sid(v) => {
+                emit_header(buf, v.protocol, v.identifier);
+                v.local.emit_tlv(buf, LOCAL_NODE);
+                v.sid.emit(buf);
+            }
+            LsNlri::Unknown { data, .. } => {
+                buf.put(&data[..]);
+            }
+        });
+    }
+}
+
+impl fmt::Display for LsNlri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LsNlri::Node(v) => {
+                write!(
+                    f,
+                    "[node][{}][{}] local {}",
+                    v.protocol, v.identifier, v.local
+                )
+            }
+            LsNlri::Link(v) => write!(
+                f,
+                "[link][{}][{}] local {} remote {} link {}",
+                v.protocol, v.identifier, v.local, v.remote, v.link
+            ),
+            LsNlri::Ipv4Prefix(v) => write!(
+                f,
+                "[ipv4-prefix][{}][{}] local {} prefix {}",
+                v.protocol, v.identifier, v.local, v.prefix
+            ),
+            LsNlri::Ipv6Prefix(v) => write!(
+                f,
+                "[ipv6-prefix][{}][{}] local {} prefix {}",
+                v.protocol, v.identifier, v.local, v.prefix
+            ),
+            LsNlri::Srv6Sid(v) => write!(
+                f,
+                "[srv6-sid][{}][{}] local {} sid {}",
+                v.protocol, v.identifier, v.local, v.sid
+            ),
+            LsNlri::Unknown { typ, data } => {
+                write!(f, "[unknown({})] length {}", typ, data.len())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+
+    #[test]
+    fn ls_nlri() {
+        // IS-IS level 2 link between 0000.0000.0001 and 0000.0000.0002.
+        let input = hex!(
+            "0002 0055 02 0000000000000000"
+            "0100 001a 0200 0004 0000fde8 0201 0004 00000000 0203 0006 000000000001"
+            "0101 001a 0200 0004 0000fde8 0201 0004 00000000 0203 0006 000000000002"
+            "0103 0004 0a000001 0104 0004 0a000002"
+        );
+        let (rest, nlri) = LsNlri::parse_be(&input).unwrap();
+        assert!(rest.is_empty());
+        let LsNlri::Link(link) = &nlri else {
+            panic!("Link NLRI expected");
+        };
+        assert_eq!(link.protocol, LsProtocol::IsisL2);
+        assert_eq!(link.remote.igp_router_id, Some(vec![0, 0, 0, 0, 0, 2]));
+        assert_eq!(link.link.ipv4_neigh_addr, Some(Ipv4Addr::new(10, 0, 0, 2)));
+        assert_eq!(
+            nlri.to_string(),
+            "[link][isis-l2][0] local [ as 65000 bgp-ls-id 0 router-id 0000.0000.0001 ] \
+             remote [ as 65000 bgp-ls-id 0 router-id 0000.0000.0002 ] \
+             link [ local 10.0.0.1 remote 10.0.0.2 ]"
+        );
+        let mut buf = BytesMut::new();
+        nlri.emit_nlri(&mut buf);
+        assert_eq!(&buf[..], &input[..]);
+
+        // Unknown TLVs are emitted in the order of the type.
+        let input = hex!(
+            "0001 0029 03 0000000000000000"
+            "0100 001c 01ff 0002 abcd 0200 0004 0000fde8 0204 0004 0a000001 0300 0002 eeff"
+        );
+        let (_, nlri) = LsNlri::parse_be(&input).unwrap();
+        let LsNlri::Node(node) = &nlri else {
+            panic!("Node NLRI expected");
+        };
+        assert_eq!(node.local.unknown.len(), 2);
+        let mut buf = BytesMut::new();
+        nlri.emit_nlri(&mut buf);
+        assert_eq!(&buf[..], &input[..]);
+
+        // OSPFv2 prefix without IP Reachability Information.
+        let input = hex!("0003 0015 03 0000000000000000 0100 0008 0200 0004 0000fde8");
+        assert!(LsNlri::parse_be(&input).is_err());
+    }
+}
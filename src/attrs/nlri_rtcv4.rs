@@ -1,9 +1,10 @@
+use bytes::{BufMut, BytesMut};
 use nom::IResult;
 use nom::error::{ErrorKind, make_error};
 use nom::number::complete::{be_u8, be_u32};
 use nom_derive::*;
 
-use crate::{ExtCommunityValue, ParseNlri};
+use crate::{EmitNlri, ExtCommunityValue, ParseNlri};
 
 #[derive(Debug, Clone)]
 pub struct Rtcv4 {
@@ -25,3 +26,17 @@ impl ParseNlri<Rtcv4> for Rtcv4 {
         Ok((input, nlri))
     }
 }
+
+impl EmitNlri for Rtcv4 {
+    fn emit_nlri(&self, buf: &mut BytesMut) {
+        if self.id != 0 {
+            buf.put_u32(self.id);
+        }
+        // Origin AS(4) + Route Target(8).
+        buf.put_u8(96);
+        buf.put_u32(self.asn);
+        buf.put_u8(self.rt.high_type);
+        buf.put_u8(self.rt.low_type);
+        buf.put(&self.rt.val[..]);
+    }
+}
@@ -0,0 +1,78 @@
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use bytes::{BufMut, BytesMut};
+use nom::IResult;
+use nom::error::{ErrorKind, make_error};
+use nom::number::complete::{be_u8, be_u32, be_u128};
+
+use crate::{EmitNlri, ParseNlri};
+
+// SR Policy NLRI (RFC9830 2.1). Length is in bits and endpoint is IPv4 or
+// IPv6 address.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SrPolicyNlri {
+    pub id: u32,
+    pub distinguisher: u32,
+    pub color: u32,
+    pub endpoint: IpAddr,
+}
+
+impl ParseNlri<SrPolicyNlri> for SrPolicyNlri {
+    fn parse_nlri(input: &[u8], addpath: bool) -> IResult<&[u8], SrPolicyNlri> {
+        let (input, id) = if addpath { be_u32(input)? } else { (input, 0) };
+        let (input, plen) = be_u8(input)?;
+        let (input, distinguisher) = be_u32(input)?;
+        let (input, color) = be_u32(input)?;
+        let (input, endpoint) = match plen {
+            96 => {
+                let (input, addr) = be_u32(input)?;
+                (input, IpAddr::V4(Ipv4Addr::from(addr)))
+            }
+            192 => {
+                let (input, addr) = be_u128(input)?;
+                (input, IpAddr::V6(Ipv6Addr::from(addr)))
+            }
+            _ => return Err(nom::Err::Error(make_error(input, ErrorKind::LengthValue))),
+        };
+        let nlri = SrPolicyNlri {
+            id,
+            distinguisher,
+            color,
+            endpoint,
+        };
+        Ok((input, nlri))
+    }
+}
+
+impl EmitNlri for SrPolicyNlri {
+    fn emit_nlri(&self, buf: &mut BytesMut) {
+        if self.id != 0 {
+            buf.put_u32(self.id);
+        }
+        match self.endpoint {
+            IpAddr::V4(addr) => {
+                buf.put_u8(96);
+                buf.put_u32(self.distinguisher);
+                buf.put_u32(self.color);
+                buf.put(&addr.octets()[..]);
+            }
+            IpAddr::V6(addr) => {
+                buf.put_u8(192);
+                buf.put_u32(self.distinguisher);
+                buf.put_u32(self.color);
+                buf.put(&addr.octets()[..]);
+            }
+        }
+    }
+}
+
+impl fmt::Display for SrPolicyNlri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[{}][{}][{}]",
+            self.distinguisher, self.color, self.endpoint
+        )
+    }
+}
@@ -1,5 +1,5 @@
 use std::fmt;
-use std::net::Ipv4Addr;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 
 use bytes::{BufMut, BytesMut};
 use ipnet::Ipv4Net;
@@ -9,7 +9,7 @@ use nom::error::{ErrorKind, make_error};
 use nom::number::complete::{be_u8, be_u32};
 use nom_derive::*;
 
-use crate::{Afi, AttrType, Label, ParseNlri, RouteDistinguisher, Safi, nlri_psize};
+use crate::{Afi, AttrType, EmitNlri, Label, ParseNlri, RouteDistinguisher, Safi, nlri_psize};
 
 use super::{AttrEmitter, AttrFlags, Ipv4Nlri};
 
@@ -80,10 +80,33 @@ impl fmt::Display for Vpnv4Nlri {
     }
 }
 
+impl EmitNlri for Vpnv4Nlri {
+    fn emit_nlri(&self, buf: &mut BytesMut) {
+        // AddPath
+        if self.nlri.id != 0 {
+            buf.put_u32(self.nlri.id);
+        }
+        // Plen
+        let plen = self.nlri.prefix.prefix_len() + 88;
+        buf.put_u8(plen);
+        // Label
+        buf.put(&self.label.to_bytes()[..]);
+        // RD
+        buf.put_u16(self.rd.typ as u16);
+        buf.put(&self.rd.val[..]);
+        // Prefix
+        let plen = nlri_psize(self.nlri.prefix.prefix_len());
+        buf.put(&self.nlri.prefix.addr().octets()[0..plen]);
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Vpnv4Nexthop {
     pub rd: RouteDistinguisher,
-    pub nhop: Ipv4Addr,
+    // IPv6 address is used with Extended Next Hop Encoding (RFC8950).
+    pub nhop: IpAddr,
+    // IPv6 link-local address which follows IPv6 global address.
+    pub link_local: Option<Ipv6Addr>,
 }
 
 impl fmt::Display for Vpnv4Nexthop {
@@ -116,31 +139,36 @@ impl AttrEmitter for Vpnv4Reach {
         buf.put_u16(u16::from(Afi::Ip));
         buf.put_u8(u8::from(Safi::MplsVpn));
         // Nexthop
-        buf.put_u8(12); // Nexthop length.  RD(8)+IPv4 Nexthop(4);
-        // Nexthop RD.
-        let rd = [0u8; 8];
-        buf.put(&rd[..]);
-        // Nexthop.
-        buf.put(&self.nhop.nhop.octets()[..]);
+        match self.nhop.nhop {
+            IpAddr::V4(addr) => {
+                buf.put_u8(12); // Nexthop length.  RD(8)+IPv4 Nexthop(4);
+                buf.put_u16(self.nhop.rd.typ as u16);
+                buf.put(&self.nhop.rd.val[..]);
+                buf.put(&addr.octets()[..]);
+            }
+            IpAddr::V6(addr) => {
+                // RD(8)+IPv6 Nexthop(16) with optional RD(8)+IPv6
+                // link-local(16).
+                let len = if self.nhop.link_local.is_some() {
+                    48
+                } else {
+                    24
+                };
+                buf.put_u8(len);
+                buf.put_u16(self.nhop.rd.typ as u16);
+                buf.put(&self.nhop.rd.val[..]);
+                buf.put(&addr.octets()[..]);
+                if let Some(link_local) = self.nhop.link_local {
+                    buf.put(&[0u8; 8][..]);
+                    buf.put(&link_local.octets()[..]);
+                }
+            }
+        }
         // SNPA
-        buf.put_u8(0);
+        buf.put_u8(self.snpa);
         // Prefix.
         for update in self.updates.iter() {
-            // AddPath
-            if update.nlri.id != 0 {
-                buf.put_u32(update.nlri.id);
-            }
-            // Plen
-            let plen = update.nlri.prefix.prefix_len() + 88;
-            buf.put_u8(plen);
-            // Label
-            buf.put(&update.label.to_bytes()[..]);
-            // RD
-            buf.put_u16(update.rd.typ as u16);
-            buf.put(&update.rd.val[..]);
-            // Prefix
-            let plen = nlri_psize(update.nlri.prefix.prefix_len());
-            buf.put(&update.nlri.prefix.addr().octets()[0..plen]);
+            update.emit_nlri(buf);
         }
     }
 }
@@ -168,21 +196,7 @@ impl AttrEmitter for Vpnv4Unreach {
         buf.put_u8(u8::from(Safi::MplsVpn));
         // Prefix.
         for withdraw in self.withdraw.iter() {
-            // AddPath
-            if withdraw.nlri.id != 0 {
-                buf.put_u32(withdraw.nlri.id);
-            }
-            // Plen
-            let plen = withdraw.nlri.prefix.prefix_len() + 88;
-            buf.put_u8(plen);
-            // Label
-            buf.put(&withdraw.label.to_bytes()[..]);
-            // RD
-            buf.put_u16(withdraw.rd.typ as u16);
-            buf.put(&withdraw.rd.val[..]);
-            // Prefix
-            let plen = nlri_psize(withdraw.nlri.prefix.prefix_len());
-            buf.put(&withdraw.nlri.prefix.addr().octets()[0..plen]);
+            withdraw.emit_nlri(buf);
         }
     }
 }
@@ -0,0 +1,191 @@
+use std::fmt;
+use std::net::Ipv6Addr;
+
+use bytes::{BufMut, BytesMut};
+use ipnet::Ipv6Net;
+use nom::IResult;
+use nom::bytes::complete::take;
+use nom::error::{ErrorKind, make_error};
+use nom::number::complete::{be_u8, be_u32};
+use nom_derive::*;
+
+use crate::{Afi, AttrType, EmitNlri, Label, ParseNlri, RouteDistinguisher, Safi, nlri_psize};
+
+use super::{AttrEmitter, AttrFlags, Ipv6Nlri};
+
+// VPN-IPv6 NLRI (RFC4659).
+#[derive(Debug, Clone)]
+pub struct Vpnv6Nlri {
+    pub label: Label,
+    pub rd: RouteDistinguisher,
+    pub nlri: Ipv6Nlri,
+}
+
+impl ParseNlri<Vpnv6Nlri> for Vpnv6Nlri {
+    fn parse_nlri(input: &[u8], add_path: bool) -> IResult<&[u8], Vpnv6Nlri> {
+        let (input, id) = if add_path { be_u32(input)? } else { (input, 0) };
+
+        // MPLS Label (3 octets) + RD (8 octets) + IPv6 Prefix (0-16 octets).
+        let (input, mut plen) = be_u8(input)?;
+
+        let psize = nlri_psize(plen);
+        if input.len() < psize {
+            return Err(nom::Err::Error(make_error(input, ErrorKind::Eof)));
+        }
+        // MPLS Label.
+        let (input, label) = take(3usize).parse(input)?;
+        let label = Label::from(label);
+
+        // RD.
+        let (input, rd) = RouteDistinguisher::parse_be(input)?;
+
+        // Adjust plen to MPLS Label and Route Distinguisher.
+        if plen < 88 {
+            // Prefix length must be >= 88.
+            return Err(nom::Err::Error(make_error(input, ErrorKind::LengthValue)));
+        }
+        plen -= 88;
+        if plen > 128 {
+            return Err(nom::Err::Error(make_error(input, ErrorKind::LengthValue)));
+        }
+        let psize = nlri_psize(plen);
+        if psize > input.len() {
+            // Prefix size must be same or smaller than remaining input buffer.
+            return Err(nom::Err::Error(make_error(input, ErrorKind::LengthValue)));
+        }
+
+        // IPv6 prefix.
+        let mut paddr = [0u8; 16];
+        paddr[..psize].copy_from_slice(&input[..psize]);
+        let (input, _) = take(psize).parse(input)?;
+        let prefix = Ipv6Net::new(Ipv6Addr::from(paddr), plen).expect("Ipv6Net create error");
+
+        let nlri = Ipv6Nlri { id, prefix };
+
+        let vpnv6 = Vpnv6Nlri { label, rd, nlri };
+
+        Ok((input, vpnv6))
+    }
+}
+
+impl fmt::Display for Vpnv6Nlri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bos = if self.label.bos { "(BoS)" } else { "" };
+        write!(
+            f,
+            "VPNv6 [{}]:[{}]{} label: {} {}",
+            self.rd, self.nlri.id, self.nlri.prefix, self.label.label, bos,
+        )
+    }
+}
+
+impl EmitNlri for Vpnv6Nlri {
+    fn emit_nlri(&self, buf: &mut BytesMut) {
+        // AddPath
+        if self.nlri.id != 0 {
+            buf.put_u32(self.nlri.id);
+        }
+        // Plen
+        let plen = self.nlri.prefix.prefix_len() + 88;
+        buf.put_u8(plen);
+        // Label
+        buf.put(&self.label.to_bytes()[..]);
+        // RD
+        buf.put_u16(self.rd.typ as u16);
+        buf.put(&self.rd.val[..]);
+        // Prefix
+        let plen = nlri_psize(self.nlri.prefix.prefix_len());
+        buf.put(&self.nlri.prefix.addr().octets()[0..plen]);
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Vpnv6Nexthop {
+    pub rd: RouteDistinguisher,
+    pub nhop: Ipv6Addr,
+    // IPv6 link-local address which follows IPv6 global address.
+    pub link_local: Option<Ipv6Addr>,
+}
+
+impl fmt::Display for Vpnv6Nexthop {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}]:{}", self.rd, self.nhop)
+    }
+}
+
+pub struct Vpnv6Reach {
+    pub snpa: u8,
+    pub nhop: Vpnv6Nexthop,
+    pub updates: Vec<Vpnv6Nlri>,
+}
+
+impl AttrEmitter for Vpnv6Reach {
+    fn attr_type(&self) -> AttrType {
+        AttrType::MpReachNlri
+    }
+
+    fn attr_flags(&self) -> AttrFlags {
+        AttrFlags::new().with_optional(true)
+    }
+
+    fn len(&self) -> Option<usize> {
+        None
+    }
+
+    fn emit(&self, buf: &mut BytesMut) {
+        // AFI/SAFI.
+        buf.put_u16(u16::from(Afi::Ip6));
+        buf.put_u8(u8::from(Safi::MplsVpn));
+        // Nexthop length.  RD(8)+IPv6 Nexthop(16) with optional RD(8)+IPv6
+        // link-local(16).
+        let len = if self.nhop.link_local.is_some() {
+            48
+        } else {
+            24
+        };
+        buf.put_u8(len);
+        // Nexthop RD.
+        buf.put_u16(self.nhop.rd.typ as u16);
+        buf.put(&self.nhop.rd.val[..]);
+        // Nexthop.
+        buf.put(&self.nhop.nhop.octets()[..]);
+        if let Some(link_local) = self.nhop.link_local {
+            buf.put(&[0u8; 8][..]);
+            buf.put(&link_local.octets()[..]);
+        }
+        // SNPA
+        buf.put_u8(self.snpa);
+        // Prefix.
+        for update in self.updates.iter() {
+            update.emit_nlri(buf);
+        }
+    }
+}
+
+pub struct Vpnv6Unreach {
+    pub withdraw: Vec<Vpnv6Nlri>,
+}
+
+impl AttrEmitter for Vpnv6Unreach {
+    fn attr_type(&self) -> AttrType {
+        AttrType::MpUnreachNlri
+    }
+
+    fn attr_flags(&self) -> AttrFlags {
+        AttrFlags::new().with_optional(true)
+    }
+
+    fn len(&self) -> Option<usize> {
+        None
+    }
+
+    fn emit(&self, buf: &mut BytesMut) {
+        // AFI/SAFI.
+        buf.put_u16(u16::from(Afi::Ip6));
+        buf.put_u8(u8::from(Safi::MplsVpn));
+        // Prefix.
+        for withdraw in self.withdraw.iter() {
+            withdraw.emit_nlri(buf);
+        }
+    }
+}
@@ -2,6 +2,7 @@ use std::fmt;
 
 use bytes::{BufMut, BytesMut};
 use nom::IResult;
+use nom::error::{ErrorKind, make_error};
 use nom::number::complete::be_u8;
 use nom_derive::*;
 
@@ -61,7 +62,8 @@ impl ParseBe<Origin> for Origin {
         let origin = match val {
             0 => Origin::Igp,
             1 => Origin::Egp,
-            _ => Origin::Incomplete,
+            2 => Origin::Incomplete,
+            _ => return Err(nom::Err::Error(make_error(input, ErrorKind::Verify))),
         };
         Ok((input, origin))
     }
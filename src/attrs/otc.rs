@@ -0,0 +1,48 @@
+use std::fmt;
+
+use bytes::{BufMut, BytesMut};
+use nom_derive::*;
+
+use crate::{AttrEmitter, AttrFlags, AttrType};
+
+// Only to Customer attribute (RFC9234).
+#[derive(Clone, PartialEq, NomBE)]
+pub struct OnlyToCustomer {
+    pub asn: u32,
+}
+
+impl OnlyToCustomer {
+    pub fn new(asn: u32) -> Self {
+        Self { asn }
+    }
+}
+
+impl AttrEmitter for OnlyToCustomer {
+    fn attr_flags(&self) -> AttrFlags {
+        AttrFlags::new().with_optional(true).with_transitive(true)
+    }
+
+    fn attr_type(&self) -> AttrType {
+        AttrType::Otc
+    }
+
+    fn len(&self) -> Option<usize> {
+        Some(4)
+    }
+
+    fn emit(&self, buf: &mut BytesMut) {
+        buf.put_u32(self.asn);
+    }
+}
+
+impl fmt::Display for OnlyToCustomer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.asn)
+    }
+}
+
+impl fmt::Debug for OnlyToCustomer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "OTC: {}", self)
+    }
+}
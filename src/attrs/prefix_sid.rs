@@ -0,0 +1,539 @@
+use std::fmt;
+use std::net::Ipv6Addr;
+
+use bytes::{BufMut, BytesMut};
+use nom::IResult;
+use nom::bytes::complete::take;
+use nom::number::complete::{be_u8, be_u16, be_u24, be_u32, be_u128};
+use nom_derive::*;
+
+use super::length_error;
+use crate::{AttrEmitter, AttrFlags, AttrType, EvpnMac, ParseBe, Vpnv4Nlri};
+
+// BGP Prefix-SID attribute TLVs (RFC8669 3, RFC9252 2).
+const TLV_LABEL_INDEX: u8 = 1;
+const TLV_ORIGINATOR_SRGB: u8 = 3;
+const TLV_SRV6_L3_SERVICE: u8 = 5;
+const TLV_SRV6_L2_SERVICE: u8 = 6;
+
+// SRv6 Service sub-TLV and sub-sub-TLV (RFC9252 3.1, 3.2.1).
+const SUB_SRV6_SID_INFO: u8 = 1;
+const SUB_SUB_SID_STRUCTURE: u8 = 1;
+
+// Type(1) + Length(2) + Value. Type and length are same format on every
+// level of the attribute.
+fn parse_tlv(input: &[u8]) -> IResult<&[u8], (u8, &[u8])> {
+    let (input, typ) = be_u8(input)?;
+    let (input, len) = be_u16(input)?;
+    let (input, value) = take(len).parse(input)?;
+    Ok((input, (typ, value)))
+}
+
+fn emit_tlv(buf: &mut BytesMut, typ: u8, value: &[u8]) {
+    buf.put_u8(typ);
+    buf.put_u16(value.len() as u16);
+    buf.put(value);
+}
+
+// SRv6 SID Structure sub-sub-TLV (RFC9252 3.2.1). Transposition length and
+// offset tell the bits of the SID which are carried in the MPLS label field
+// of the NLRI.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Srv6SidStructure {
+    pub lb_len: u8,
+    pub ln_len: u8,
+    pub fun_len: u8,
+    pub arg_len: u8,
+    pub tpos_len: u8,
+    pub tpos_offset: u8,
+}
+
+impl Srv6SidStructure {
+    fn parse_value(input: &[u8]) -> IResult<&[u8], Self> {
+        if input.len() != 6 {
+            return Err(length_error(input));
+        }
+        let val = Self {
+            lb_len: input[0],
+            ln_len: input[1],
+            fun_len: input[2],
+            arg_len: input[3],
+            tpos_len: input[4],
+            tpos_offset: input[5],
+        };
+        Ok((&[], val))
+    }
+
+    fn emit_value(&self, buf: &mut BytesMut) {
+        buf.put_u8(self.lb_len);
+        buf.put_u8(self.ln_len);
+        buf.put_u8(self.fun_len);
+        buf.put_u8(self.arg_len);
+        buf.put_u8(self.tpos_len);
+        buf.put_u8(self.tpos_offset);
+    }
+
+    // Transposed bits go to the high order bits of 24 bits label field.
+    fn is_transposable(&self) -> bool {
+        self.tpos_len > 0
+            && self.tpos_len <= 24
+            && self.tpos_offset as u32 + self.tpos_len as u32 <= 128
+    }
+
+    // Merge the transposed bits in `label` field into the SID.
+    pub fn merge(&self, sid: Ipv6Addr, label: u32) -> Ipv6Addr {
+        if !self.is_transposable() {
+            return sid;
+        }
+        let mask = (1u128 << self.tpos_len) - 1;
+        let bits = (label as u128 >> (24 - self.tpos_len)) & mask;
+        let shift = 128 - self.tpos_offset as u32 - self.tpos_len as u32;
+        Ipv6Addr::from(u128::from(sid) | (bits << shift))
+    }
+
+    // Split the SID into the SID with transposed bits cleared and the label
+    // field carrying them.
+    pub fn split(&self, sid: Ipv6Addr) -> (Ipv6Addr, u32) {
+        if !self.is_transposable() {
+            return (sid, 0);
+        }
+        let mask = (1u128 << self.tpos_len) - 1;
+        let shift = 128 - self.tpos_offset as u32 - self.tpos_len as u32;
+        let bits = (u128::from(sid) >> shift) & mask;
+        let sid = Ipv6Addr::from(u128::from(sid) & !(mask << shift));
+        (sid, (bits as u32) << (24 - self.tpos_len))
+    }
+}
+
+impl fmt::Display for Srv6SidStructure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}/{}/{}/{} transposition {}/{}",
+            self.lb_len, self.ln_len, self.fun_len, self.arg_len, self.tpos_len, self.tpos_offset
+        )
+    }
+}
+
+// SRv6 SID Information sub-TLV (RFC9252 3.1).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Srv6SidInfo {
+    pub sid: Ipv6Addr,
+    pub flags: u8,
+    pub behavior: u16,
+    pub structure: Option<Srv6SidStructure>,
+    // Unknown sub-sub-TLVs are kept for propagation.
+    pub unknown: Vec<(u8, Vec<u8>)>,
+}
+
+impl Srv6SidInfo {
+    pub fn new(sid: Ipv6Addr, behavior: u16) -> Self {
+        Self {
+            sid,
+            flags: 0,
+            behavior,
+            structure: None,
+            unknown: Vec::new(),
+        }
+    }
+
+    fn parse_value(input: &[u8]) -> IResult<&[u8], Self> {
+        let (input, _reserved) = be_u8(input)?;
+        let (input, sid) = be_u128(input)?;
+        let (input, flags) = be_u8(input)?;
+        let (input, behavior) = be_u16(input)?;
+        let (mut input, _reserved) = be_u8(input)?;
+        let mut info = Self::new(Ipv6Addr::from(sid), behavior);
+        info.flags = flags;
+        while !input.is_empty() {
+            let (remaining, (typ, value)) = parse_tlv(input)?;
+            if typ == SUB_SUB_SID_STRUCTURE {
+                let (_, structure) = Srv6SidStructure::parse_value(value)?;
+                info.structure = Some(structure);
+            } else {
+                info.unknown.push((typ, value.to_vec()));
+            }
+            input = remaining;
+        }
+        Ok((input, info))
+    }
+
+    fn emit_value(&self, buf: &mut BytesMut) {
+        buf.put_u8(0);
+        buf.put(&self.sid.octets()[..]);
+        buf.put_u8(self.flags);
+        buf.put_u16(self.behavior);
+        buf.put_u8(0);
+        if let Some(structure) = &self.structure {
+            let mut value = BytesMut::new();
+            structure.emit_value(&mut value);
+            emit_tlv(buf, SUB_SUB_SID_STRUCTURE, &value);
+        }
+        for (typ, value) in self.unknown.iter() {
+            emit_tlv(buf, *typ, value);
+        }
+    }
+
+    // SID merged with the transposed bits in the label field.
+    pub fn sid_with_label(&self, label: u32) -> Ipv6Addr {
+        match &self.structure {
+            Some(structure) => structure.merge(self.sid, label),
+            None => self.sid,
+        }
+    }
+}
+
+impl fmt::Display for Srv6SidInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "sid {} behavior {}", self.sid, self.behavior)?;
+        if let Some(structure) = &self.structure {
+            write!(f, " structure {}", structure)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Srv6ServiceSubTlv {
+    SidInfo(Srv6SidInfo),
+    Unknown { typ: u8, value: Vec<u8> },
+}
+
+impl fmt::Display for Srv6ServiceSubTlv {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Srv6ServiceSubTlv::SidInfo(info) => write!(f, "{}", info),
+            Srv6ServiceSubTlv::Unknown { typ, value } => {
+                write!(f, "unknown type {} length {}", typ, value.len())
+            }
+        }
+    }
+}
+
+// SRv6 L3 and L2 Service TLV (RFC9252 2).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Srv6Service {
+    pub subs: Vec<Srv6ServiceSubTlv>,
+}
+
+impl Srv6Service {
+    fn parse_value(input: &[u8]) -> IResult<&[u8], Self> {
+        let (mut input, _reserved) = be_u8(input)?;
+        let mut service = Srv6Service::default();
+        while !input.is_empty() {
+            let (remaining, (typ, value)) = parse_tlv(input)?;
+            let sub = if typ == SUB_SRV6_SID_INFO {
+                Srv6ServiceSubTlv::SidInfo(Srv6SidInfo::parse_value(value)?.1)
+            } else {
+                Srv6ServiceSubTlv::Unknown {
+                    typ,
+                    value: value.to_vec(),
+                }
+            };
+            service.subs.push(sub);
+            input = remaining;
+        }
+        Ok((input, service))
+    }
+
+    fn emit_value(&self, buf: &mut BytesMut) {
+        buf.put_u8(0);
+        for sub in self.subs.iter() {
+            match sub {
+                Srv6ServiceSubTlv::SidInfo(info) => {
+                    let mut value = BytesMut::new();
+                    info.emit_value(&mut value);
+                    emit_tlv(buf, SUB_SRV6_SID_INFO, &value);
+                }
+                Srv6ServiceSubTlv::Unknown { typ, value } => emit_tlv(buf, *typ, value),
+            }
+        }
+    }
+
+    // First SRv6 SID Information.
+    pub fn sid_info(&self) -> Option<&Srv6SidInfo> {
+        self.subs.iter().find_map(|sub| match sub {
+            Srv6ServiceSubTlv::SidInfo(info) => Some(info),
+            _ => None,
+        })
+    }
+}
+
+impl fmt::Display for Srv6Service {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[")?;
+        for (i, sub) in self.subs.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, " {}", sub)?;
+        }
+        write!(f, " ]")
+    }
+}
+
+// SRGB range of Originator SRGB TLV (RFC8669 3.2).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Srgb {
+    pub base: u32,
+    pub range: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PrefixSidTlv {
+    LabelIndex { flags: u16, index: u32 },
+    OriginatorSrgb { flags: u16, srgbs: Vec<Srgb> },
+    Srv6L3Service(Srv6Service),
+    Srv6L2Service(Srv6Service),
+    Unknown { typ: u8, value: Vec<u8> },
+}
+
+impl PrefixSidTlv {
+    fn parse_be(input: &[u8]) -> IResult<&[u8], Self> {
+        let (input, (typ, value)) = parse_tlv(input)?;
+        let tlv = match typ {
+            TLV_LABEL_INDEX => {
+                let (value, _reserved) = be_u8(value)?;
+                let (value, flags) = be_u16(value)?;
+                let (value, index) = be_u32(value)?;
+                if !value.is_empty() {
+                    return Err(length_error(value));
+                }
+                PrefixSidTlv::LabelIndex { flags, index }
+            }
+            TLV_ORIGINATOR_SRGB => {
+                let (mut value, flags) = be_u16(value)?;
+                if value.is_empty() || !value.len().is_multiple_of(6) {
+                    return Err(length_error(value));
+                }
+                let mut srgbs = Vec::new();
+                while !value.is_empty() {
+                    let (remaining, base) = be_u24(value)?;
+                    let (remaining, range) = be_u24(remaining)?;
+                    srgbs.push(Srgb { base, range });
+                    value = remaining;
+                }
+                PrefixSidTlv::OriginatorSrgb { flags, srgbs }
+            }
+            TLV_SRV6_L3_SERVICE => PrefixSidTlv::Srv6L3Service(Srv6Service::parse_value(value)?.1),
+            TLV_SRV6_L2_SERVICE => PrefixSidTlv::Srv6L2Service(Srv6Service::parse_value(value)?.1),
+            _ => PrefixSidTlv::Unknown {
+                typ,
+                value: value.to_vec(),
+            },
+        };
+        Ok((input, tlv))
+    }
+
+    fn emit(&self, buf: &mut BytesMut) {
+        let mut value = BytesMut::new();
+        let typ = match self {
+            PrefixSidTlv::LabelIndex { flags, index } => {
+                value.put_u8(0);
+                value.put_u16(*flags);
+                value.put_u32(*index);
+                TLV_LABEL_INDEX
+            }
+            PrefixSidTlv::OriginatorSrgb { flags, srgbs } => {
+                value.put_u16(*flags);
+                for srgb in srgbs.iter() {
+                    value.put(&srgb.base.to_be_bytes()[1..]);
+                    value.put(&srgb.range.to_be_bytes()[1..]);
+                }
+                TLV_ORIGINATOR_SRGB
+            }
+            PrefixSidTlv::Srv6L3Service(service) => {
+                service.emit_value(&mut value);
+                TLV_SRV6_L3_SERVICE
+            }
+            PrefixSidTlv::Srv6L2Service(service) => {
+                service.emit_value(&mut value);
+                TLV_SRV6_L2_SERVICE
+            }
+            PrefixSidTlv::Unknown { typ, value: v } => {
+                value.put(&v[..]);
+                *typ
+            }
+        };
+        emit_tlv(buf, typ, &value);
+    }
+}
+
+impl fmt::Display for PrefixSidTlv {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PrefixSidTlv::LabelIndex { index, .. } => write!(f, "label-index {}", index),
+            PrefixSidTlv::OriginatorSrgb { srgbs, .. } => {
+                write!(f, "srgb")?;
+                for srgb in srgbs.iter() {
+                    write!(f, " {}/{}", srgb.base, srgb.range)?;
+                }
+                Ok(())
+            }
+            PrefixSidTlv::Srv6L3Service(service) => write!(f, "srv6-l3-service {}", service),
+            PrefixSidTlv::Srv6L2Service(service) => write!(f, "srv6-l2-service {}", service),
+            PrefixSidTlv::Unknown { typ, value } => {
+                write!(f, "unknown type {} length {}", typ, value.len())
+            }
+        }
+    }
+}
+
+// BGP Prefix-SID attribute (RFC8669, RFC9252).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PrefixSid {
+    pub tlvs: Vec<PrefixSidTlv>,
+}
+
+impl PrefixSid {
+    pub fn label_index(&self) -> Option<u32> {
+        self.tlvs.iter().find_map(|tlv| match tlv {
+            PrefixSidTlv::LabelIndex { index, .. } => Some(*index),
+            _ => None,
+        })
+    }
+
+    pub fn srv6_l3_service(&self) -> Option<&Srv6Service> {
+        self.tlvs.iter().find_map(|tlv| match tlv {
+            PrefixSidTlv::Srv6L3Service(service) => Some(service),
+            _ => None,
+        })
+    }
+
+    pub fn srv6_l2_service(&self) -> Option<&Srv6Service> {
+        self.tlvs.iter().find_map(|tlv| match tlv {
+            PrefixSidTlv::Srv6L2Service(service) => Some(service),
+            _ => None,
+        })
+    }
+}
+
+impl ParseBe<PrefixSid> for PrefixSid {
+    fn parse_be(input: &[u8]) -> IResult<&[u8], PrefixSid> {
+        let mut input = input;
+        let mut attr = PrefixSid::default();
+        while !input.is_empty() {
+            let (remaining, tlv) = PrefixSidTlv::parse_be(input)?;
+            attr.tlvs.push(tlv);
+            input = remaining;
+        }
+        Ok((input, attr))
+    }
+}
+
+impl AttrEmitter for PrefixSid {
+    fn attr_flags(&self) -> AttrFlags {
+        AttrFlags::new().with_optional(true).with_transitive(true)
+    }
+
+    fn attr_type(&self) -> AttrType {
+        AttrType::PrefixSid
+    }
+
+    fn len(&self) -> Option<usize> {
+        None
+    }
+
+    fn emit(&self, buf: &mut BytesMut) {
+        for tlv in self.tlvs.iter() {
+            tlv.emit(buf);
+        }
+    }
+}
+
+impl fmt::Display for PrefixSid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, tlv) in self.tlvs.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", tlv)?;
+        }
+        Ok(())
+    }
+}
+
+impl Vpnv4Nlri {
+    // SRv6 L3 service SID with the transposed bits in the label
+    // (RFC9252 4).
+    pub fn srv6_sid(&self, prefix_sid: &PrefixSid) -> Option<Ipv6Addr> {
+        let info = prefix_sid.srv6_l3_service()?.sid_info()?;
+        let label = self.label.to_bytes();
+        let label = u32::from_be_bytes([0, label[0], label[1], label[2]]);
+        Some(info.sid_with_label(label))
+    }
+}
+
+impl EvpnMac {
+    // SRv6 L2 service SID with the transposed bits in the first label
+    // (RFC9252 6.1).
+    pub fn srv6_sid(&self, prefix_sid: &PrefixSid) -> Option<Ipv6Addr> {
+        let info = prefix_sid.srv6_l2_service()?.sid_info()?;
+        Some(info.sid_with_label(self.vni))
+    }
+
+    // SRv6 L3 service SID with the transposed bits in the second label.
+    pub fn srv6_l3_sid(&self, prefix_sid: &PrefixSid) -> Option<Ipv6Addr> {
+        let info = prefix_sid.srv6_l3_service()?.sid_info()?;
+        Some(info.sid_with_label(self.label2?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Ipv4Nlri, Label, RouteDistinguisher};
+    use hex_literal::hex;
+    use std::str::FromStr;
+
+    #[test]
+    fn srv6_l3_service() {
+        let input = hex!(
+            "05 0022 00"
+            "01 001e 00 20010db8000100000000000000000000 00 0013 00"
+            "01 0006 28 18 10 00 10 40"
+        );
+        let (rest, attr) = PrefixSid::parse_be(&input).unwrap();
+        assert!(rest.is_empty());
+        assert_eq!(
+            attr.to_string(),
+            "srv6-l3-service [ sid 2001:db8:1:: behavior 19 structure 40/24/16/0 transposition 16/64 ]"
+        );
+        let mut buf = BytesMut::new();
+        attr.emit(&mut buf);
+        assert_eq!(&buf[..], &input[..]);
+
+        // Function 0x0123 is transposed into the label field.
+        let vpnv4 = Vpnv4Nlri {
+            label: Label::new(0x01230, 0, false),
+            rd: RouteDistinguisher::from_str("65000:100").unwrap(),
+            nlri: Ipv4Nlri {
+                id: 0,
+                prefix: "10.0.0.0/24".parse().unwrap(),
+            },
+        };
+        let sid = Ipv6Addr::from_str("2001:db8:1:0:123::").unwrap();
+        assert_eq!(vpnv4.srv6_sid(&attr), Some(sid));
+
+        let structure = attr
+            .srv6_l3_service()
+            .unwrap()
+            .sid_info()
+            .unwrap()
+            .structure;
+        let (base, label) = structure.unwrap().split(sid);
+        assert_eq!(base, Ipv6Addr::from_str("2001:db8:1::").unwrap());
+        assert_eq!(label, 0x012300);
+    }
+
+    #[test]
+    fn label_index() {
+        let input = hex!("01 0007 00 0000 00000064 03 0008 0000 003e80 001f40");
+        let (_, attr) = PrefixSid::parse_be(&input).unwrap();
+        assert_eq!(attr.to_string(), "label-index 100, srgb 16000/8000");
+        assert_eq!(attr.label_index(), Some(100));
+        let mut buf = BytesMut::new();
+        attr.emit(&mut buf);
+        assert_eq!(&buf[..], &input[..]);
+    }
+}
@@ -0,0 +1,818 @@
+use std::fmt;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use bytes::{BufMut, BytesMut};
+use nom::IResult;
+use nom::bytes::complete::take;
+use nom::number::complete::{be_u8, be_u16, be_u32, be_u128};
+use nom_derive::*;
+
+use super::length_error;
+use crate::{AttrEmitter, AttrFlags, AttrType, ParseBe};
+
+// Tunnel type of SR Policy (RFC9830 2.1).
+pub const TUNNEL_TYPE_SR_POLICY: u16 = 15;
+
+// Tunnel Encapsulation Attribute sub-TLVs (RFC9012 3, RFC9830 2.4).
+const SUB_PREFERENCE: u8 = 12;
+const SUB_BINDING_SID: u8 = 13;
+const SUB_ENLP: u8 = 14;
+const SUB_PRIORITY: u8 = 15;
+const SUB_SEGMENT_LIST: u8 = 128;
+const SUB_CANDIDATE_PATH_NAME: u8 = 129;
+const SUB_POLICY_NAME: u8 = 130;
+
+// Segment List sub-TLVs (RFC9830 2.4.4).
+const SEG_MPLS: u8 = 1;
+const SEG_IPV4_NODE: u8 = 3;
+const SEG_IPV6_NODE: u8 = 4;
+const SEG_IPV4_LOCAL_ID: u8 = 5;
+const SEG_IPV4_ADJACENCY: u8 = 6;
+const SEG_WEIGHT: u8 = 9;
+const SEG_SRV6: u8 = 13;
+
+// Sub-TLV of type 128 or higher has 2 octets length (RFC9012 2).
+fn parse_sub_tlv(input: &[u8]) -> IResult<&[u8], (u8, &[u8])> {
+    let (input, typ) = be_u8(input)?;
+    let (input, len) = if typ >= 128 {
+        be_u16(input)?
+    } else {
+        let (input, len) = be_u8(input)?;
+        (input, len as u16)
+    };
+    let (input, value) = take(len).parse(input)?;
+    Ok((input, (typ, value)))
+}
+
+fn emit_sub_tlv(buf: &mut BytesMut, typ: u8, value: &[u8]) {
+    buf.put_u8(typ);
+    if typ >= 128 {
+        buf.put_u16(value.len() as u16);
+    } else {
+        buf.put_u8(value.len() as u8);
+    }
+    buf.put(value);
+}
+
+// Optional 4 octets SR-MPLS SID of the segment.
+fn parse_opt_sid(input: &[u8]) -> IResult<&[u8], Option<u32>> {
+    match input.len() {
+        0 => Ok((input, None)),
+        4 => be_u32(input).map(|(i, v)| (i, Some(v))),
+        _ => Err(length_error(input)),
+    }
+}
+
+// SRv6 Endpoint Behavior and SID Structure (RFC9830 2.4.4.2.4).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Srv6EndpointBehavior {
+    pub behavior: u16,
+    pub lb_len: u8,
+    pub ln_len: u8,
+    pub fun_len: u8,
+    pub arg_len: u8,
+}
+
+impl Srv6EndpointBehavior {
+    fn parse_be(input: &[u8]) -> IResult<&[u8], Self> {
+        let (input, behavior) = be_u16(input)?;
+        let (input, _reserved) = be_u16(input)?;
+        let (input, lb_len) = be_u8(input)?;
+        let (input, ln_len) = be_u8(input)?;
+        let (input, fun_len) = be_u8(input)?;
+        let (input, arg_len) = be_u8(input)?;
+        let val = Self {
+            behavior,
+            lb_len,
+            ln_len,
+            fun_len,
+            arg_len,
+        };
+        Ok((input, val))
+    }
+
+    fn emit(&self, buf: &mut BytesMut) {
+        buf.put_u16(self.behavior);
+        buf.put_u16(0);
+        buf.put_u8(self.lb_len);
+        buf.put_u8(self.ln_len);
+        buf.put_u8(self.fun_len);
+        buf.put_u8(self.arg_len);
+    }
+}
+
+// Segment of the Segment List. `flags` has V, A, S and B flags.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Segment {
+    // Type A: SR-MPLS label.
+    Mpls {
+        flags: u8,
+        label: u32,
+        tc: u8,
+        s: bool,
+        ttl: u8,
+    },
+    // Type B: SRv6 SID.
+    Srv6 {
+        flags: u8,
+        sid: Ipv6Addr,
+        behavior: Option<Srv6EndpointBehavior>,
+    },
+    // Type C: IPv4 node address with SR algorithm.
+    Ipv4Node {
+        flags: u8,
+        algorithm: u8,
+        addr: Ipv4Addr,
+        sid: Option<u32>,
+    },
+    // Type D: IPv6 node address with SR algorithm.
+    Ipv6Node {
+        flags: u8,
+        algorithm: u8,
+        addr: Ipv6Addr,
+        sid: Option<u32>,
+    },
+    // Type E: IPv4 node address with local interface ID.
+    Ipv4LocalId {
+        flags: u8,
+        local_id: u32,
+        addr: Ipv4Addr,
+        sid: Option<u32>,
+    },
+    // Type F: IPv4 local and remote addresses.
+    Ipv4Adjacency {
+        flags: u8,
+        local: Ipv4Addr,
+        remote: Ipv4Addr,
+        sid: Option<u32>,
+    },
+    Unknown {
+        typ: u8,
+        value: Vec<u8>,
+    },
+}
+
+// MPLS label stack entry. Label(20), TC(3), S(1) and TTL(8).
+fn parse_label_entry(val: u32) -> (u32, u8, bool, u8) {
+    let label = val >> 12;
+    let tc = ((val >> 9) & 0x7) as u8;
+    let s = (val >> 8) & 0x1 != 0;
+    let ttl = (val & 0xff) as u8;
+    (label, tc, s, ttl)
+}
+
+fn label_entry(label: u32, tc: u8, s: bool, ttl: u8) -> u32 {
+    let s = if s { 1 } else { 0 };
+    (label << 12) | ((tc as u32 & 0x7) << 9) | (s << 8) | ttl as u32
+}
+
+impl Segment {
+    fn parse_value(typ: u8, value: &[u8]) -> IResult<&[u8], Self> {
+        let (input, flags) = be_u8(value)?;
+        let segment = match typ {
+            SEG_MPLS => {
+                let (input, _reserved) = be_u8(input)?;
+                let (input, val) = be_u32(input)?;
+                if !input.is_empty() {
+                    return Err(length_error(input));
+                }
+                let (label, tc, s, ttl) = parse_label_entry(val);
+                Segment::Mpls {
+                    flags,
+                    label,
+                    tc,
+                    s,
+                    ttl,
+                }
+            }
+            SEG_SRV6 => {
+                let (input, _reserved) = be_u8(input)?;
+                let (input, sid) = be_u128(input)?;
+                let behavior = match input.len() {
+                    0 => None,
+                    8 => Some(Srv6EndpointBehavior::parse_be(input)?.1),
+                    _ => return Err(length_error(input)),
+                };
+                Segment::Srv6 {
+                    flags,
+                    sid: Ipv6Addr::from(sid),
+                    behavior,
+                }
+            }
+            SEG_IPV4_NODE => {
+                let (input, algorithm) = be_u8(input)?;
+                let (input, addr) = be_u32(input)?;
+                let (_, sid) = parse_opt_sid(input)?;
+                Segment::Ipv4Node {
+                    flags,
+                    algorithm,
+                    addr: Ipv4Addr::from(addr),
+                    sid,
+                }
+            }
+            SEG_IPV6_NODE => {
+                let (input, algorithm) = be_u8(input)?;
+                let (input, addr) = be_u128(input)?;
+                let (_, sid) = parse_opt_sid(input)?;
+                Segment::Ipv6Node {
+                    flags,
+                    algorithm,
+                    addr: Ipv6Addr::from(addr),
+                    sid,
+                }
+            }
+            SEG_IPV4_LOCAL_ID => {
+                let (input, _reserved) = be_u8(input)?;
+                let (input, local_id) = be_u32(input)?;
+                let (input, addr) = be_u32(input)?;
+                let (_, sid) = parse_opt_sid(input)?;
+                Segment::Ipv4LocalId {
+                    flags,
+                    local_id,
+                    addr: Ipv4Addr::from(addr),
+                    sid,
+                }
+            }
+            SEG_IPV4_ADJACENCY => {
+                let (input, _reserved) = be_u8(input)?;
+                let (input, local) = be_u32(input)?;
+                let (input, remote) = be_u32(input)?;
+                let (_, sid) = parse_opt_sid(input)?;
+                Segment::Ipv4Adjacency {
+                    flags,
+                    local: Ipv4Addr::from(local),
+                    remote: Ipv4Addr::from(remote),
+                    sid,
+                }
+            }
+            _ => Segment::Unknown {
+                typ,
+                value: value.to_vec(),
+            },
+        };
+        Ok((&[], segment))
+    }
+
+    fn emit(&self, buf: &mut BytesMut) {
+        let mut value = BytesMut::new();
+        let typ = match self {
+            Segment::Mpls {
+                flags,
+                label,
+                tc,
+                s,
+                ttl,
+            } => {
+                value.put_u8(*flags);
+                value.put_u8(0);
+                value.put_u32(label_entry(*label, *tc, *s, *ttl));
+                SEG_MPLS
+            }
+            Segment::Srv6 {
+                flags,
+                sid,
+                behavior,
+            } => {
+                value.put_u8(*flags);
+                value.put_u8(0);
+                value.put(&sid.octets()[..]);
+                if let Some(behavior) = behavior {
+                    behavior.emit(&mut value);
+                }
+                SEG_SRV6
+            }
+            Segment::Ipv4Node {
+                flags,
+                algorithm,
+                addr,
+                sid,
+            } => {
+                value.put_u8(*flags);
+                value.put_u8(*algorithm);
+                value.put(&addr.octets()[..]);
+                if let Some(sid) = sid {
+                    value.put_u32(*sid);
+                }
+                SEG_IPV4_NODE
+            }
+            Segment::Ipv6Node {
+                flags,
+                algorithm,
+                addr,
+                sid,
+            } => {
+                value.put_u8(*flags);
+                value.put_u8(*algorithm);
+                value.put(&addr.octets()[..]);
+                if let Some(sid) = sid {
+                    value.put_u32(*sid);
+                }
+                SEG_IPV6_NODE
+            }
+            Segment::Ipv4LocalId {
+                flags,
+                local_id,
+                addr,
+                sid,
+            } => {
+                value.put_u8(*flags);
+                value.put_u8(0);
+                value.put_u32(*local_id);
+                value.put(&addr.octets()[..]);
+                if let Some(sid) = sid {
+                    value.put_u32(*sid);
+                }
+                SEG_IPV4_LOCAL_ID
+            }
+            Segment::Ipv4Adjacency {
+                flags,
+                local,
+                remote,
+                sid,
+            } => {
+                value.put_u8(*flags);
+                value.put_u8(0);
+                value.put(&local.octets()[..]);
+                value.put(&remote.octets()[..]);
+                if let Some(sid) = sid {
+                    value.put_u32(*sid);
+                }
+                SEG_IPV4_ADJACENCY
+            }
+            Segment::Unknown { typ, value: v } => {
+                value.put(&v[..]);
+                *typ
+            }
+        };
+        emit_sub_tlv(buf, typ, &value);
+    }
+}
+
+fn fmt_opt_sid(f: &mut fmt::Formatter<'_>, sid: &Option<u32>) -> fmt::Result {
+    if let Some(sid) = sid {
+        write!(f, " sid {}", sid)?;
+    }
+    Ok(())
+}
+
+impl fmt::Display for Segment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Segment::Mpls { label, .. } => write!(f, "mpls {}", label),
+            Segment::Srv6 { sid, behavior, .. } => {
+                write!(f, "srv6 {}", sid)?;
+                if let Some(behavior) = behavior {
+                    write!(f, " behavior {}", behavior.behavior)?;
+                }
+                Ok(())
+            }
+            Segment::Ipv4Node {
+                algorithm,
+                addr,
+                sid,
+                ..
+            } => {
+                write!(f, "ipv4-node {} algo {}", addr, algorithm)?;
+                fmt_opt_sid(f, sid)
+            }
+            Segment::Ipv6Node {
+                algorithm,
+                addr,
+                sid,
+                ..
+            } => {
+                write!(f, "ipv6-node {} algo {}", addr, algorithm)?;
+                fmt_opt_sid(f, sid)
+            }
+            Segment::Ipv4LocalId {
+                local_id,
+                addr,
+                sid,
+                ..
+            } => {
+                write!(f, "ipv4-local-id {} {}", addr, local_id)?;
+                fmt_opt_sid(f, sid)
+            }
+            Segment::Ipv4Adjacency {
+                local, remote, sid, ..
+            } => {
+                write!(f, "ipv4-adjacency {} {}", local, remote)?;
+                fmt_opt_sid(f, sid)
+            }
+            Segment::Unknown { typ, value } => {
+                write!(f, "unknown type {} length {}", typ, value.len())
+            }
+        }
+    }
+}
+
+// Weight sub-TLV of Segment List (RFC9830 2.4.4.1).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SegmentWeight {
+    pub flags: u8,
+    pub weight: u32,
+}
+
+// Segment List sub-TLV (RFC9830 2.4.4).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SegmentList {
+    pub weight: Option<SegmentWeight>,
+    pub segments: Vec<Segment>,
+}
+
+impl SegmentList {
+    fn parse_value(input: &[u8]) -> IResult<&[u8], Self> {
+        let (mut input, _reserved) = be_u8(input)?;
+        let mut list = SegmentList::default();
+        while !input.is_empty() {
+            let (remaining, (typ, value)) = parse_sub_tlv(input)?;
+            if typ == SEG_WEIGHT {
+                let (value, flags) = be_u8(value)?;
+                let (value, _reserved) = be_u8(value)?;
+                let (value, weight) = be_u32(value)?;
+                if !value.is_empty() {
+                    return Err(length_error(value));
+                }
+                list.weight = Some(SegmentWeight { flags, weight });
+            } else {
+                let (_, segment) = Segment::parse_value(typ, value)?;
+                list.segments.push(segment);
+            }
+            input = remaining;
+        }
+        Ok((input, list))
+    }
+
+    fn emit_value(&self, buf: &mut BytesMut) {
+        buf.put_u8(0);
+        if let Some(weight) = self.weight {
+            let mut value = BytesMut::new();
+            value.put_u8(weight.flags);
+            value.put_u8(0);
+            value.put_u32(weight.weight);
+            emit_sub_tlv(buf, SEG_WEIGHT, &value);
+        }
+        for segment in self.segments.iter() {
+            segment.emit(buf);
+        }
+    }
+}
+
+impl fmt::Display for SegmentList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "segment-list")?;
+        if let Some(weight) = self.weight {
+            write!(f, " weight {}", weight.weight)?;
+        }
+        write!(f, " [")?;
+        for (i, segment) in self.segments.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, " {}", segment)?;
+        }
+        write!(f, " ]")
+    }
+}
+
+// Binding SID value. Empty, MPLS label stack entry or SRv6 SID (RFC9830
+// 2.4.2).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BindingSid {
+    None,
+    Mpls {
+        label: u32,
+        tc: u8,
+        s: bool,
+        ttl: u8,
+    },
+    Srv6(Ipv6Addr),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TunnelSubTlv {
+    Preference { flags: u8, preference: u32 },
+    BindingSid { flags: u8, sid: BindingSid },
+    SegmentList(SegmentList),
+    Enlp { flags: u8, enlp: u8 },
+    Priority(u8),
+    CandidatePathName(String),
+    PolicyName(String),
+    Unknown { typ: u8, value: Vec<u8> },
+}
+
+// Name with a leading reserved octet. None when the name is not UTF-8.
+fn parse_name(input: &[u8]) -> IResult<&[u8], Option<String>> {
+    let (input, _reserved) = be_u8(input)?;
+    Ok((&[], String::from_utf8(input.to_vec()).ok()))
+}
+
+impl TunnelSubTlv {
+    fn parse_value(typ: u8, value: &[u8]) -> IResult<&[u8], Self> {
+        let sub = match typ {
+            SUB_PREFERENCE => {
+                let (input, flags) = be_u8(value)?;
+                let (input, _reserved) = be_u8(input)?;
+                let (input, preference) = be_u32(input)?;
+                if !input.is_empty() {
+                    return Err(length_error(input));
+                }
+                TunnelSubTlv::Preference { flags, preference }
+            }
+            SUB_BINDING_SID => {
+                let (input, flags) = be_u8(value)?;
+                let (input, _reserved) = be_u8(input)?;
+                let sid = match input.len() {
+                    0 => BindingSid::None,
+                    4 => {
+                        let (label, tc, s, ttl) = parse_label_entry(be_u32(input)?.1);
+                        BindingSid::Mpls { label, tc, s, ttl }
+                    }
+                    16 => BindingSid::Srv6(Ipv6Addr::from(be_u128(input)?.1)),
+                    _ => return Err(length_error(input)),
+                };
+                TunnelSubTlv::BindingSid { flags, sid }
+            }
+            SUB_SEGMENT_LIST => TunnelSubTlv::SegmentList(SegmentList::parse_value(value)?.1),
+            SUB_ENLP => {
+                let (input, flags) = be_u8(value)?;
+                let (input, _reserved) = be_u8(input)?;
+                let (input, enlp) = be_u8(input)?;
+                if !input.is_empty() {
+                    return Err(length_error(input));
+                }
+                TunnelSubTlv::Enlp { flags, enlp }
+            }
+            SUB_PRIORITY => {
+                let (input, priority) = be_u8(value)?;
+                let (input, _reserved) = be_u8(input)?;
+                if !input.is_empty() {
+                    return Err(length_error(input));
+                }
+                TunnelSubTlv::Priority(priority)
+            }
+            SUB_CANDIDATE_PATH_NAME | SUB_POLICY_NAME => match parse_name(value)?.1 {
+                Some(name) if typ == SUB_POLICY_NAME => TunnelSubTlv::PolicyName(name),
+                Some(name) => TunnelSubTlv::CandidatePathName(name),
+                // Name which is not UTF-8 is kept as is.
+                None => TunnelSubTlv::Unknown {
+                    typ,
+                    value: value.to_vec(),
+                },
+            },
+            _ => TunnelSubTlv::Unknown {
+                typ,
+                value: value.to_vec(),
+            },
+        };
+        Ok((&[], sub))
+    }
+
+    fn emit(&self, buf: &mut BytesMut) {
+        let mut value = BytesMut::new();
+        let typ = match self {
+            TunnelSubTlv::Preference { flags, preference } => {
+                value.put_u8(*flags);
+                value.put_u8(0);
+                value.put_u32(*preference);
+                SUB_PREFERENCE
+            }
+            TunnelSubTlv::BindingSid { flags, sid } => {
+                value.put_u8(*flags);
+                value.put_u8(0);
+                match sid {
+                    BindingSid::None => {}
+                    BindingSid::Mpls { label, tc, s, ttl } => {
+                        value.put_u32(label_entry(*label, *tc, *s, *ttl))
+                    }
+                    BindingSid::Srv6(addr) => value.put(&addr.octets()[..]),
+                }
+                SUB_BINDING_SID
+            }
+            TunnelSubTlv::SegmentList(list) => {
+                list.emit_value(&mut value);
+                SUB_SEGMENT_LIST
+            }
+            TunnelSubTlv::Enlp { flags, enlp } => {
+                value.put_u8(*flags);
+                value.put_u8(0);
+                value.put_u8(*enlp);
+                SUB_ENLP
+            }
+            TunnelSubTlv::Priority(priority) => {
+                value.put_u8(*priority);
+                value.put_u8(0);
+                SUB_PRIORITY
+            }
+            TunnelSubTlv::CandidatePathName(name) => {
+                value.put_u8(0);
+                value.put(name.as_bytes());
+                SUB_CANDIDATE_PATH_NAME
+            }
+            TunnelSubTlv::PolicyName(name) => {
+                value.put_u8(0);
+                value.put(name.as_bytes());
+                SUB_POLICY_NAME
+            }
+            TunnelSubTlv::Unknown { typ, value: v } => {
+                value.put(&v[..]);
+                *typ
+            }
+        };
+        emit_sub_tlv(buf, typ, &value);
+    }
+}
+
+impl fmt::Display for TunnelSubTlv {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TunnelSubTlv::Preference { preference, .. } => write!(f, "preference {}", preference),
+            TunnelSubTlv::BindingSid { sid, .. } => match sid {
+                BindingSid::None => write!(f, "binding-sid none"),
+                BindingSid::Mpls { label, .. } => write!(f, "binding-sid {}", label),
+                BindingSid::Srv6(addr) => write!(f, "binding-sid {}", addr),
+            },
+            TunnelSubTlv::SegmentList(list) => write!(f, "{}", list),
+            TunnelSubTlv::Enlp { enlp, .. } => write!(f, "enlp {}", enlp),
+            TunnelSubTlv::Priority(priority) => write!(f, "priority {}", priority),
+            TunnelSubTlv::CandidatePathName(name) => write!(f, "candidate-path-name {}", name),
+            TunnelSubTlv::PolicyName(name) => write!(f, "policy-name {}", name),
+            TunnelSubTlv::Unknown { typ, value } => {
+                write!(f, "unknown type {} length {}", typ, value.len())
+            }
+        }
+    }
+}
+
+// Tunnel TLV of the Tunnel Encapsulation Attribute.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TunnelTlv {
+    pub tunnel_type: u16,
+    pub subs: Vec<TunnelSubTlv>,
+}
+
+impl TunnelTlv {
+    fn parse_be(input: &[u8]) -> IResult<&[u8], Self> {
+        let (input, tunnel_type) = be_u16(input)?;
+        let (input, len) = be_u16(input)?;
+        let (input, mut value) = take(len).parse(input)?;
+        let mut subs = Vec::new();
+        while !value.is_empty() {
+            let (remaining, (typ, v)) = parse_sub_tlv(value)?;
+            let (_, sub) = TunnelSubTlv::parse_value(typ, v)?;
+            subs.push(sub);
+            value = remaining;
+        }
+        Ok((input, Self { tunnel_type, subs }))
+    }
+
+    fn emit(&self, buf: &mut BytesMut) {
+        let mut value = BytesMut::new();
+        for sub in self.subs.iter() {
+            sub.emit(&mut value);
+        }
+        buf.put_u16(self.tunnel_type);
+        buf.put_u16(value.len() as u16);
+        buf.put(&value[..]);
+    }
+}
+
+impl fmt::Display for TunnelTlv {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.tunnel_type == TUNNEL_TYPE_SR_POLICY {
+            write!(f, "sr-policy")?;
+        } else {
+            write!(f, "tunnel-type {}", self.tunnel_type)?;
+        }
+        for sub in self.subs.iter() {
+            write!(f, " {}", sub)?;
+        }
+        Ok(())
+    }
+}
+
+// Tunnel Encapsulation Attribute (RFC9012).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TunnelEncap {
+    pub tunnels: Vec<TunnelTlv>,
+}
+
+impl ParseBe<TunnelEncap> for TunnelEncap {
+    fn parse_be(input: &[u8]) -> IResult<&[u8], TunnelEncap> {
+        let mut input = input;
+        let mut attr = TunnelEncap::default();
+        while !input.is_empty() {
+            let (remaining, tunnel) = TunnelTlv::parse_be(input)?;
+            attr.tunnels.push(tunnel);
+            input = remaining;
+        }
+        Ok((input, attr))
+    }
+}
+
+impl AttrEmitter for TunnelEncap {
+    fn attr_flags(&self) -> AttrFlags {
+        AttrFlags::new().with_optional(true).with_transitive(true)
+    }
+
+    fn attr_type(&self) -> AttrType {
+        AttrType::TunnelEncap
+    }
+
+    fn len(&self) -> Option<usize> {
+        None
+    }
+
+    fn emit(&self, buf: &mut BytesMut) {
+        for tunnel in self.tunnels.iter() {
+            tunnel.emit(buf);
+        }
+    }
+}
+
+impl fmt::Display for TunnelEncap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, tunnel) in self.tunnels.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", tunnel)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+
+    #[test]
+    fn sr_policy() {
+        let input = hex!(
+            "000f 003b"
+            "0c 06 00 00 00000064"
+            "0d 06 00 00 05dc01ff"
+            "80 0019 00 09 06 80 00 00000001 01 06 00 00 03e81100 01 06 00 00 03e90100"
+            "0e 03 00 00 01"
+            "0f 02 0a 00"
+            "82 0003 00 7031"
+        );
+        let (rest, attr) = TunnelEncap::parse_be(&input).unwrap();
+        assert!(rest.is_empty());
+        assert_eq!(
+            attr.to_string(),
+            "sr-policy preference 100 binding-sid 24000 \
+             segment-list weight 1 [ mpls 16001, mpls 16016 ] enlp 1 priority 10 policy-name p1"
+        );
+        assert_eq!(
+            attr.tunnels[0].subs[1],
+            TunnelSubTlv::BindingSid {
+                flags: 0,
+                sid: BindingSid::Mpls {
+                    label: 24000,
+                    tc: 0,
+                    s: true,
+                    ttl: 255,
+                },
+            }
+        );
+        let TunnelSubTlv::SegmentList(list) = &attr.tunnels[0].subs[2] else {
+            panic!("Segment List expected");
+        };
+        assert_eq!(
+            list.weight,
+            Some(SegmentWeight {
+                flags: 0x80,
+                weight: 1,
+            })
+        );
+        assert_eq!(
+            list.segments[1],
+            Segment::Mpls {
+                flags: 0,
+                label: 16016,
+                tc: 0,
+                s: true,
+                ttl: 0,
+            }
+        );
+        let mut buf = BytesMut::new();
+        attr.emit(&mut buf);
+        assert_eq!(&buf[..], &input[..]);
+
+        // Policy name which is not UTF-8.
+        let input = hex!("000f 0006 82 0003 00 ff31");
+        let (_, attr) = TunnelEncap::parse_be(&input).unwrap();
+        assert_eq!(
+            attr.tunnels[0].subs[0],
+            TunnelSubTlv::Unknown {
+                typ: SUB_POLICY_NAME,
+                value: vec![0x00, 0xff, 0x31],
+            }
+        );
+        let mut buf = BytesMut::new();
+        attr.emit(&mut buf);
+        assert_eq!(&buf[..], &input[..]);
+    }
+}
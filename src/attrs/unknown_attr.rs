@@ -0,0 +1,89 @@
+use std::fmt;
+
+use bytes::{BufMut, BytesMut};
+
+use crate::{AttrEmitter, AttrFlags, AttrType};
+
+// Path attribute which is not recognized by this implementation. The raw
+// attribute value is kept so that optional transitive attributes can be passed
+// along to other peers as required by RFC4271.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UnknownAttr {
+    pub flags: AttrFlags,
+    pub type_code: u8,
+    pub data: Vec<u8>,
+}
+
+impl UnknownAttr {
+    pub fn new(flags: AttrFlags, type_code: u8, data: Vec<u8>) -> Self {
+        Self {
+            flags,
+            type_code,
+            data,
+        }
+    }
+
+    pub fn is_optional_transitive(&self) -> bool {
+        self.flags.optional() && self.flags.transitive()
+    }
+}
+
+impl AttrEmitter for UnknownAttr {
+    fn attr_flags(&self) -> AttrFlags {
+        // RFC4271 5: When an optional transitive attribute is passed along,
+        // the Partial bit must be set. Extended length bit is decided by
+        // attr_emit().
+        let flags = self.flags.with_extended(false);
+        if self.is_optional_transitive() {
+            flags.with_partial(true)
+        } else {
+            flags
+        }
+    }
+
+    fn attr_type(&self) -> AttrType {
+        self.type_code.into()
+    }
+
+    fn len(&self) -> Option<usize> {
+        Some(self.data.len())
+    }
+
+    fn emit(&self, buf: &mut BytesMut) {
+        buf.put(&self.data[..]);
+    }
+}
+
+impl fmt::Display for UnknownAttr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Type: {}, Flags: 0x{:02x}, Length: {}",
+            self.type_code,
+            u8::from(self.flags),
+            self.data.len()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_partial_bit() {
+        // Optional transitive attribute gets Partial bit.
+        let flags = AttrFlags::new().with_optional(true).with_transitive(true);
+        let attr = UnknownAttr::new(flags, 99, vec![1, 2, 3]);
+        let mut buf = BytesMut::new();
+        attr.attr_emit(&mut buf);
+        assert_eq!(&buf[..], &[0xe0, 99, 3, 1, 2, 3]);
+
+        // Extended length bit is recomputed from the data length.
+        let flags = AttrFlags::new().with_optional(true).with_extended(true);
+        let attr = UnknownAttr::new(flags, 100, vec![0]);
+        let mut buf = BytesMut::new();
+        attr.attr_emit(&mut buf);
+        assert_eq!(&buf[..], &[0x80, 100, 1, 0]);
+    }
+}
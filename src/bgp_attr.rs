@@ -3,8 +3,10 @@ use std::fmt;
 use bytes::BytesMut;
 
 use crate::{
-    Aggregator, Aigp, As4Path, AtomicAggregate, AttrEmitter, BgpNexthop, ClusterList, Community,
-    ExtCommunity, LargeCommunity, LocalPref, Med, NexthopAttr, Origin, OriginatorId, PmsiTunnel,
+    Aggregator, Aggregator2, Aigp, As2Path, As4Aggregator, As4Path, AtomicAggregate, AttrEmitter,
+    BgpNexthop, ClusterList, Community, ExtCommunity, ExtIpv6Community, LargeCommunity,
+    LinkStateAttr, LocalPref, Med, NexthopAttr, OnlyToCustomer, Origin, OriginatorId, PmsiTunnel,
+    PrefixSid, Role, TunnelEncap, UnknownAttr,
 };
 
 // BGP Attribute for quick access to each attribute. This would be used for
@@ -17,6 +19,8 @@ pub struct BgpAttr {
     pub aspath: Option<As4Path>,
     /// Nexthop
     pub nexthop: Option<BgpNexthop>,
+    /// MP_REACH_NLRI Nexthop
+    pub mp_nexthop: Option<BgpNexthop>,
     /// Multi-Exit Discriminator
     pub med: Option<Med>,
     /// Local preference (IBGP only)
@@ -33,13 +37,24 @@ pub struct BgpAttr {
     pub cluster_list: Option<ClusterList>,
     /// Extended Community
     pub ecom: Option<ExtCommunity>,
+    /// IPv6 Address Specific Extended Community
+    pub ecom_v6: Option<ExtIpv6Community>,
     /// PMSI Tunnel
     pub pmsi_tunnel: Option<PmsiTunnel>,
+    /// Tunnel Encapsulation
+    pub tunnel_encap: Option<TunnelEncap>,
     /// AIGP
     pub aigp: Option<Aigp>,
+    /// BGP-LS Attribute
+    pub link_state: Option<LinkStateAttr>,
     /// Large Community
     pub lcom: Option<LargeCommunity>,
-    // TODO: Unknown Attributes.
+    /// Only to Customer
+    pub otc: Option<OnlyToCustomer>,
+    /// BGP Prefix-SID
+    pub prefix_sid: Option<PrefixSid>,
+    /// Unknown Attributes
+    pub unknown: Vec<UnknownAttr>,
 }
 
 impl BgpAttr {
@@ -53,11 +68,23 @@ impl BgpAttr {
     }
 
     pub fn attr_emit(&self, buf: &mut BytesMut) {
+        self.attr_emit_as4(buf, true);
+    }
+
+    // When `as4` is false, attributes are emitted for OLD BGP speaker. AS
+    // numbers in AS_PATH and AGGREGATOR which do not fit in 2 octet are
+    // replaced with AS_TRANS and AS4_PATH and AS4_AGGREGATOR are added (RFC
+    // 6793 4.2.2).
+    pub fn attr_emit_as4(&self, buf: &mut BytesMut, as4: bool) {
         if let Some(v) = &self.origin {
             v.attr_emit(buf);
         }
         if let Some(v) = &self.aspath {
-            v.attr_emit(buf);
+            if as4 {
+                v.attr_emit(buf);
+            } else {
+                As2Path::from(v).attr_emit(buf);
+            }
         }
         if let Some(v) = &self.nexthop
             && let BgpNexthop::Ipv4(addr) = v
@@ -75,7 +102,11 @@ impl BgpAttr {
             v.attr_emit(buf);
         }
         if let Some(v) = &self.aggregator {
-            v.attr_emit(buf);
+            if as4 {
+                v.attr_emit(buf);
+            } else {
+                Aggregator2::from(v.clone()).attr_emit(buf);
+            }
         }
         if let Some(v) = &self.com {
             v.attr_emit(buf);
@@ -89,15 +120,81 @@ impl BgpAttr {
         if let Some(v) = &self.ecom {
             v.attr_emit(buf);
         }
+        if !as4 {
+            if let Some(v) = &self.aspath
+                && v.has_as4()
+            {
+                v.as4_path().attr_emit(buf);
+            }
+            if let Some(v) = &self.aggregator
+                && v.asn > u16::MAX as u32
+            {
+                As4Aggregator::from(v).attr_emit(buf);
+            }
+        }
         if let Some(v) = &self.pmsi_tunnel {
             v.attr_emit(buf);
         }
+        if let Some(v) = &self.tunnel_encap {
+            v.attr_emit(buf);
+        }
+        if let Some(v) = &self.ecom_v6 {
+            v.attr_emit(buf);
+        }
         if let Some(v) = &self.aigp {
             v.attr_emit(buf);
         }
+        if let Some(v) = &self.link_state {
+            v.attr_emit(buf);
+        }
         if let Some(v) = &self.lcom {
             v.attr_emit(buf);
         }
+        if let Some(v) = &self.otc {
+            v.attr_emit(buf);
+        }
+        if let Some(v) = &self.prefix_sid {
+            v.attr_emit(buf);
+        }
+        // Only optional transitive attributes are passed along to other peers.
+        for v in self.unknown.iter() {
+            if v.is_optional_transitive() {
+                v.attr_emit(buf);
+            }
+        }
+    }
+
+    // RFC9234 5: Ingress procedure of OTC. `role` is the local role of the
+    // session. Returns false when the route is a leak and is ineligible.
+    pub fn otc_ingress(&mut self, role: Role, remote_as: u32) -> bool {
+        match (role, &self.otc) {
+            // Received from Customer or RS-Client.
+            (Role::Provider | Role::RouteServer, Some(_)) => false,
+            (Role::Peer, Some(otc)) => otc.asn == remote_as,
+            // Received from Provider, Peer or RS.
+            (Role::Customer | Role::Peer | Role::RouteServerClient, None) => {
+                self.otc = Some(OnlyToCustomer::new(remote_as));
+                true
+            }
+            _ => true,
+        }
+    }
+
+    // RFC9234 5: Egress procedure of OTC. `role` is the local role of the
+    // session. Returns false when the route must not be advertised.
+    pub fn otc_egress(&mut self, role: Role, local_as: u32) -> bool {
+        match role {
+            // Sending to Provider, Peer or RS.
+            Role::Customer | Role::Peer | Role::RouteServerClient if self.otc.is_some() => false,
+            // Sending to Customer, Peer or RS-Client.
+            Role::Provider | Role::RouteServer | Role::Peer => {
+                if self.otc.is_none() {
+                    self.otc = Some(OnlyToCustomer::new(local_as));
+                }
+                true
+            }
+            _ => true,
+        }
     }
 }
 
@@ -136,21 +233,49 @@ impl fmt::Display for BgpAttr {
         if let Some(v) = &self.pmsi_tunnel {
             writeln!(f, " PMSI Tunnel: {}", v)?;
         }
+        if let Some(v) = &self.tunnel_encap {
+            writeln!(f, " Tunnel Encap: {}", v)?;
+        }
+        if let Some(v) = &self.ecom_v6 {
+            writeln!(f, " ExtIpv6Community: {}", v)?;
+        }
         if let Some(v) = &self.aigp {
             writeln!(f, " AIGP: {}", v)?;
         }
+        if let Some(v) = &self.link_state {
+            writeln!(f, " Link State: {}", v)?;
+        }
         if let Some(v) = &self.lcom {
             writeln!(f, " LargeCommunity: {}", v)?;
         }
+        if let Some(v) = &self.otc {
+            writeln!(f, " OTC: {}", v)?;
+        }
+        if let Some(v) = &self.prefix_sid {
+            writeln!(f, " Prefix SID: {}", v)?;
+        }
+        for v in self.unknown.iter() {
+            writeln!(f, " Unknown Attribute: {}", v)?;
+        }
         // Nexthop
-        if let Some(v) = &self.nexthop {
+        for v in self.nexthop.iter().chain(self.mp_nexthop.iter()) {
             match v {
                 BgpNexthop::Ipv4(v) => {
                     writeln!(f, " Nexthop: {}", v)?;
                 }
+                BgpNexthop::Ipv6 { global, link_local } => {
+                    write!(f, " Nexthop: {}", global)?;
+                    if let Some(link_local) = link_local {
+                        write!(f, " {}", link_local)?;
+                    }
+                    writeln!(f)?;
+                }
                 BgpNexthop::Vpnv4(v) => {
                     writeln!(f, " Nexthop: {}", v)?;
                 }
+                BgpNexthop::Vpnv6(v) => {
+                    writeln!(f, " Nexthop: {}", v)?;
+                }
                 BgpNexthop::Evpn(v) => {
                     writeln!(f, " Nexthop: {}", v)?;
                 }
@@ -185,4 +310,32 @@ mod tests {
         assert_eq!(bgp_attr.origin.unwrap(), Origin::Igp);
         assert_eq!(bgp_attr.aspath.unwrap().length(), 0);
     }
+
+    #[test]
+    fn test_otc() {
+        // Received from Customer with OTC is a leak.
+        let mut bgp_attr = BgpAttr::new();
+        bgp_attr.otc = Some(OnlyToCustomer::new(65001));
+        assert!(!bgp_attr.otc_ingress(Role::Provider, 65001));
+
+        // Received from Peer with OTC of other AS is a leak.
+        assert!(bgp_attr.otc_ingress(Role::Peer, 65001));
+        assert!(!bgp_attr.otc_ingress(Role::Peer, 65002));
+
+        // Received from Provider, OTC is added.
+        let mut bgp_attr = BgpAttr::new();
+        assert!(bgp_attr.otc_ingress(Role::Customer, 65002));
+        assert_eq!(bgp_attr.otc, Some(OnlyToCustomer::new(65002)));
+
+        // Route with OTC is not sent to Provider, Peer or RS.
+        assert!(!bgp_attr.clone().otc_egress(Role::Customer, 65000));
+        assert!(!bgp_attr.clone().otc_egress(Role::Peer, 65000));
+        assert!(bgp_attr.otc_egress(Role::Provider, 65000));
+        assert_eq!(bgp_attr.otc, Some(OnlyToCustomer::new(65002)));
+
+        // OTC is added when sent to Customer.
+        let mut bgp_attr = BgpAttr::new();
+        assert!(bgp_attr.otc_egress(Role::Provider, 65000));
+        assert_eq!(bgp_attr.otc, Some(OnlyToCustomer::new(65000)));
+    }
 }
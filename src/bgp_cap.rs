@@ -5,8 +5,9 @@ use bytes::BytesMut;
 
 use crate::{
     AddPathValue, AfiSafi, CapAddPath, CapAs4, CapDynamic, CapEmit, CapEnhancedRefresh,
-    CapExtended, CapFqdn, CapLlgr, CapMultiProtocol, CapPathLimit, CapRefresh, CapRefreshCisco,
-    CapRestart, CapVersion, CapabilityPacket, LlgrValue, PathLimitValue, RestartValue,
+    CapExtended, CapExtendedNextHop, CapFqdn, CapLlgr, CapMultiLabel, CapMultiProtocol,
+    CapPathLimit, CapRefresh, CapRefreshCisco, CapRestart, CapRole, CapVersion, CapabilityPacket,
+    ExtendedNextHopValue, LlgrValue, MultiLabelValue, PathLimitValue, RestartValue,
 };
 
 #[derive(Default, Debug, PartialEq, Clone)]
@@ -16,17 +17,31 @@ pub struct BgpCap {
     pub refresh_cisco: Option<CapRefreshCisco>,
     pub enhanced_refresh: Option<CapEnhancedRefresh>,
     pub extended: Option<CapExtended>,
-    pub restart: BTreeMap<AfiSafi, RestartValue>,
+    pub multi_label: BTreeMap<AfiSafi, MultiLabelValue>,
+    pub extended_nexthop: BTreeMap<AfiSafi, ExtendedNextHopValue>,
+    pub role: Option<CapRole>,
+    // Graceful Restart capability. Empty when it is advertised without
+    // AFI/SAFI (helper mode).
+    pub restart: Option<BTreeMap<AfiSafi, RestartValue>>,
     pub as4: Option<CapAs4>,
     pub dynamic: Option<CapDynamic>,
     pub addpath: BTreeMap<AfiSafi, AddPathValue>,
-    pub llgr: BTreeMap<AfiSafi, LlgrValue>,
+    // LLGR capability. Empty when it is advertised without AFI/SAFI.
+    pub llgr: Option<BTreeMap<AfiSafi, LlgrValue>>,
     pub fqdn: Option<CapFqdn>,
     pub version: Option<CapVersion>,
     pub path_limit: BTreeMap<AfiSafi, PathLimitValue>,
 }
 
 impl BgpCap {
+    pub fn has_restart(&self) -> bool {
+        self.restart.is_some()
+    }
+
+    pub fn has_llgr(&self) -> bool {
+        self.llgr.is_some()
+    }
+
     pub fn emit(&self, buf: &mut BytesMut) {
         for (_, v) in self.mp.iter() {
             v.emit(buf, false);
@@ -40,12 +55,29 @@ impl BgpCap {
         if let Some(v) = &self.enhanced_refresh {
             v.emit(buf, false);
         }
+        if !self.extended_nexthop.is_empty() {
+            let mut v = CapExtendedNextHop::default();
+            for (_, val) in self.extended_nexthop.iter() {
+                v.values.push(val.clone());
+            }
+            v.emit(buf, false);
+        }
         if let Some(v) = &self.extended {
             v.emit(buf, false);
         }
-        if !self.restart.is_empty() {
+        if !self.multi_label.is_empty() {
+            let mut v = CapMultiLabel::default();
+            for (_, val) in self.multi_label.iter() {
+                v.values.push(val.clone());
+            }
+            v.emit(buf, false);
+        }
+        if let Some(v) = &self.role {
+            v.emit(buf, false);
+        }
+        if let Some(restart) = &self.restart {
             let mut v = CapRestart::default();
-            for (_, val) in self.restart.iter() {
+            for (_, val) in restart.iter() {
                 v.values.push(val.clone());
             }
             v.emit(buf, false);
@@ -63,9 +95,9 @@ impl BgpCap {
             }
             v.emit(buf, false);
         }
-        if !self.llgr.is_empty() {
+        if let Some(llgr) = &self.llgr {
             let mut v = CapLlgr::default();
-            for (_, val) in self.llgr.iter() {
+            for (_, val) in llgr.iter() {
                 v.values.push(val.clone());
             }
             v.emit(buf, false);
@@ -100,13 +132,32 @@ impl BgpCap {
                     CapabilityPacket::RouteRefresh(v) => {
                         bgp_cap.refresh = Some(v);
                     }
+                    CapabilityPacket::ExtendedNextHop(v) => {
+                        for nexthop in v.values.into_iter() {
+                            // SAFI over 255 can not be negotiated.
+                            if let Some(safi) = nexthop.safi() {
+                                let key = AfiSafi::new(nexthop.afi, safi);
+                                bgp_cap.extended_nexthop.insert(key, nexthop);
+                            }
+                        }
+                    }
                     CapabilityPacket::ExtendedMessage(v) => {
                         bgp_cap.extended = Some(v);
                     }
+                    CapabilityPacket::MultipleLabels(v) => {
+                        for label in v.values.into_iter() {
+                            let key = AfiSafi::new(label.afi, label.safi);
+                            bgp_cap.multi_label.insert(key, label);
+                        }
+                    }
+                    CapabilityPacket::Role(v) => {
+                        bgp_cap.role = Some(v);
+                    }
                     CapabilityPacket::GracefulRestart(v) => {
+                        let map = bgp_cap.restart.get_or_insert_default();
                         for restart in v.values.into_iter() {
                             let key = AfiSafi::new(restart.afi, restart.safi);
-                            bgp_cap.restart.insert(key, restart);
+                            map.insert(key, restart);
                         }
                     }
                     CapabilityPacket::DynamicCapability(v) => {
@@ -122,9 +173,10 @@ impl BgpCap {
                         bgp_cap.enhanced_refresh = Some(v);
                     }
                     CapabilityPacket::Llgr(v) => {
+                        let map = bgp_cap.llgr.get_or_insert_default();
                         for llgr in v.values.into_iter() {
                             let key = AfiSafi::new(llgr.afi, llgr.safi);
-                            bgp_cap.llgr.insert(key, llgr);
+                            map.insert(key, llgr);
                         }
                     }
                     CapabilityPacket::Fqdn(v) => {
@@ -143,9 +195,10 @@ impl BgpCap {
                         bgp_cap.refresh_cisco = Some(v);
                     }
                     CapabilityPacket::LlgrOld(v) => {
+                        let map = bgp_cap.llgr.get_or_insert_default();
                         for llgr in v.values.into_iter() {
                             let key = AfiSafi::new(llgr.afi, llgr.safi);
-                            bgp_cap.llgr.insert(key, llgr);
+                            map.insert(key, llgr);
                         }
                     }
                     CapabilityPacket::Unknown(_v) => {
@@ -172,12 +225,29 @@ impl fmt::Display for BgpCap {
         if let Some(v) = &self.enhanced_refresh {
             writeln!(f, " {}", v)?;
         }
+        if !self.extended_nexthop.is_empty() {
+            let mut v = CapExtendedNextHop::default();
+            for (_, val) in self.extended_nexthop.iter() {
+                v.values.push(val.clone());
+            }
+            writeln!(f, " {}", v)?;
+        }
         if let Some(v) = &self.extended {
             writeln!(f, " {}", v)?;
         }
-        if !self.restart.is_empty() {
+        if !self.multi_label.is_empty() {
+            let mut v = CapMultiLabel::default();
+            for (_, val) in self.multi_label.iter() {
+                v.values.push(val.clone());
+            }
+            writeln!(f, " {}", v)?;
+        }
+        if let Some(v) = &self.role {
+            writeln!(f, " {}", v)?;
+        }
+        if let Some(restart) = &self.restart {
             let mut v = CapRestart::default();
-            for (_, val) in self.restart.iter() {
+            for (_, val) in restart.iter() {
                 v.values.push(val.clone());
             }
             writeln!(f, " {}", v)?;
@@ -195,9 +265,9 @@ impl fmt::Display for BgpCap {
             }
             writeln!(f, " {}", v)?;
         }
-        if !self.llgr.is_empty() {
+        if let Some(llgr) = &self.llgr {
             let mut v = CapLlgr::default();
-            for (_, val) in self.llgr.iter() {
+            for (_, val) in llgr.iter() {
                 v.values.push(val.clone());
             }
             writeln!(f, " {}", v)?;
@@ -1,10 +1,16 @@
-use std::net::{IpAddr, Ipv4Addr};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 
-use crate::Vpnv4Nexthop;
+use crate::{Vpnv4Nexthop, Vpnv6Nexthop};
 
 #[derive(Debug, Clone)]
 pub enum BgpNexthop {
     Ipv4(Ipv4Addr),
+    // IPv6 global address with optional link-local address (RFC2545 3).
+    Ipv6 {
+        global: Ipv6Addr,
+        link_local: Option<Ipv6Addr>,
+    },
     Vpnv4(Vpnv4Nexthop),
+    Vpnv6(Vpnv6Nexthop),
     Evpn(IpAddr),
 }
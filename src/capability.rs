@@ -0,0 +1,277 @@
+use std::fmt;
+
+use bytes::{BufMut, BytesMut};
+use nom::IResult;
+use nom::bytes::complete::take;
+use nom::number::complete::{be_u8, be_u16, be_u32};
+use nom_derive::*;
+
+use crate::{
+    BGP_HEADER_LEN, BgpHeader, BgpType, CapCode, CapUnknown, CapabilityHeader, CapabilityPacket,
+    many0,
+};
+
+// Dynamic Capability message (draft-ietf-idr-dynamic-cap).
+#[derive(Debug, NomBE)]
+pub struct CapabilityMessage {
+    pub header: BgpHeader,
+    #[nom(Ignore)]
+    pub values: Vec<DynamicCapValue>,
+}
+
+#[repr(u8)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum CapAction {
+    #[default]
+    Advertise = 0,
+    Remove = 1,
+}
+
+// Each tuple in the CAPABILITY message:
+//
+// +------------------------------+
+// | Init/Ack (1 bit)             |
+// +------------------------------+
+// | Ack Request (1 bit)          |
+// +------------------------------+
+// | Reserved (5 bits)            |
+// +------------------------------+
+// | Action (1 bit)               |
+// +------------------------------+
+// | Sequence Number (4 octets)   |
+// +------------------------------+
+// | Capability Code (1 octet)    |
+// +------------------------------+
+// | Capability Length (2 octets) |
+// +------------------------------+
+// | Capability Value (variable)  |
+// +------------------------------+
+#[derive(Debug, PartialEq, Clone)]
+pub struct DynamicCapValue {
+    pub ack: bool,
+    pub ack_request: bool,
+    pub action: CapAction,
+    pub seq: u32,
+    pub cap: CapabilityPacket,
+}
+
+const DYNAMIC_CAP_ACK: u8 = 0x80;
+const DYNAMIC_CAP_ACK_REQUEST: u8 = 0x40;
+const DYNAMIC_CAP_ACTION: u8 = 0x01;
+
+impl DynamicCapValue {
+    pub fn new(action: CapAction, seq: u32, cap: CapabilityPacket) -> Self {
+        Self {
+            ack: false,
+            ack_request: false,
+            action,
+            seq,
+            cap,
+        }
+    }
+
+    // Acknowledgement for this value. Echo back the sequence number and the
+    // capability.
+    pub fn to_ack(&self) -> Self {
+        Self {
+            ack: true,
+            ack_request: false,
+            action: self.action,
+            seq: self.seq,
+            cap: self.cap.clone(),
+        }
+    }
+
+    pub fn parse_be(input: &[u8]) -> IResult<&[u8], Self> {
+        let (input, flags) = be_u8(input)?;
+        let (input, seq) = be_u32(input)?;
+        let (input, code) = be_u8(input)?;
+        let (input, length) = be_u16(input)?;
+        let (input, value) = take(length).parse(input)?;
+        // Unknown capability keeps the received code and value as is.
+        let cap = if let CapCode::Unknown(_) = code.into() {
+            CapabilityPacket::Unknown(CapUnknown {
+                header: CapabilityHeader {
+                    code,
+                    length: value.len() as u8,
+                },
+                data: value.to_vec(),
+            })
+        } else {
+            let (_, cap) = CapabilityPacket::parse_be(value, code.into())?;
+            cap
+        };
+        let action = if flags & DYNAMIC_CAP_ACTION != 0 {
+            CapAction::Remove
+        } else {
+            CapAction::Advertise
+        };
+        let val = Self {
+            ack: flags & DYNAMIC_CAP_ACK != 0,
+            ack_request: flags & DYNAMIC_CAP_ACK_REQUEST != 0,
+            action,
+            seq,
+            cap,
+        };
+        Ok((input, val))
+    }
+
+    pub fn emit(&self, buf: &mut BytesMut) {
+        let mut flags = 0u8;
+        if self.ack {
+            flags |= DYNAMIC_CAP_ACK;
+        }
+        if self.ack_request {
+            flags |= DYNAMIC_CAP_ACK_REQUEST;
+        }
+        if self.action == CapAction::Remove {
+            flags |= DYNAMIC_CAP_ACTION;
+        }
+        buf.put_u8(flags);
+        buf.put_u32(self.seq);
+
+        let cap = self.cap.cap_emit();
+        let mut value = BytesMut::new();
+        cap.emit_value(&mut value);
+        buf.put_u8(cap.code().into());
+        buf.put_u16(value.len() as u16);
+        buf.put(&value[..]);
+    }
+}
+
+impl CapabilityMessage {
+    pub fn new(values: Vec<DynamicCapValue>) -> Self {
+        Self {
+            header: BgpHeader::new(BgpType::Capability, 0),
+            values,
+        }
+    }
+
+    pub fn parse_packet(input: &[u8]) -> IResult<&[u8], CapabilityMessage> {
+        let (input, mut packet) = CapabilityMessage::parse_be(input)?;
+        let len = packet.header.length.saturating_sub(BGP_HEADER_LEN);
+        let (input, values) = take(len).parse(input)?;
+        let (_, values) = many0(DynamicCapValue::parse_be).parse(values)?;
+        packet.values = values;
+        Ok((input, packet))
+    }
+}
+
+impl From<CapabilityMessage> for BytesMut {
+    fn from(message: CapabilityMessage) -> Self {
+        let mut buf = BytesMut::new();
+        let header: BytesMut = message.header.into();
+        buf.put(&header[..]);
+        for value in message.values.iter() {
+            value.emit(&mut buf);
+        }
+
+        const LENGTH_POS: std::ops::Range<usize> = 16..18;
+        let length: u16 = buf.len() as u16;
+        buf[LENGTH_POS].copy_from_slice(&length.to_be_bytes());
+
+        buf
+    }
+}
+
+impl fmt::Display for CapAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CapAction::Advertise => write!(f, "Advertise"),
+            CapAction::Remove => write!(f, "Remove"),
+        }
+    }
+}
+
+impl fmt::Display for CapabilityMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Capability Message:")?;
+        for value in self.values.iter() {
+            writeln!(
+                f,
+                " {} Seq:{}{}{} {}",
+                value.action,
+                value.seq,
+                if value.ack { " Ack" } else { "" },
+                if value.ack_request { " AckRequest" } else { "" },
+                value.cap
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AddPathValue, Afi, CapAddPath, CapMultiProtocol, Safi};
+
+    #[test]
+    fn capability_message_roundtrip() {
+        let mp = CapabilityPacket::MultiProtocol(CapMultiProtocol::new(&Afi::Ip6, &Safi::Unicast));
+        let mut addpath = DynamicCapValue::new(
+            CapAction::Advertise,
+            2,
+            CapabilityPacket::AddPath(CapAddPath::new(Afi::Ip, Safi::Unicast, 3)),
+        );
+        addpath.ack_request = true;
+        let message = CapabilityMessage::new(vec![
+            DynamicCapValue::new(CapAction::Remove, 1, mp.clone()),
+            addpath,
+        ]);
+        let buf: BytesMut = message.into();
+        assert_eq!(
+            &buf[19..31],
+            &[0x01, 0, 0, 0, 1, 0x01, 0x00, 0x04, 0x00, 0x02, 0x00, 0x01]
+        );
+
+        let (rest, message) = CapabilityMessage::parse_packet(&buf).unwrap();
+        assert!(rest.is_empty());
+        assert_eq!(message.values.len(), 2);
+        assert_eq!(message.values[0].action, CapAction::Remove);
+        assert_eq!(message.values[0].cap, mp);
+        assert!(message.values[1].ack_request);
+        assert!(!message.values[1].ack);
+        assert_eq!(message.values[1].seq, 2);
+        let CapabilityPacket::AddPath(v) = &message.values[1].cap else {
+            panic!("AddPath capability expected");
+        };
+        assert_eq!(
+            v.values[0],
+            AddPathValue {
+                afi: Afi::Ip,
+                safi: Safi::Unicast,
+                send_receive: 3.into(),
+            }
+        );
+        assert!(message.values[1].to_ack().ack);
+    }
+
+    #[test]
+    fn capability_message_unknown() {
+        for data in [vec![], vec![0xaa]] {
+            let cap = CapabilityPacket::Unknown(CapUnknown {
+                header: CapabilityHeader {
+                    code: 0xf0,
+                    length: data.len() as u8,
+                },
+                data: data.clone(),
+            });
+            let message =
+                CapabilityMessage::new(vec![DynamicCapValue::new(CapAction::Advertise, 1, cap)]);
+            let buf: BytesMut = message.into();
+            assert_eq!(
+                &buf[24..],
+                &[&[0xf0, 0x00, data.len() as u8][..], &data[..]].concat()
+            );
+
+            let (rest, message) = CapabilityMessage::parse_packet(&buf).unwrap();
+            assert!(rest.is_empty());
+            let CapabilityPacket::Unknown(v) = &message.values[0].cap else {
+                panic!("Unknown capability expected");
+            };
+            assert_eq!(v.header.code, 0xf0);
+            assert_eq!(v.data, data);
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use std::fmt;
+
+use bytes::{BufMut, BytesMut};
+use nom_derive::*;
+
+use super::{CapCode, CapEmit};
+use crate::{Afi, Safi};
+
+// Extended Next Hop Encoding capability (RFC8950).
+#[derive(Debug, Default, PartialEq, NomBE, Clone)]
+pub struct CapExtendedNextHop {
+    pub values: Vec<ExtendedNextHopValue>,
+}
+
+// NLRI SAFI is encoded in 2 octets in this capability. The received value
+// is kept as is.
+#[derive(Debug, PartialEq, NomBE, Clone)]
+pub struct ExtendedNextHopValue {
+    pub afi: Afi,
+    pub safi: u16,
+    pub nhop_afi: Afi,
+}
+
+impl ExtendedNextHopValue {
+    pub fn new(afi: Afi, safi: Safi, nhop_afi: Afi) -> Self {
+        Self {
+            afi,
+            safi: u8::from(safi).into(),
+            nhop_afi,
+        }
+    }
+
+    // None when the value does not fit in SAFI.
+    pub fn safi(&self) -> Option<Safi> {
+        u8::try_from(self.safi).ok().map(Safi::from)
+    }
+}
+
+impl CapExtendedNextHop {
+    pub fn new(afi: Afi, safi: Safi, nhop_afi: Afi) -> Self {
+        Self {
+            values: vec![ExtendedNextHopValue::new(afi, safi, nhop_afi)],
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+impl CapEmit for CapExtendedNextHop {
+    fn code(&self) -> CapCode {
+        CapCode::ExtendedNextHop
+    }
+
+    fn len(&self) -> u8 {
+        (self.values.len() * 6) as u8
+    }
+
+    fn emit_value(&self, buf: &mut BytesMut) {
+        for val in self.values.iter() {
+            buf.put_u16(val.afi.into());
+            buf.put_u16(val.safi);
+            buf.put_u16(val.nhop_afi.into());
+        }
+    }
+}
+
+impl fmt::Display for CapExtendedNextHop {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let _ = write!(f, "Extended Nexthop: ");
+        for (i, value) in self.values.iter().enumerate() {
+            if i > 0 {
+                let _ = write!(f, ", ");
+            }
+            match value.safi() {
+                Some(safi) => {
+                    let _ = write!(f, "{}/{} via {}", value.afi, safi, value.nhop_afi);
+                }
+                None => {
+                    let _ = write!(f, "{}/{} via {}", value.afi, value.safi, value.nhop_afi);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CapabilityPacket;
+
+    #[test]
+    fn extended_nexthop_roundtrip() {
+        let cap = CapExtendedNextHop::new(Afi::Ip, Safi::MplsVpn, Afi::Ip6);
+        let mut buf = BytesMut::new();
+        cap.emit(&mut buf, true);
+        assert_eq!(&buf[..], &[0x05, 0x06, 0x00, 0x01, 0x00, 0x80, 0x00, 0x02]);
+
+        let (rest, parsed) = CapabilityPacket::parse_cap(&buf).unwrap();
+        assert!(rest.is_empty());
+        assert_eq!(parsed, CapabilityPacket::ExtendedNextHop(cap));
+
+        // SAFI over 255 is kept.
+        let input = [0x05, 0x06, 0x00, 0x01, 0x01, 0x00, 0x00, 0x02];
+        let (_, parsed) = CapabilityPacket::parse_cap(&input).unwrap();
+        let CapabilityPacket::ExtendedNextHop(cap) = parsed else {
+            panic!("Extended Next Hop expected");
+        };
+        assert_eq!(cap.values[0].safi, 256);
+        assert_eq!(cap.values[0].safi(), None);
+        let mut buf = BytesMut::new();
+        cap.emit(&mut buf, true);
+        assert_eq!(&buf[..], &input);
+    }
+}
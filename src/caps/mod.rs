@@ -13,6 +13,9 @@ pub use refresh::{CapEnhancedRefresh, CapRefresh, CapRefreshCisco};
 pub mod extend;
 pub use extend::CapExtended;
 
+pub mod ext_nexthop;
+pub use ext_nexthop::{CapExtendedNextHop, ExtendedNextHopValue};
+
 pub mod graceful;
 pub use graceful::{CapRestart, RestartValue};
 
@@ -37,6 +40,12 @@ pub use version::CapVersion;
 pub mod path_limit;
 pub use path_limit::{CapPathLimit, PathLimitValue};
 
+pub mod multi_label;
+pub use multi_label::{CapMultiLabel, MultiLabelValue};
+
+pub mod role;
+pub use role::{CapRole, Role};
+
 pub mod unknown;
 pub use unknown::CapUnknown;
 
@@ -0,0 +1,70 @@
+use std::fmt;
+
+use bytes::{BufMut, BytesMut};
+use nom_derive::*;
+
+use super::{CapCode, CapEmit};
+use crate::{Afi, Safi};
+
+// Multiple Labels capability (RFC8277).
+#[derive(Debug, Default, PartialEq, NomBE, Clone)]
+pub struct CapMultiLabel {
+    pub values: Vec<MultiLabelValue>,
+}
+
+// Maximum number of labels the speaker is able to receive for the AFI/SAFI.
+#[derive(Debug, PartialEq, NomBE, Clone)]
+pub struct MultiLabelValue {
+    pub afi: Afi,
+    pub safi: Safi,
+    pub count: u8,
+}
+
+impl MultiLabelValue {
+    pub fn new(afi: Afi, safi: Safi, count: u8) -> Self {
+        Self { afi, safi, count }
+    }
+}
+
+impl CapMultiLabel {
+    pub fn new(afi: Afi, safi: Safi, count: u8) -> Self {
+        Self {
+            values: vec![MultiLabelValue::new(afi, safi, count)],
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+impl CapEmit for CapMultiLabel {
+    fn code(&self) -> CapCode {
+        CapCode::MultipleLabels
+    }
+
+    fn len(&self) -> u8 {
+        (self.values.len() * 4) as u8
+    }
+
+    fn emit_value(&self, buf: &mut BytesMut) {
+        for val in self.values.iter() {
+            buf.put_u16(val.afi.into());
+            buf.put_u8(val.safi.into());
+            buf.put_u8(val.count);
+        }
+    }
+}
+
+impl fmt::Display for CapMultiLabel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let _ = write!(f, "Multiple Labels: ");
+        for (i, value) in self.values.iter().enumerate() {
+            if i > 0 {
+                let _ = write!(f, ", ");
+            }
+            let _ = write!(f, "{}/{} {}", value.afi, value.safi, value.count);
+        }
+        Ok(())
+    }
+}
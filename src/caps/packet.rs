@@ -2,6 +2,7 @@ use std::fmt;
 
 use bytes::BytesMut;
 use nom::IResult;
+use nom::error::{ErrorKind, make_error};
 use nom_derive::*;
 
 use super::*;
@@ -28,8 +29,14 @@ pub enum CapabilityPacket {
     MultiProtocol(CapMultiProtocol),
     #[nom(Selector = "CapCode::RouteRefresh")]
     RouteRefresh(CapRefresh),
+    #[nom(Selector = "CapCode::ExtendedNextHop")]
+    ExtendedNextHop(CapExtendedNextHop),
     #[nom(Selector = "CapCode::ExtendedMessage")]
     ExtendedMessage(CapExtended),
+    #[nom(Selector = "CapCode::MultipleLabels")]
+    MultipleLabels(CapMultiLabel),
+    #[nom(Selector = "CapCode::Role")]
+    Role(CapRole),
     #[nom(Selector = "CapCode::GracefulRestart")]
     GracefulRestart(CapRestart),
     #[nom(Selector = "CapCode::As4")]
@@ -59,11 +66,45 @@ pub enum CapabilityPacket {
 impl CapabilityPacket {
     pub fn parse_cap(input: &[u8]) -> IResult<&[u8], CapabilityPacket> {
         let (input, cap_header) = CapabilityHeader::parse_be(input)?;
+        if input.len() < cap_header.length as usize {
+            return Err(nom::Err::Error(make_error(input, ErrorKind::Eof)));
+        }
         let (cap, input) = input.split_at(cap_header.length as usize);
+        // Unknown capability keeps the received code and value as is.
+        if let CapCode::Unknown(_) = cap_header.code.into() {
+            let cap = CapUnknown {
+                header: cap_header,
+                data: cap.to_vec(),
+            };
+            return Ok((input, CapabilityPacket::Unknown(cap)));
+        }
         let (_, cap) = CapabilityPacket::parse_be(cap, cap_header.code.into())?;
         Ok((input, cap))
     }
 
+    pub fn cap_emit(&self) -> &dyn CapEmit {
+        match self {
+            Self::MultiProtocol(m) => m,
+            Self::RouteRefresh(m) => m,
+            Self::ExtendedNextHop(m) => m,
+            Self::ExtendedMessage(m) => m,
+            Self::MultipleLabels(m) => m,
+            Self::Role(m) => m,
+            Self::GracefulRestart(m) => m,
+            Self::As4(m) => m,
+            Self::DynamicCapability(m) => m,
+            Self::AddPath(m) => m,
+            Self::EnhancedRouteRefresh(m) => m,
+            Self::Llgr(m) => m,
+            Self::Fqdn(m) => m,
+            Self::SoftwareVersion(m) => m,
+            Self::PathLimit(m) => m,
+            Self::RouteRefreshCisco(m) => m,
+            Self::LlgrOld(m) => m,
+            Self::Unknown(m) => m,
+        }
+    }
+
     pub fn encode(&self, buf: &mut BytesMut) {
         match self {
             Self::MultiProtocol(m) => {
@@ -72,9 +113,15 @@ impl CapabilityPacket {
             Self::RouteRefresh(m) => {
                 m.emit(buf, false);
             }
+            Self::ExtendedNextHop(m) => {
+                m.emit(buf, false);
+            }
             Self::ExtendedMessage(m) => {
                 m.emit(buf, false);
             }
+            Self::MultipleLabels(m) => {
+                m.emit(buf, false);
+            }
             Self::As4(m) => {
                 m.emit(buf, false);
             }
@@ -87,6 +134,9 @@ impl CapabilityPacket {
             Self::GracefulRestart(m) => {
                 m.emit(buf, false);
             }
+            Self::Role(m) => {
+                m.emit(buf, false);
+            }
             Self::EnhancedRouteRefresh(m) => {
                 m.emit(buf, false);
             }
@@ -120,7 +170,10 @@ impl fmt::Display for CapabilityPacket {
         match self {
             Self::MultiProtocol(v) => write!(f, "{}", v),
             Self::RouteRefresh(v) => write!(f, "{}", v),
+            Self::ExtendedNextHop(v) => write!(f, "{}", v),
             Self::ExtendedMessage(v) => write!(f, "{}", v),
+            Self::MultipleLabels(v) => write!(f, "{}", v),
+            Self::Role(v) => write!(f, "{}", v),
             Self::GracefulRestart(v) => write!(f, "{}", v),
             Self::As4(v) => write!(f, "{}", v),
             Self::DynamicCapability(v) => write!(f, "{}", v),
@@ -0,0 +1,143 @@
+use std::fmt;
+
+use bytes::{BufMut, BytesMut};
+use nom::{IResult, number::complete::be_u8};
+use nom_derive::*;
+use strum_macros::{Display, EnumString};
+
+use super::{CapCode, CapEmit};
+use crate::{BgpParseError, OpenError};
+
+// BGP Role (RFC9234).
+#[repr(u8)]
+#[derive(Debug, Clone, PartialEq, Copy, Eq, Display, EnumString)]
+pub enum Role {
+    Provider = 0,
+    #[strum(serialize = "RS")]
+    RouteServer = 1,
+    #[strum(serialize = "RS-Client")]
+    RouteServerClient = 2,
+    Customer = 3,
+    Peer = 4,
+    #[strum(disabled)]
+    Unknown(u8),
+}
+
+impl From<Role> for u8 {
+    fn from(role: Role) -> Self {
+        use Role::*;
+        match role {
+            Provider => 0,
+            RouteServer => 1,
+            RouteServerClient => 2,
+            Customer => 3,
+            Peer => 4,
+            Unknown(v) => v,
+        }
+    }
+}
+
+impl From<u8> for Role {
+    fn from(role: u8) -> Self {
+        use Role::*;
+        match role {
+            0 => Provider,
+            1 => RouteServer,
+            2 => RouteServerClient,
+            3 => Customer,
+            4 => Peer,
+            v => Unknown(v),
+        }
+    }
+}
+
+impl Role {
+    pub fn parse_be(input: &[u8]) -> IResult<&[u8], Self> {
+        let (input, role) = be_u8(input)?;
+        Ok((input, role.into()))
+    }
+
+    // RFC9234 4.2: Allowed pairs of local and remote role.
+    pub fn is_match(&self, remote: Role) -> bool {
+        use Role::*;
+        matches!(
+            (self, remote),
+            (Provider, Customer)
+                | (Customer, Provider)
+                | (RouteServer, RouteServerClient)
+                | (RouteServerClient, RouteServer)
+                | (Peer, Peer)
+        )
+    }
+}
+
+#[derive(Debug, PartialEq, NomBE, Clone)]
+pub struct CapRole {
+    pub role: Role,
+}
+
+impl CapRole {
+    pub fn new(role: Role) -> Self {
+        Self { role }
+    }
+
+    // RFC9234 4.2: Check the role received from the peer. In strict mode the
+    // peer must advertise the capability. Error is Role Mismatch
+    // NOTIFICATION.
+    pub fn check(&self, remote: Option<&CapRole>, strict: bool) -> Result<(), BgpParseError> {
+        let matched = match remote {
+            Some(remote) => self.role.is_match(remote.role),
+            None => !strict,
+        };
+        if !matched {
+            return Err(BgpParseError::open_error(OpenError::RoleMismatch, &[]));
+        }
+        Ok(())
+    }
+}
+
+impl CapEmit for CapRole {
+    fn code(&self) -> CapCode {
+        CapCode::Role
+    }
+
+    fn len(&self) -> u8 {
+        1
+    }
+
+    fn emit_value(&self, buf: &mut BytesMut) {
+        buf.put_u8(self.role.into());
+    }
+}
+
+impl fmt::Display for CapRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Role: {}", self.role)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CapabilityPacket, NotificationPacket};
+
+    #[test]
+    fn role_check() {
+        let cap = CapRole::new(Role::Provider);
+        let mut buf = BytesMut::new();
+        cap.emit(&mut buf, true);
+        assert_eq!(&buf[..], &[0x09, 0x01, 0x00]);
+        let (_, parsed) = CapabilityPacket::parse_cap(&buf).unwrap();
+        assert_eq!(parsed, CapabilityPacket::Role(cap.clone()));
+
+        assert!(cap.check(Some(&CapRole::new(Role::Customer)), true).is_ok());
+        assert!(cap.check(None, false).is_ok());
+        assert!(cap.check(None, true).is_err());
+
+        let err = cap
+            .check(Some(&CapRole::new(Role::Peer)), false)
+            .unwrap_err();
+        let notification = NotificationPacket::try_from(err).unwrap();
+        assert_eq!(notification.sub_code, 11);
+    }
+}
@@ -10,6 +10,7 @@ pub enum CapCode {
     RouteRefresh = 2,
     ExtendedNextHop = 5,
     ExtendedMessage = 6,
+    MultipleLabels = 8,
     Role = 9,
     GracefulRestart = 64,
     As4 = 65,
@@ -33,6 +34,7 @@ impl From<CapCode> for u8 {
             RouteRefresh => 2,
             ExtendedNextHop => 5,
             ExtendedMessage => 6,
+            MultipleLabels => 8,
             Role => 9,
             GracefulRestart => 64,
             As4 => 65,
@@ -58,6 +60,7 @@ impl From<u8> for CapCode {
             2 => RouteRefresh,
             5 => ExtendedNextHop,
             6 => ExtendedMessage,
+            8 => MultipleLabels,
             9 => Role,
             64 => GracefulRestart,
             65 => As4,
@@ -22,7 +22,7 @@ impl Default for CapUnknown {
 
 impl CapEmit for CapUnknown {
     fn code(&self) -> CapCode {
-        CapCode::Unknown(100)
+        self.header.code.into()
     }
 
     fn len(&self) -> u8 {
@@ -0,0 +1,283 @@
+use bytes::{Buf, BytesMut};
+
+use crate::{
+    BGP_HEADER_LEN, BGP_PACKET_LEN, BgpPacket, BgpParseError, BgpType, HeaderError, ParseOption,
+    peek_bgp_length,
+};
+
+// Maximum message length with Extended Message capability (RFC8654).
+pub const BGP_EXTENDED_PACKET_LEN: usize = 65535;
+
+// Framer for BGP messages over TCP byte stream. Received bytes are buffered
+// until whole message is available.
+#[derive(Debug)]
+pub struct BgpCodec {
+    buf: BytesMut,
+    max_len: usize,
+    // Negotiated session parameters.
+    pub opt: Option<ParseOption>,
+}
+
+impl Default for BgpCodec {
+    fn default() -> Self {
+        Self {
+            buf: BytesMut::new(),
+            max_len: BGP_PACKET_LEN,
+            opt: None,
+        }
+    }
+}
+
+impl BgpCodec {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Extended Message capability is negotiated (RFC8654). It must not be
+    // set before OPEN message exchange completes.
+    pub fn set_extended(&mut self, extended: bool) {
+        self.max_len = if extended {
+            BGP_EXTENDED_PACKET_LEN
+        } else {
+            BGP_PACKET_LEN
+        };
+    }
+
+    // Apply the result of capability negotiation. It must not be set before
+    // OPEN message exchange completes.
+    pub fn set_option(&mut self, opt: ParseOption) {
+        self.set_extended(opt.extended);
+        self.opt = Some(opt);
+    }
+
+    pub fn max_len(&self) -> usize {
+        self.max_len
+    }
+
+    // RFC8654 4: OPEN and KEEPALIVE must not exceed 4096 octets even when
+    // Extended Message is negotiated.
+    fn type_max_len(&self, typ: u8) -> usize {
+        if typ == BgpType::Open as u8 || typ == BgpType::Keepalive as u8 {
+            BGP_PACKET_LEN
+        } else {
+            self.max_len
+        }
+    }
+
+    // Append received bytes.
+    pub fn extend(&mut self, data: &[u8]) {
+        self.buf.extend_from_slice(data);
+    }
+
+    // Next message in the received bytes. Ok(None) when more bytes are
+    // needed.
+    pub fn next_packet(&mut self) -> Result<Option<BgpPacket>, BgpParseError> {
+        let mut buf = std::mem::take(&mut self.buf);
+        let packet = self.decode_frame(&mut buf);
+        self.buf = buf;
+        packet
+    }
+
+    // Decode a message from `src`. The message is consumed from `src` only
+    // when it is decoded.
+    pub fn decode_frame(&self, src: &mut BytesMut) -> Result<Option<BgpPacket>, BgpParseError> {
+        if src.len() < BGP_HEADER_LEN as usize {
+            return Ok(None);
+        }
+        // RFC4271 6.1: Marker must be all ones.
+        if src[..16].iter().any(|x| *x != 0xff) {
+            return Err(BgpParseError::header_error(
+                HeaderError::ConnectionNotSynced,
+                &[],
+            ));
+        }
+        let length = peek_bgp_length(src);
+        if length < BGP_HEADER_LEN as usize || length > self.type_max_len(src[18]) {
+            return Err(BgpParseError::bad_message_length(length as u16));
+        }
+        if src.len() < length {
+            src.reserve(length - src.len());
+            return Ok(None);
+        }
+        let (_, packet) = BgpPacket::parse_packet(&src[..length], &self.opt)?;
+        src.advance(length);
+        Ok(Some(packet))
+    }
+
+    // Encode a message. Error when the message exceeds the maximum length.
+    pub fn encode_frame(&self, packet: BgpPacket, dst: &mut BytesMut) -> Result<(), BgpParseError> {
+        let as4 = self.opt.as_ref().is_some_and(ParseOption::is_as4);
+        let max_len = match packet {
+            BgpPacket::Open(_) | BgpPacket::Keepalive(_) => BGP_PACKET_LEN,
+            _ => self.max_len,
+        };
+        let buf = match packet {
+            BgpPacket::Update(mut update) => {
+                if let (Some(opt), Some(mp_update)) = (&self.opt, &mut update.mp_update) {
+                    mp_update.truncate_labels(opt);
+                }
+                update.emit_packet(as4)
+            }
+            packet => packet.into(),
+        };
+        if buf.len() > max_len {
+            return Err(BgpParseError::MessageTooLong {
+                length: buf.len(),
+                max: max_len,
+            });
+        }
+        dst.extend_from_slice(&buf);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "tokio-util")]
+mod tokio_codec {
+    use bytes::BytesMut;
+    use thiserror::Error;
+    use tokio_util::codec::{Decoder, Encoder};
+
+    use super::BgpCodec;
+    use crate::{BgpPacket, BgpParseError};
+
+    #[derive(Error, Debug)]
+    pub enum BgpCodecError {
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
+
+        #[error(transparent)]
+        Parse(#[from] BgpParseError),
+    }
+
+    impl Decoder for BgpCodec {
+        type Item = BgpPacket;
+        type Error = BgpCodecError;
+
+        fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+            Ok(self.decode_frame(src)?)
+        }
+    }
+
+    impl Encoder<BgpPacket> for BgpCodec {
+        type Error = BgpCodecError;
+
+        fn encode(&mut self, item: BgpPacket, dst: &mut BytesMut) -> Result<(), Self::Error> {
+            Ok(self.encode_frame(item, dst)?)
+        }
+    }
+}
+
+#[cfg(feature = "tokio-util")]
+pub use tokio_codec::BgpCodecError;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::Ipv4Addr;
+
+    use ipnet::Ipv4Net;
+
+    use crate::{
+        BgpAttr, BgpHeader, Ipv4Nlri, Label, LabeledIpv4Nlri, MpNlriReachAttr, UpdatePacket,
+    };
+
+    fn keepalive() -> BytesMut {
+        BgpHeader::new(BgpType::Keepalive, BGP_HEADER_LEN).into()
+    }
+
+    #[test]
+    fn decode_chunks() {
+        let mut codec = BgpCodec::new();
+        let mut stream = keepalive();
+        stream.extend_from_slice(&keepalive());
+
+        // Feed one byte at a time.
+        let mut packets = Vec::new();
+        for byte in stream.iter() {
+            codec.extend(&[*byte]);
+            while let Some(packet) = codec.next_packet().unwrap() {
+                packets.push(packet);
+            }
+        }
+        assert_eq!(packets.len(), 2);
+        assert!(matches!(packets[0], BgpPacket::Keepalive(_)));
+    }
+
+    #[test]
+    fn decode_length_bounds() {
+        let mut codec = BgpCodec::new();
+        let mut buf = keepalive();
+        buf[16..18].copy_from_slice(&5000u16.to_be_bytes());
+        assert!(codec.decode_frame(&mut buf.clone()).is_err());
+
+        // Extended Message allows up to 65535 except OPEN and KEEPALIVE.
+        codec.set_extended(true);
+        assert!(codec.decode_frame(&mut buf.clone()).is_err());
+        buf[18] = BgpType::Update as u8;
+        assert!(codec.decode_frame(&mut buf).unwrap().is_none());
+
+        let mut buf = keepalive();
+        buf[16..18].copy_from_slice(&18u16.to_be_bytes());
+        assert!(codec.decode_frame(&mut buf).is_err());
+
+        let mut buf = keepalive();
+        buf[0] = 0;
+        assert!(codec.decode_frame(&mut buf).is_err());
+    }
+
+    #[test]
+    fn encode_length_bounds() {
+        let mut codec = BgpCodec::new();
+        codec.set_extended(true);
+
+        let mut update = UpdatePacket::new();
+        for i in 0..20000u32 {
+            let prefix = Ipv4Net::new(Ipv4Addr::from(i << 8), 24).unwrap();
+            update.ipv4_withdraw.push(Ipv4Nlri { id: 0, prefix });
+        }
+        let mut dst = BytesMut::new();
+        let Err(BgpParseError::MessageTooLong { length, max }) =
+            codec.encode_frame(BgpPacket::Update(Box::new(update)), &mut dst)
+        else {
+            panic!("Length error expected");
+        };
+        assert!(length > BGP_EXTENDED_PACKET_LEN);
+        assert_eq!(max, BGP_EXTENDED_PACKET_LEN);
+        assert!(dst.is_empty());
+    }
+
+    #[test]
+    fn encode_label_count() {
+        // Single label is sent without Multiple Labels capability.
+        let mut codec = BgpCodec::new();
+        codec.set_option(ParseOption::default());
+        let mut update = UpdatePacket::new();
+        update.bgp_attr = Some(BgpAttr::new());
+        update.mp_update = Some(MpNlriReachAttr::Ipv4Labeled {
+            snpa: 0,
+            nhop: "10.0.0.1".parse().unwrap(),
+            link_local: None,
+            updates: vec![LabeledIpv4Nlri {
+                labels: vec![Label::new(16, 0, false), Label::new(17, 0, true)],
+                nlri: Ipv4Nlri {
+                    id: 0,
+                    prefix: "10.0.0.0/24".parse().unwrap(),
+                },
+            }],
+        });
+        let mut buf = BytesMut::new();
+        codec
+            .encode_frame(BgpPacket::Update(Box::new(update)), &mut buf)
+            .unwrap();
+
+        let Some(BgpPacket::Update(update)) = codec.decode_frame(&mut buf).unwrap() else {
+            panic!("Update expected");
+        };
+        let Some(MpNlriReachAttr::Ipv4Labeled { updates, .. }) = update.mp_update else {
+            panic!("Labeled unicast expected");
+        };
+        assert_eq!(updates[0].labels.len(), 1);
+        assert_eq!(updates[0].labels[0].label, 16);
+        assert!(updates[0].labels[0].bos);
+    }
+}
@@ -1,6 +1,13 @@
+use std::fmt;
+
 use thiserror::Error;
 
-use crate::AttrType;
+use bytes::BytesMut;
+
+use crate::{
+    AttrType, BGP_VERSION, BgpType, CapabilityPacket, HeaderError, NotificationPacket, NotifyCode,
+    OpenError, RouteRefreshError, UpdateError,
+};
 
 #[derive(Error, Debug)]
 pub enum BgpParseError {
@@ -9,6 +16,7 @@ pub enum BgpParseError {
         attr_type: AttrType,
         #[source]
         source: Box<dyn std::error::Error + Send + Sync>,
+        data: Vec<u8>,
     },
 
     #[error("Invalid attribute length for {attr_type:?}: expected {expected}, got {actual}")]
@@ -24,11 +32,168 @@ pub enum BgpParseError {
     #[error("Incomplete data: need {needed} more bytes")]
     IncompleteData { needed: usize },
 
+    #[error("Message too long to send: {length} octets, maximum {max}")]
+    MessageTooLong { length: usize, max: usize },
+
     #[error("Unknown attribute type: {attr_type}")]
     UnknownAttributeType { attr_type: u8 },
 
+    #[error("Malformed attribute {attr_type:?}: {sub_code:?}")]
+    MalformedAttribute {
+        attr_type: AttrType,
+        sub_code: UpdateError,
+        data: Vec<u8>,
+    },
+
     #[error("Header length is smaller than expected: got {actual}, expected {expected}")]
     InvalidHeaderLength { expected: usize, actual: usize },
+
+    #[error("Invalid message header: {sub_code:?}")]
+    InvalidHeader {
+        sub_code: HeaderError,
+        data: Vec<u8>,
+    },
+
+    #[error("Invalid OPEN message: {sub_code:?}")]
+    InvalidOpen { sub_code: OpenError, data: Vec<u8> },
+
+    #[error("Invalid UPDATE message: {sub_code:?}")]
+    InvalidUpdate {
+        sub_code: UpdateError,
+        data: Vec<u8>,
+    },
+
+    #[error("Invalid ROUTE-REFRESH message: {sub_code:?}")]
+    InvalidRouteRefresh {
+        sub_code: RouteRefreshError,
+        data: Vec<u8>,
+    },
+}
+
+// Error Subcode 0 is used when no appropriate subcode is defined (RFC4271 4.5).
+const UNSPECIFIC: u8 = 0;
+
+impl BgpParseError {
+    pub fn header_error(sub_code: HeaderError, data: &[u8]) -> Self {
+        BgpParseError::InvalidHeader {
+            sub_code,
+            data: data.to_vec(),
+        }
+    }
+
+    pub fn open_error(sub_code: OpenError, data: &[u8]) -> Self {
+        BgpParseError::InvalidOpen {
+            sub_code,
+            data: data.to_vec(),
+        }
+    }
+
+    pub fn update_error(sub_code: UpdateError, data: &[u8]) -> Self {
+        BgpParseError::InvalidUpdate {
+            sub_code,
+            data: data.to_vec(),
+        }
+    }
+
+    // Parse failure of the message body is reported with the Error Code of
+    // the message type. OPEN uses subcode 0 (Unspecific) and UPDATE uses
+    // Malformed Attribute List (RFC4271 6.2, 6.3). Other errors are kept.
+    pub fn message_error(self, typ: BgpType) -> Self {
+        match (self, typ) {
+            (BgpParseError::NomError(_), BgpType::Open) => {
+                Self::open_error(OpenError::Unknown(UNSPECIFIC), &[])
+            }
+            (BgpParseError::NomError(_), BgpType::Update) => {
+                Self::update_error(UpdateError::MalformedAttributeList, &[])
+            }
+            (err, _) => err,
+        }
+    }
+
+    // RFC4271 6.1: Data is the erroneous Length field.
+    pub fn bad_message_length(length: u16) -> Self {
+        Self::header_error(HeaderError::BadMessageLength, &length.to_be_bytes())
+    }
+
+    // RFC4271 6.2: Data is the largest locally supported version number.
+    pub fn unsupported_version() -> Self {
+        Self::open_error(
+            OpenError::UnsupportedVersionNumber,
+            &(BGP_VERSION as u16).to_be_bytes(),
+        )
+    }
+
+    // RFC5492 5: Data is the list of unsupported capabilities.
+    pub fn unsupported_capability(caps: &[CapabilityPacket]) -> Self {
+        let mut buf = BytesMut::new();
+        for cap in caps.iter() {
+            cap.cap_emit().emit(&mut buf, true);
+        }
+        Self::open_error(OpenError::UnsupportedCapability, &buf)
+    }
+}
+
+// IncompleteData is not an error of the peer but tells more bytes are needed
+// so it is returned as is. MessageTooLong is a local encode error.
+impl TryFrom<BgpParseError> for NotificationPacket {
+    type Error = BgpParseError;
+
+    fn try_from(err: BgpParseError) -> Result<Self, Self::Error> {
+        use BgpParseError::*;
+        let notification = match err {
+            InvalidHeader { sub_code, data } => {
+                NotificationPacket::new(NotifyCode::MsgHeaderError, sub_code.into(), data)
+            }
+            InvalidHeaderLength { actual, .. } => NotificationPacket::new(
+                NotifyCode::MsgHeaderError,
+                HeaderError::BadMessageLength.into(),
+                (actual as u16).to_be_bytes().to_vec(),
+            ),
+            InvalidOpen { sub_code, data } => {
+                NotificationPacket::new(NotifyCode::OpenMsgError, sub_code.into(), data)
+            }
+            InvalidUpdate { sub_code, data } => {
+                NotificationPacket::new(NotifyCode::UpdateMsgError, sub_code.into(), data)
+            }
+            InvalidRouteRefresh { sub_code, data } => {
+                NotificationPacket::new(NotifyCode::RouteRefreshError, sub_code.into(), data)
+            }
+            MalformedAttribute { sub_code, data, .. } => {
+                NotificationPacket::new(NotifyCode::UpdateMsgError, sub_code.into(), data)
+            }
+            AttributeParseError {
+                attr_type, data, ..
+            } => {
+                // RFC4271 6.3: Subcode depends on the attribute and Data is
+                // the erroneous attribute.
+                let sub_code = match attr_type {
+                    AttrType::Origin => UpdateError::InvalidOriginAttribute,
+                    AttrType::AsPath => UpdateError::MalformedAspath,
+                    AttrType::NextHop => UpdateError::InvalidNexthopAttribute,
+                    _ if attr_type.spec(true).is_none_or(|spec| spec.optional) => {
+                        UpdateError::OptionalAttributeError
+                    }
+                    _ => UpdateError::AttributeLengthError,
+                };
+                NotificationPacket::new(NotifyCode::UpdateMsgError, sub_code.into(), data)
+            }
+            InvalidAttributeLength { .. } => NotificationPacket::new(
+                NotifyCode::UpdateMsgError,
+                UpdateError::AttributeLengthError.into(),
+                Vec::new(),
+            ),
+            UnknownAttributeType { .. } => NotificationPacket::new(
+                NotifyCode::UpdateMsgError,
+                UpdateError::UnrecognizedWellknownAttribute.into(),
+                Vec::new(),
+            ),
+            NomError(_) => {
+                NotificationPacket::new(NotifyCode::MsgHeaderError, UNSPECIFIC, Vec::new())
+            }
+            IncompleteData { .. } | MessageTooLong { .. } => return Err(err),
+        };
+        Ok(notification)
+    }
 }
 
 impl From<nom::Err<nom::error::Error<&[u8]>>> for BgpParseError {
@@ -62,3 +227,58 @@ impl<I> nom::error::ParseError<I> for BgpParseError {
         }
     }
 }
+
+// Error handling approach for a malformed UPDATE message (RFC7606 2).
+#[derive(Debug)]
+pub enum UpdateErrorAction {
+    AttributeDiscard,
+    TreatAsWithdraw,
+    SessionReset(NotificationPacket),
+}
+
+impl UpdateErrorAction {
+    pub fn session_reset(sub_code: UpdateError, data: &[u8]) -> Self {
+        UpdateErrorAction::SessionReset(NotificationPacket::new(
+            NotifyCode::UpdateMsgError,
+            sub_code.into(),
+            data.to_vec(),
+        ))
+    }
+
+    fn severity(&self) -> u8 {
+        match self {
+            UpdateErrorAction::AttributeDiscard => 1,
+            UpdateErrorAction::TreatAsWithdraw => 2,
+            UpdateErrorAction::SessionReset(_) => 3,
+        }
+    }
+
+    // RFC7606 2: When multiple errors are found, the most severe approach is
+    // used. The first session reset is kept for the NOTIFICATION.
+    pub fn escalate(action: &mut Option<Self>, other: Self) {
+        if action
+            .as_ref()
+            .is_none_or(|action| other.severity() > action.severity())
+        {
+            *action = Some(other);
+        }
+    }
+
+    pub fn is_session_reset(&self) -> bool {
+        matches!(self, UpdateErrorAction::SessionReset(_))
+    }
+}
+
+impl fmt::Display for UpdateErrorAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UpdateErrorAction::AttributeDiscard => write!(f, "Attribute Discard"),
+            UpdateErrorAction::TreatAsWithdraw => write!(f, "Treat-as-withdraw"),
+            UpdateErrorAction::SessionReset(notification) => write!(
+                f,
+                "Session Reset ({}/{})",
+                notification.code, notification.sub_code
+            ),
+        }
+    }
+}
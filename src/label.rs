@@ -50,6 +50,15 @@ impl Label {
     pub fn from_bytes(bytes: &[u8]) -> Self {
         Label::from(bytes)
     }
+
+    // Label field 0x800000 in withdrawn labeled NLRI (RFC8277 2.4).
+    pub fn withdraw() -> Self {
+        Label::new(0x80000, 0, false)
+    }
+
+    pub fn is_withdraw(&self) -> bool {
+        self.label == 0x80000 && self.exp == 0 && !self.bos
+    }
 }
 
 impl Default for Label {
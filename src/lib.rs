@@ -1,13 +1,19 @@
+pub mod capability;
+pub mod codec;
 pub mod notification;
 pub mod open;
 pub mod packet;
 pub mod parser;
+pub mod route_refresh;
 pub mod update;
 
+pub use capability::*;
+pub use codec::*;
 pub use notification::*;
 pub use open::*;
 pub use packet::*;
 pub use parser::*;
+pub use route_refresh::*;
 pub use update::*;
 
 pub mod caps;
@@ -120,7 +120,7 @@ fn notify_sub_code_str(code: NotifyCode, sub_code: u8) -> String {
 }
 
 #[repr(u8)]
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum HeaderError {
     ConnectionNotSynced = 1,
     BadMessageLength = 2,
@@ -140,6 +140,18 @@ impl From<u8> for HeaderError {
     }
 }
 
+impl From<HeaderError> for u8 {
+    fn from(error: HeaderError) -> Self {
+        use HeaderError::*;
+        match error {
+            ConnectionNotSynced => 1,
+            BadMessageLength => 2,
+            BadMessageType => 3,
+            Unknown(v) => v,
+        }
+    }
+}
+
 fn sub_header_error_str(sub_code: HeaderError) -> String {
     use HeaderError::*;
     match sub_code {
@@ -151,7 +163,7 @@ fn sub_header_error_str(sub_code: HeaderError) -> String {
 }
 
 #[repr(u8)]
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum OpenError {
     UnsupportedVersionNumber = 1,
     BadPeerAS = 2,
@@ -212,7 +224,7 @@ fn sub_open_error_str(sub_code: OpenError) -> String {
 }
 
 #[repr(u8)]
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum UpdateError {
     MalformedAttributeList = 1,
     UnrecognizedWellknownAttribute = 2,
@@ -247,6 +259,25 @@ impl From<u8> for UpdateError {
     }
 }
 
+impl From<UpdateError> for u8 {
+    fn from(error: UpdateError) -> Self {
+        use UpdateError::*;
+        match error {
+            MalformedAttributeList => 1,
+            UnrecognizedWellknownAttribute => 2,
+            MissingWellknownAttribute => 3,
+            AttributeFlagsError => 4,
+            AttributeLengthError => 5,
+            InvalidOriginAttribute => 6,
+            InvalidNexthopAttribute => 8,
+            OptionalAttributeError => 9,
+            InvalidNetworkField => 10,
+            MalformedAspath => 11,
+            Unknown(v) => v,
+        }
+    }
+}
+
 fn sub_update_error_str(sub_code: UpdateError) -> String {
     use UpdateError::*;
     match sub_code {
@@ -349,7 +380,7 @@ fn sub_cease_error_str(sub_code: CeaseError) -> String {
 }
 
 #[repr(u8)]
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum RouteRefreshError {
     InvalidMessageLength = 1, // RFC7313
     Unknown(u8),
@@ -365,6 +396,16 @@ impl From<u8> for RouteRefreshError {
     }
 }
 
+impl From<RouteRefreshError> for u8 {
+    fn from(error: RouteRefreshError) -> Self {
+        use RouteRefreshError::*;
+        match error {
+            InvalidMessageLength => 1,
+            Unknown(v) => v,
+        }
+    }
+}
+
 fn sub_route_refresh_error_str(sub_code: RouteRefreshError) -> String {
     use RouteRefreshError::*;
     match sub_code {
@@ -2,14 +2,17 @@ use std::fmt;
 use std::net::Ipv4Addr;
 
 use bytes::{BufMut, BytesMut};
-use nom::IResult;
-use nom::error::{ErrorKind, make_error};
 use nom_derive::*;
 
-use crate::{BgpCap, BgpHeader, CapabilityHeader, CapabilityPacket, many0};
+use crate::{
+    BgpCap, BgpHeader, BgpParseError, CapabilityHeader, CapabilityPacket, OpenError, many0,
+};
 
 pub const BGP_VERSION: u8 = 4;
 
+// Optional Parameter Type for Capabilities.
+const OPT_PARAM_CAPABILITY: u8 = 2;
+
 #[derive(Debug, PartialEq, NomBE)]
 pub struct OpenPacket {
     pub header: BgpHeader,
@@ -47,33 +50,61 @@ impl OpenPacket {
         }
     }
 
-    pub fn parse_packet(input: &[u8]) -> IResult<&[u8], OpenPacket> {
+    // Parse OPEN message. Errors are reported as described in RFC4271 6.2.
+    pub fn parse_packet(input: &[u8]) -> Result<(&[u8], OpenPacket), BgpParseError> {
         let (input, mut packet) = OpenPacket::parse_be(input)?;
+        if packet.version != BGP_VERSION {
+            return Err(BgpParseError::unsupported_version());
+        }
+        // Hold Time must be either zero or at least three seconds.
+        if matches!(packet.hold_time, 1 | 2) {
+            return Err(BgpParseError::open_error(
+                OpenError::UnacceptableHoldTime,
+                &[],
+            ));
+        }
         let (input, len) = if packet.opt_param_len == 255 {
             let (input, ext) = OpenExtended::parse_be(input)?;
             if ext.non_ext_op_type != 255 {
-                return Err(nom::Err::Error(make_error(input, ErrorKind::Tag)));
+                return Err(BgpParseError::open_error(OpenError::Unknown(0), &[]));
             }
             (input, ext.ext_opt_parm_len)
         } else {
             (input, packet.opt_param_len as u16)
         };
         if input.len() != len as usize {
-            return Err(nom::Err::Error(make_error(input, ErrorKind::LengthValue)));
+            return Err(BgpParseError::open_error(OpenError::Unknown(0), &[]));
         }
         let (opts, input) = input.split_at(len as usize);
-        let (_, caps) = many0(parse_caps).parse(opts)?;
+        let caps = parse_opt_params(opts)?;
         let bgp_cap = BgpCap::from(caps);
         packet.bgp_cap = bgp_cap;
         Ok((input, packet))
     }
 }
 
-fn parse_caps(input: &[u8]) -> IResult<&[u8], Vec<CapabilityPacket>> {
-    let (input, header) = CapabilityHeader::parse_be(input)?;
-    let (opts, input) = input.split_at(header.length as usize);
-    let (_, caps) = many0(CapabilityPacket::parse_cap).parse(opts)?;
-    Ok((input, caps))
+fn parse_opt_params(mut input: &[u8]) -> Result<Vec<Vec<CapabilityPacket>>, BgpParseError> {
+    let mut caps = Vec::new();
+    while !input.is_empty() {
+        let (opts, header) = CapabilityHeader::parse_be(input)?;
+        // Capabilities is the only Optional Parameter (RFC5492).
+        if header.code != OPT_PARAM_CAPABILITY {
+            return Err(BgpParseError::open_error(
+                OpenError::UnsupportedOptionalParameter,
+                &[],
+            ));
+        }
+        if opts.len() < header.length as usize {
+            return Err(BgpParseError::open_error(OpenError::Unknown(0), &[]));
+        }
+        let (opts, remaining) = opts.split_at(header.length as usize);
+        let (_, cap) = many0(CapabilityPacket::parse_cap)
+            .parse(opts)
+            .map_err(|_| BgpParseError::open_error(OpenError::Unknown(0), &[]))?;
+        caps.push(cap);
+        input = remaining;
+    }
+    Ok(caps)
 }
 
 impl From<OpenPacket> for BytesMut {
@@ -1,13 +1,13 @@
 use bytes::{BufMut, BytesMut};
 use nom_derive::*;
 
-use crate::{NotificationPacket, OpenPacket, UpdatePacket};
+use crate::{CapabilityMessage, NotificationPacket, OpenPacket, RouteRefreshPacket, UpdatePacket};
 
 pub const BGP_PACKET_LEN: usize = 4096;
 pub const BGP_HEADER_LEN: u16 = 19;
 
 #[repr(u8)]
-#[derive(Debug, Eq, PartialEq, NomBE)]
+#[derive(Debug, Eq, PartialEq, Clone, Copy, NomBE)]
 pub enum BgpType {
     Open = 1,
     Update = 2,
@@ -18,7 +18,7 @@ pub enum BgpType {
     Max = 7,
 }
 
-#[derive(Debug, PartialEq, NomBE)]
+#[derive(Debug, PartialEq, Clone, NomBE)]
 pub struct BgpHeader {
     pub marker: [u8; 16],
     pub length: u16,
@@ -52,4 +52,19 @@ pub enum BgpPacket {
     Keepalive(BgpHeader),
     Notification(NotificationPacket),
     Update(Box<UpdatePacket>),
+    RouteRefresh(RouteRefreshPacket),
+    Capability(CapabilityMessage),
+}
+
+impl From<BgpPacket> for BytesMut {
+    fn from(packet: BgpPacket) -> Self {
+        match packet {
+            BgpPacket::Open(v) => (*v).into(),
+            BgpPacket::Keepalive(v) => v.into(),
+            BgpPacket::Notification(v) => v.into(),
+            BgpPacket::Update(v) => (*v).into(),
+            BgpPacket::RouteRefresh(v) => v.into(),
+            BgpPacket::Capability(v) => v.into(),
+        }
+    }
 }
@@ -1,12 +1,13 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::convert::TryInto;
 
 use nom::combinator::peek;
 use nom_derive::*;
 
 use crate::{
-    Afi, AfiSafi, BgpHeader, BgpPacket, BgpParseError, BgpType, NotificationPacket, OpenPacket,
-    Safi, UpdatePacket,
+    Afi, AfiSafi, BGP_HEADER_LEN, BgpCap, BgpHeader, BgpPacket, BgpParseError, BgpType,
+    CapabilityMessage, HeaderError, LlgrValue, NotificationPacket, OpenPacket, ROUTE_REFRESH_LEN,
+    RestartValue, RouteRefreshError, RouteRefreshPacket, RouteRefreshSubtype, Safi, UpdatePacket,
 };
 
 #[derive(Default, Debug, Clone)]
@@ -15,15 +16,122 @@ pub struct Direct {
     pub send: bool,
 }
 
+// Number of labels which can be received and sent (RFC8277).
+#[derive(Debug, Clone, Copy)]
+pub struct LabelCount {
+    pub recv: u8,
+    pub send: u8,
+}
+
 #[derive(Default, Debug, Clone)]
 pub struct ParseOption {
     // AS4
     pub as4: Direct,
     // AddPath
     pub add_path: BTreeMap<AfiSafi, Direct>,
+    // Multiprotocol AFI/SAFI supported by both speakers.
+    pub mp: BTreeSet<AfiSafi>,
+    // Extended Message (RFC8654).
+    pub extended: bool,
+    // Extended Next Hop Encoding (RFC8950). NLRI AFI/SAFI to next hop AFI.
+    pub extended_nexthop: BTreeMap<AfiSafi, Afi>,
+    // Multiple Labels (RFC8277).
+    pub multi_label: BTreeMap<AfiSafi, LabelCount>,
+    // Graceful Restart parameters of the peer for negotiated AFI/SAFI. None
+    // when either speaker does not advertise the capability.
+    pub restart: Option<BTreeMap<AfiSafi, RestartValue>>,
+    // Long-Lived Graceful Restart parameters of the peer for negotiated
+    // AFI/SAFI. None when either speaker does not advertise the capability.
+    pub llgr: Option<BTreeMap<AfiSafi, LlgrValue>>,
 }
 
 impl ParseOption {
+    // Session parameters from the capabilities of OPEN messages sent (local)
+    // and received (remote).
+    pub fn negotiate(local: &BgpCap, remote: &BgpCap) -> Self {
+        let mut opt = ParseOption {
+            as4: Direct {
+                recv: remote.as4.is_some(),
+                send: local.as4.is_some(),
+            },
+            extended: local.extended.is_some() && remote.extended.is_some(),
+            ..Default::default()
+        };
+
+        // RFC4760 8: Speaker without Multiprotocol capability supports IPv4
+        // unicast only.
+        let families = |cap: &BgpCap| -> BTreeSet<AfiSafi> {
+            if cap.mp.is_empty() {
+                BTreeSet::from([AfiSafi::new(Afi::Ip, Safi::Unicast)])
+            } else {
+                cap.mp.keys().copied().collect()
+            }
+        };
+        opt.mp = families(local)
+            .intersection(&families(remote))
+            .copied()
+            .collect();
+
+        // RFC7911 4: Receive is set when we are able to receive and the peer
+        // is able to send, and vice versa.
+        for (key, local) in local.addpath.iter() {
+            let Some(remote) = remote.addpath.get(key) else {
+                continue;
+            };
+            let direct = Direct {
+                recv: local.send_receive.is_receive() && remote.send_receive.is_send(),
+                send: local.send_receive.is_send() && remote.send_receive.is_receive(),
+            };
+            if direct.recv || direct.send {
+                opt.add_path.insert(*key, direct);
+            }
+        }
+
+        // RFC8950 4: Both speakers advertise the same next hop AFI for the
+        // NLRI AFI/SAFI.
+        for (key, local) in local.extended_nexthop.iter() {
+            if let Some(remote) = remote.extended_nexthop.get(key)
+                && local.nhop_afi == remote.nhop_afi
+            {
+                opt.extended_nexthop.insert(*key, local.nhop_afi);
+            }
+        }
+
+        // RFC8277 2.1: Multiple labels are sent only when both speakers
+        // advertise the capability for the AFI/SAFI.
+        for (key, local) in local.multi_label.iter() {
+            if let Some(remote) = remote.multi_label.get(key) {
+                let count = LabelCount {
+                    recv: local.count,
+                    send: remote.count,
+                };
+                opt.multi_label.insert(*key, count);
+            }
+        }
+
+        // The capability may carry no AFI/SAFI so the presence of the
+        // capability is checked.
+        if let (Some(_), Some(remote)) = (&local.restart, &remote.restart) {
+            opt.restart = Some(
+                remote
+                    .iter()
+                    .filter(|(key, _)| opt.mp.contains(key))
+                    .map(|(key, value)| (*key, value.clone()))
+                    .collect(),
+            );
+        }
+        if let (Some(_), Some(remote)) = (&local.llgr, &remote.llgr) {
+            opt.llgr = Some(
+                remote
+                    .iter()
+                    .filter(|(key, _)| opt.mp.contains(key))
+                    .map(|(key, value)| (*key, value.clone()))
+                    .collect(),
+            );
+        }
+        opt
+    }
+
     pub fn is_as4(&self) -> bool {
         self.as4.send && self.as4.recv
     }
@@ -38,9 +146,36 @@ impl ParseOption {
         self.add_path.get(&key).is_some_and(|direct| direct.send)
     }
 
+    pub fn is_mp(&self, afi: Afi, safi: Safi) -> bool {
+        self.mp.contains(&AfiSafi { afi, safi })
+    }
+
+    pub fn is_extended_nexthop(&self, afi: Afi, safi: Safi) -> bool {
+        let key = AfiSafi { afi, safi };
+        self.extended_nexthop.contains_key(&key)
+    }
+
+    // Number of labels which can be received from the peer.
+    pub fn label_recv_count(&self, afi: Afi, safi: Safi) -> u8 {
+        let key = AfiSafi { afi, safi };
+        self.multi_label.get(&key).map_or(1, |count| count.recv)
+    }
+
+    // Number of labels which can be sent to the peer.
+    pub fn label_send_count(&self, afi: Afi, safi: Safi) -> u8 {
+        let key = AfiSafi { afi, safi };
+        self.multi_label.get(&key).map_or(1, |count| count.send)
+    }
+
     pub fn clear(&mut self) {
         self.as4 = Direct::default();
         self.add_path.clear();
+        self.mp.clear();
+        self.extended = false;
+        self.extended_nexthop.clear();
+        self.multi_label.clear();
+        self.restart = None;
+        self.llgr = None;
     }
 }
 
@@ -56,20 +191,63 @@ pub fn peek_bgp_length(input: &[u8]) -> usize {
     }
 }
 
+// Minimum message length for each message type.
+fn min_message_len(typ: u8) -> Option<u16> {
+    match typ {
+        1 => Some(29), // OPEN
+        2 => Some(23), // UPDATE
+        3 => Some(21), // NOTIFICATION
+        4 => Some(19), // KEEPALIVE
+        5 => Some(19), // ROUTE-REFRESH, the length is checked as RFC7313.
+        6 => Some(19), // CAPABILITY
+        _ => None,
+    }
+}
+
+// RFC4271 6.1: Message Header error handling.
+fn validate_header(input: &[u8]) -> Result<(), BgpParseError> {
+    if input.len() < BGP_HEADER_LEN as usize {
+        return Err(BgpParseError::IncompleteData {
+            needed: BGP_HEADER_LEN as usize - input.len(),
+        });
+    }
+    if input[..16].iter().any(|x| *x != 0xff) {
+        return Err(BgpParseError::header_error(
+            HeaderError::ConnectionNotSynced,
+            &[],
+        ));
+    }
+    let length = peek_bgp_length(input) as u16;
+    let typ = input[18];
+    let Some(min_len) = min_message_len(typ) else {
+        return Err(BgpParseError::header_error(
+            HeaderError::BadMessageType,
+            &[typ],
+        ));
+    };
+    // KEEPALIVE is header only.
+    if length < min_len || (typ == 4 && length != BGP_HEADER_LEN) {
+        return Err(BgpParseError::bad_message_length(length));
+    }
+    Ok(())
+}
+
 impl BgpPacket {
-    pub fn parse_packet(
-        input: &[u8],
-        as4: bool,
-        opt: Option<ParseOption>,
-    ) -> Result<(&[u8], BgpPacket), BgpParseError> {
+    pub fn parse_packet<'a>(
+        input: &'a [u8],
+        opt: &Option<ParseOption>,
+    ) -> Result<(&'a [u8], BgpPacket), BgpParseError> {
+        validate_header(input)?;
         let (_, header) = peek(BgpHeader::parse_be).parse(input)?;
         match header.typ {
             BgpType::Open => {
-                let (input, packet) = OpenPacket::parse_packet(input)?;
+                let (input, packet) = OpenPacket::parse_packet(input)
+                    .map_err(|err| err.message_error(BgpType::Open))?;
                 Ok((input, BgpPacket::Open(Box::new(packet))))
             }
             BgpType::Update => {
-                let (input, p) = UpdatePacket::parse_packet(input, as4, opt)?;
+                let (input, p) = UpdatePacket::parse_packet(input, opt)
+                    .map_err(|err| err.message_error(BgpType::Update))?;
                 Ok((input, BgpPacket::Update(Box::new(p))))
             }
             BgpType::Notification => {
@@ -80,9 +258,122 @@ impl BgpPacket {
                 let (input, header) = BgpHeader::parse_be(input)?;
                 Ok((input, BgpPacket::Keepalive(header)))
             }
-            _ => Err(BgpParseError::NomError(
-                "Unknown BGP packet type".to_string(),
+            BgpType::RouteRefresh => {
+                if header.length != ROUTE_REFRESH_LEN {
+                    // RFC7313 5: Data is the complete ROUTE-REFRESH message.
+                    let subtype = input.get(21).map(|v| RouteRefreshSubtype::from(*v));
+                    if matches!(
+                        subtype,
+                        Some(RouteRefreshSubtype::Borr | RouteRefreshSubtype::Eorr)
+                    ) {
+                        let len = (header.length as usize).min(input.len());
+                        return Err(BgpParseError::InvalidRouteRefresh {
+                            sub_code: RouteRefreshError::InvalidMessageLength,
+                            data: input[..len].to_vec(),
+                        });
+                    }
+                    return Err(BgpParseError::bad_message_length(header.length));
+                }
+                let (input, packet) = RouteRefreshPacket::parse_packet(input)?;
+                Ok((input, BgpPacket::RouteRefresh(packet)))
+            }
+            BgpType::Capability => {
+                let (input, packet) = CapabilityMessage::parse_packet(input)?;
+                Ok((input, BgpPacket::Capability(packet)))
+            }
+            _ => Err(BgpParseError::header_error(
+                HeaderError::BadMessageType,
+                &[header.typ as u8],
             )),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        AddPathValue, CapAs4, CapExtended, CapMultiProtocol, ExtendedNextHopValue, MultiLabelValue,
+    };
+
+    fn cap(families: &[(Afi, Safi)], send_receive: u8) -> BgpCap {
+        let mut cap = BgpCap {
+            as4: Some(CapAs4::new(65000)),
+            extended: Some(CapExtended::default()),
+            ..Default::default()
+        };
+        for (afi, safi) in families.iter() {
+            let key = AfiSafi::new(*afi, *safi);
+            cap.mp.insert(key, CapMultiProtocol::new(afi, safi));
+            let value = AddPathValue {
+                afi: *afi,
+                safi: *safi,
+                send_receive: send_receive.into(),
+            };
+            cap.addpath.insert(key, value);
+            cap.restart
+                .get_or_insert_default()
+                .insert(key, RestartValue::new(120, *afi, *safi));
+        }
+        cap
+    }
+
+    #[test]
+    fn negotiate() {
+        let mut local = cap(&[(Afi::Ip, Safi::Unicast), (Afi::Ip6, Safi::Unicast)], 1);
+        let mut remote = cap(&[(Afi::Ip6, Safi::Unicast), (Afi::L2vpn, Safi::Evpn)], 3);
+        remote.extended = None;
+
+        // IPv4 unicast over IPv6 next hop on both, VPNv4 on remote only.
+        let key = AfiSafi::new(Afi::Ip, Safi::Unicast);
+        let value = ExtendedNextHopValue::new(Afi::Ip, Safi::Unicast, Afi::Ip6);
+        local.extended_nexthop.insert(key, value.clone());
+        remote.extended_nexthop.insert(key, value);
+        let key = AfiSafi::new(Afi::Ip, Safi::MplsVpn);
+        let value = ExtendedNextHopValue::new(Afi::Ip, Safi::MplsVpn, Afi::Ip6);
+        remote.extended_nexthop.insert(key, value);
+
+        // Multiple Labels on IPv4 labeled unicast.
+        let key = AfiSafi::new(Afi::Ip, Safi::MplsLabel);
+        let value = MultiLabelValue::new(Afi::Ip, Safi::MplsLabel, 4);
+        local.multi_label.insert(key, value);
+        let value = MultiLabelValue::new(Afi::Ip, Safi::MplsLabel, 2);
+        remote.multi_label.insert(key, value);
+
+        let opt = ParseOption::negotiate(&local, &remote);
+        assert!(opt.is_as4());
+        assert!(!opt.extended);
+        assert_eq!(
+            opt.mp,
+            BTreeSet::from([AfiSafi::new(Afi::Ip6, Safi::Unicast)])
+        );
+        assert!(opt.is_add_path_recv(Afi::Ip6, Safi::Unicast));
+        assert!(!opt.is_add_path_send(Afi::Ip6, Safi::Unicast));
+        assert!(!opt.is_add_path_recv(Afi::L2vpn, Safi::Evpn));
+        assert_eq!(opt.restart.as_ref().unwrap().len(), 1);
+        assert!(opt.llgr.is_none());
+        assert!(opt.is_extended_nexthop(Afi::Ip, Safi::Unicast));
+        assert!(!opt.is_extended_nexthop(Afi::Ip, Safi::MplsVpn));
+        assert_eq!(opt.label_send_count(Afi::Ip, Safi::MplsLabel), 2);
+        assert_eq!(opt.label_recv_count(Afi::Ip, Safi::MplsLabel), 4);
+        assert_eq!(opt.label_send_count(Afi::Ip6, Safi::MplsLabel), 1);
+
+        // Without Multiprotocol and AS4 capability on the peer.
+        let opt = ParseOption::negotiate(&local, &BgpCap::default());
+        assert!(!opt.is_as4());
+        assert!(opt.is_mp(Afi::Ip, Safi::Unicast));
+        assert_eq!(opt.mp.len(), 1);
+        assert!(opt.add_path.is_empty());
+        assert!(opt.restart.is_none());
+
+        // Graceful Restart helper mode has no AFI/SAFI.
+        let helper = BgpCap {
+            restart: Some(BTreeMap::new()),
+            ..Default::default()
+        };
+        let opt = ParseOption::negotiate(&helper, &local);
+        assert_eq!(opt.restart.unwrap().len(), 1);
+        let opt = ParseOption::negotiate(&local, &helper);
+        assert!(opt.restart.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,146 @@
+use std::fmt;
+
+use bytes::{BufMut, BytesMut};
+use nom::IResult;
+use nom::error::{ErrorKind, make_error};
+use nom::number::complete::be_u8;
+use nom_derive::*;
+
+use crate::{Afi, BgpHeader, BgpType, Safi};
+
+pub const ROUTE_REFRESH_LEN: u16 = 23;
+
+#[derive(Debug, NomBE)]
+pub struct RouteRefreshPacket {
+    pub header: BgpHeader,
+    pub afi: Afi,
+    pub subtype: RouteRefreshSubtype,
+    pub safi: Safi,
+}
+
+// Message Subtype as defined in RFC7313. It was "Reserved" in RFC2918.
+#[repr(u8)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum RouteRefreshSubtype {
+    Normal = 0,
+    Borr = 1,
+    Eorr = 2,
+    Unknown(u8),
+}
+
+impl RouteRefreshSubtype {
+    pub fn parse_be(input: &[u8]) -> IResult<&[u8], Self> {
+        let (input, subtype) = be_u8(input)?;
+        let subtype: Self = subtype.into();
+        Ok((input, subtype))
+    }
+}
+
+impl From<RouteRefreshSubtype> for u8 {
+    fn from(subtype: RouteRefreshSubtype) -> Self {
+        use RouteRefreshSubtype::*;
+        match subtype {
+            Normal => 0,
+            Borr => 1,
+            Eorr => 2,
+            Unknown(v) => v,
+        }
+    }
+}
+
+impl From<u8> for RouteRefreshSubtype {
+    fn from(subtype: u8) -> Self {
+        use RouteRefreshSubtype::*;
+        match subtype {
+            0 => Normal,
+            1 => Borr,
+            2 => Eorr,
+            v => Unknown(v),
+        }
+    }
+}
+
+impl fmt::Display for RouteRefreshSubtype {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use RouteRefreshSubtype::*;
+        match self {
+            Normal => write!(f, "Normal"),
+            Borr => write!(f, "BoRR"),
+            Eorr => write!(f, "EoRR"),
+            Unknown(v) => write!(f, "Unknown({})", v),
+        }
+    }
+}
+
+impl RouteRefreshPacket {
+    pub fn new(afi: Afi, safi: Safi, subtype: RouteRefreshSubtype) -> Self {
+        Self {
+            header: BgpHeader::new(BgpType::RouteRefresh, ROUTE_REFRESH_LEN),
+            afi,
+            subtype,
+            safi,
+        }
+    }
+
+    pub fn parse_packet(input: &[u8]) -> IResult<&[u8], RouteRefreshPacket> {
+        let (input, packet) = RouteRefreshPacket::parse_be(input)?;
+        // RFC7313: A message with length other than 23 is invalid.
+        if packet.header.length != ROUTE_REFRESH_LEN {
+            return Err(nom::Err::Error(make_error(input, ErrorKind::LengthValue)));
+        }
+        Ok((input, packet))
+    }
+}
+
+impl From<RouteRefreshPacket> for BytesMut {
+    fn from(refresh: RouteRefreshPacket) -> Self {
+        let mut buf = BytesMut::new();
+        let header: BytesMut = refresh.header.into();
+        buf.put(&header[..]);
+        buf.put_u16(refresh.afi.into());
+        buf.put_u8(refresh.subtype.into());
+        buf.put_u8(refresh.safi.into());
+
+        const LENGTH_POS: std::ops::Range<usize> = 16..18;
+        let length: u16 = buf.len() as u16;
+        buf[LENGTH_POS].copy_from_slice(&length.to_be_bytes());
+
+        buf
+    }
+}
+
+impl fmt::Display for RouteRefreshPacket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Route Refresh Message:")?;
+        writeln!(f, " AFI/SAFI: {}/{}", self.afi, self.safi)?;
+        writeln!(f, " Subtype: {}", self.subtype)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn route_refresh_roundtrip() {
+        let refresh = RouteRefreshPacket::new(Afi::Ip6, Safi::Unicast, RouteRefreshSubtype::Borr);
+        let buf: BytesMut = refresh.into();
+        assert_eq!(buf.len(), ROUTE_REFRESH_LEN as usize);
+        assert_eq!(&buf[16..], &[0x00, 0x17, 0x05, 0x00, 0x02, 0x01, 0x01]);
+
+        let (rest, packet) = RouteRefreshPacket::parse_packet(&buf).unwrap();
+        assert!(rest.is_empty());
+        assert_eq!(packet.afi, Afi::Ip6);
+        assert_eq!(packet.safi, Safi::Unicast);
+        assert_eq!(packet.subtype, RouteRefreshSubtype::Borr);
+    }
+
+    #[test]
+    fn route_refresh_invalid_length() {
+        let mut buf: BytesMut =
+            RouteRefreshPacket::new(Afi::Ip, Safi::Unicast, RouteRefreshSubtype::Eorr).into();
+        buf.put_u8(0);
+        buf[16..18].copy_from_slice(&24u16.to_be_bytes());
+        assert!(RouteRefreshPacket::parse_packet(&buf).is_err());
+    }
+}
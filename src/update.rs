@@ -6,8 +6,8 @@ use nom_derive::*;
 
 use crate::{
     Afi, BGP_HEADER_LEN, BgpAttr, BgpHeader, BgpParseError, BgpType, Ipv4Nlri, MpNlriReachAttr,
-    MpNlriUnreachAttr, ParseOption, Safi, nlri_psize, parse_bgp_nlri_ipv4,
-    parse_bgp_update_attribute,
+    MpNlriUnreachAttr, ParseOption, Safi, UpdateError, UpdateErrorAction, nlri_psize,
+    parse_bgp_nlri_ipv4, parse_bgp_update_attribute,
 };
 
 #[derive(NomBE)]
@@ -23,6 +23,8 @@ pub struct UpdatePacket {
     pub mp_update: Option<MpNlriReachAttr>,
     #[nom(Ignore)]
     pub mp_withdraw: Option<MpNlriUnreachAttr>,
+    #[nom(Ignore)]
+    pub error_action: Option<UpdateErrorAction>,
 }
 
 impl UpdatePacket {
@@ -40,21 +42,30 @@ impl Default for UpdatePacket {
             ipv4_withdraw: Vec::new(),
             mp_update: None,
             mp_withdraw: None,
+            error_action: None,
         }
     }
 }
 
 impl From<UpdatePacket> for BytesMut {
     fn from(update: UpdatePacket) -> Self {
+        update.emit_packet(true)
+    }
+}
+
+impl UpdatePacket {
+    // Emit UPDATE message. When `as4` is false, the message is encoded for
+    // OLD BGP speaker which does not support 4 octet AS number.
+    pub fn emit_packet(&self, as4: bool) -> BytesMut {
         let mut buf = BytesMut::new();
-        let header: BytesMut = update.header.into();
+        let header: BytesMut = self.header.clone().into();
         buf.put(&header[..]);
 
         // IPv4 unicast withdraw.
         let withdraw_len_pos = buf.len();
         buf.put_u16(0u16); // Placeholder.
         let withdraw_pos: std::ops::Range<usize> = withdraw_len_pos..withdraw_len_pos + 2;
-        for ip in update.ipv4_withdraw.iter() {
+        for ip in self.ipv4_withdraw.iter() {
             if ip.id != 0 {
                 buf.put_u32(ip.id);
             }
@@ -71,17 +82,17 @@ impl From<UpdatePacket> for BytesMut {
         let attr_pos: std::ops::Range<usize> = attr_len_pos..attr_len_pos + 2;
 
         // Attributes emit.
-        if let Some(bgp_attr) = update.bgp_attr {
-            bgp_attr.attr_emit(&mut buf);
+        if let Some(bgp_attr) = &self.bgp_attr {
+            bgp_attr.attr_emit_as4(&mut buf, as4);
         }
 
         // MP reach.
-        if let Some(mp_update) = update.mp_update {
+        if let Some(mp_update) = &self.mp_update {
             mp_update.attr_emit(&mut buf);
         }
 
         // MP reach.
-        if let Some(mp_withdraw) = update.mp_withdraw {
+        if let Some(mp_withdraw) = &self.mp_withdraw {
             mp_withdraw.attr_emit(&mut buf);
         }
 
@@ -89,7 +100,7 @@ impl From<UpdatePacket> for BytesMut {
         buf[attr_pos].copy_from_slice(&attr_len.to_be_bytes());
 
         // IPv4 unicast update.
-        for ip in update.ipv4_update.iter() {
+        for ip in self.ipv4_update.iter() {
             if ip.id != 0 {
                 buf.put_u32(ip.id);
             }
@@ -144,37 +155,90 @@ impl fmt::Display for UpdatePacket {
         {
             writeln!(f, " EoR: IPv4/Unicast")?;
         }
+        if let Some(error_action) = &self.error_action {
+            writeln!(f, " Error: {}", error_action)?;
+        }
         Ok(())
     }
 }
 
 impl UpdatePacket {
-    pub fn parse_packet(
-        input: &[u8],
-        as4: bool,
-        opt: Option<ParseOption>,
-    ) -> Result<(&[u8], UpdatePacket), BgpParseError> {
+    // Parse UPDATE message. Errors found in the message are handled as
+    // described in RFC7606 and the approach is set to `error_action` with the
+    // partially parsed message. Err is returned only when the message itself
+    // is not available.
+    pub fn parse_packet<'a>(
+        input: &'a [u8],
+        opt: &Option<ParseOption>,
+    ) -> Result<(&'a [u8], UpdatePacket), BgpParseError> {
         let add_path = if let Some(opt) = opt.as_ref() {
             opt.is_add_path_recv(Afi::Ip, Safi::Unicast)
         } else {
             false
         };
         let (input, mut packet) = UpdatePacket::parse_be(input)?;
-        let (input, withdraw_len) = be_u16(input)?;
-        let (input, mut withdrawal) = parse_bgp_nlri_ipv4(input, withdraw_len, add_path)?;
-        packet.ipv4_withdraw.append(&mut withdrawal);
-        let (input, attr_len) = be_u16(input)?;
-        let (input, bgp_attr, mp_update, mp_withdraw) = if attr_len > 0 {
-            parse_bgp_update_attribute(input, attr_len, as4, opt)?
-        } else {
-            (input, None, None, None)
-        };
-        packet.bgp_attr = bgp_attr;
-        packet.mp_update = mp_update;
-        packet.mp_withdraw = mp_withdraw;
-        let nlri_len = packet.header.length - BGP_HEADER_LEN - 2 - withdraw_len - 2 - attr_len;
-        let (input, mut updates) = parse_bgp_nlri_ipv4(input, nlri_len, add_path)?;
-        packet.ipv4_update.append(&mut updates);
+        let length = packet.header.length.saturating_sub(BGP_HEADER_LEN) as usize;
+        if input.len() < length {
+            return Err(BgpParseError::IncompleteData {
+                needed: length - input.len(),
+            });
+        }
+        let (body, input) = input.split_at(length);
+
+        // RFC7606 5.3: Withdrawn Routes Length and Total Attribute Length
+        // must be consistent with the message length to locate NLRI.
+        let (body, withdraw_len) = be_u16(body)?;
+        if body.len() < withdraw_len as usize + 2 {
+            packet.error_action = Some(UpdateErrorAction::session_reset(
+                UpdateError::MalformedAttributeList,
+                &[],
+            ));
+            return Ok((input, packet));
+        }
+        let (withdraw, body) = body.split_at(withdraw_len as usize);
+        let (nlri, attr_len) = be_u16(body)?;
+        if nlri.len() < attr_len as usize {
+            packet.error_action = Some(UpdateErrorAction::session_reset(
+                UpdateError::MalformedAttributeList,
+                &[],
+            ));
+            return Ok((input, packet));
+        }
+        let (attr, nlri) = nlri.split_at(attr_len as usize);
+
+        // RFC7606 5.3: Syntactic error in NLRI field resets the session.
+        match parse_bgp_nlri_ipv4(withdraw, withdraw_len, add_path) {
+            Ok((_, mut withdrawal)) => packet.ipv4_withdraw.append(&mut withdrawal),
+            Err(_) => UpdateErrorAction::escalate(
+                &mut packet.error_action,
+                UpdateErrorAction::session_reset(UpdateError::InvalidNetworkField, &[]),
+            ),
+        }
+        match parse_bgp_nlri_ipv4(nlri, nlri.len() as u16, add_path) {
+            Ok((_, mut updates)) => packet.ipv4_update.append(&mut updates),
+            Err(_) => UpdateErrorAction::escalate(
+                &mut packet.error_action,
+                UpdateErrorAction::session_reset(UpdateError::InvalidNetworkField, &[]),
+            ),
+        }
+
+        if attr_len > 0 {
+            let ipv4_nlri = !nlri.is_empty();
+            let (_, bgp_attr, mp_update, mp_withdraw, action) =
+                parse_bgp_update_attribute(attr, attr_len, ipv4_nlri, opt)?;
+            packet.bgp_attr = bgp_attr;
+            packet.mp_update = mp_update;
+            packet.mp_withdraw = mp_withdraw;
+            if let Some(action) = action {
+                UpdateErrorAction::escalate(&mut packet.error_action, action);
+            }
+        } else if !nlri.is_empty() {
+            // RFC7606 3.d: NLRI without any attribute.
+            UpdateErrorAction::escalate(
+                &mut packet.error_action,
+                UpdateErrorAction::TreatAsWithdraw,
+            );
+        }
         Ok((input, packet))
     }
 }
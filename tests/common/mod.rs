@@ -0,0 +1,10 @@
+use bgp_packet::{BgpCap, CapAs4, ParseOption};
+
+// Session with 4 octet AS number capability on both sides.
+pub fn as4_opt() -> Option<ParseOption> {
+    let cap = BgpCap {
+        as4: Some(CapAs4::new(65000)),
+        ..Default::default()
+    };
+    Some(ParseOption::negotiate(&cap, &cap))
+}
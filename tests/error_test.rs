@@ -1,6 +1,12 @@
-use bgp_packet::{BgpPacket, BgpParseError};
+use bgp_packet::{
+    BgpPacket, BgpParseError, BgpType, CapRefresh, CapabilityPacket, HeaderError,
+    NotificationPacket, NotifyCode, OpenError, UpdateError, UpdateErrorAction, UpdatePacket,
+};
 use hex_literal::hex;
 
+mod common;
+use common::as4_opt;
+
 #[test]
 fn test_attribute_parse_error_context() {
     // Create invalid BGP Update packet with truncated attribute
@@ -15,7 +21,7 @@ fn test_attribute_parse_error_context() {
         "00"   // Truncated data
     );
 
-    let result = BgpPacket::parse_packet(&invalid_update, false, None);
+    let result = BgpPacket::parse_packet(&invalid_update, &None);
 
     match result {
         Err(BgpParseError::IncompleteData { needed }) => {
@@ -25,10 +31,7 @@ fn test_attribute_parse_error_context() {
             );
             assert!(needed > 0);
         }
-        Err(BgpParseError::AttributeParseError {
-            attr_type,
-            source: _,
-        }) => {
+        Err(BgpParseError::AttributeParseError { attr_type, .. }) => {
             println!(
                 "Successfully caught attribute parse error for: {:?}",
                 attr_type
@@ -52,6 +55,7 @@ fn test_error_display() {
     let error = BgpParseError::AttributeParseError {
         attr_type: AttrType::Origin,
         source: Box::new(BgpParseError::NomError("test error".to_string())),
+        data: Vec::new(),
     };
 
     let error_string = format!("{}", error);
@@ -59,3 +63,267 @@ fn test_error_display() {
     assert!(error_string.contains("Failed to parse BGP attribute"));
     println!("Error display: {}", error_string);
 }
+
+fn parse_update(packet: &[u8]) -> UpdatePacket {
+    match BgpPacket::parse_packet(packet, &as4_opt()) {
+        Ok((_, BgpPacket::Update(update))) => *update,
+        other => panic!("Expected Update packet but got {:?}", other.err()),
+    }
+}
+
+#[test]
+fn test_treat_as_withdraw() {
+    let update = parse_update(&hex!(
+        "ffffffffffffffffffffffffffffffff" // BGP marker
+        "002d" // Length: 45 bytes
+        "02"   // Type: Update
+        "0000" // Withdrawal length: 0
+        "0014" // Path attributes length: 20
+        "40010100"           // ORIGIN
+        "400200"             // AS_PATH
+        "4003040a000001"     // NEXT_HOP
+        "800403000001"       // MED with length 3
+        "080a" // NLRI: 10.0.0.0/8
+    ));
+    assert!(matches!(
+        update.error_action,
+        Some(UpdateErrorAction::TreatAsWithdraw)
+    ));
+    assert_eq!(update.ipv4_update.len(), 1);
+    assert_eq!(update.ipv4_update[0].prefix.to_string(), "10.0.0.0/8");
+    let attr = update.bgp_attr.unwrap();
+    assert!(attr.origin.is_some());
+    assert!(attr.med.is_none());
+}
+
+#[test]
+fn test_attribute_discard() {
+    let update = parse_update(&hex!(
+        "ffffffffffffffffffffffffffffffff" // BGP marker
+        "0031" // Length: 49 bytes
+        "02"   // Type: Update
+        "0000" // Withdrawal length: 0
+        "0018" // Path attributes length: 24
+        "40010100"           // ORIGIN
+        "400200"             // AS_PATH
+        "4003040a000001"     // NEXT_HOP
+        "c00703000001"       // AGGREGATOR with length 3
+        "4001 01 01"         // Duplicate ORIGIN
+        "080a" // NLRI: 10.0.0.0/8
+    ));
+    assert!(matches!(
+        update.error_action,
+        Some(UpdateErrorAction::AttributeDiscard)
+    ));
+    let attr = update.bgp_attr.unwrap();
+    assert!(attr.aggregator.is_none());
+    assert_eq!(attr.origin.unwrap(), bgp_packet::Origin::Igp);
+}
+
+#[test]
+fn test_missing_wellknown_attribute() {
+    let update = parse_update(&hex!(
+        "ffffffffffffffffffffffffffffffff" // BGP marker
+        "001d" // Length: 29 bytes
+        "02"   // Type: Update
+        "0000" // Withdrawal length: 0
+        "0004" // Path attributes length: 4
+        "40010100" // ORIGIN
+        "080a"     // NLRI: 10.0.0.0/8
+    ));
+    assert!(matches!(
+        update.error_action,
+        Some(UpdateErrorAction::TreatAsWithdraw)
+    ));
+}
+
+#[test]
+fn test_session_reset() {
+    // Withdrawal length overruns the message.
+    let update = parse_update(&hex!(
+        "ffffffffffffffffffffffffffffffff" // BGP marker
+        "0017" // Length: 23 bytes
+        "02"   // Type: Update
+        "0004" // Withdrawal length: 4
+        "0000" // Path attributes length: 0
+    ));
+    let Some(UpdateErrorAction::SessionReset(notification)) = update.error_action else {
+        panic!("Expected session reset");
+    };
+    assert_eq!(notification.code, NotifyCode::UpdateMsgError);
+    assert_eq!(
+        notification.sub_code,
+        u8::from(UpdateError::MalformedAttributeList)
+    );
+}
+
+#[test]
+fn test_attribute_flags_error() {
+    let packet = hex!(
+        "ffffffffffffffffffffffffffffffff" // BGP marker
+        "001e" // Length: 30 bytes
+        "02"   // Type: Update
+        "0000" // Withdrawal length: 0
+        "0007" // Path attributes length: 7
+        "c0010100" // ORIGIN with Optional bit
+        "4f0200"   // AS_PATH with unused flag bits
+    );
+    let update = parse_update(&packet);
+    assert!(matches!(
+        update.error_action,
+        Some(UpdateErrorAction::TreatAsWithdraw)
+    ));
+    let attr = update.bgp_attr.unwrap();
+    assert!(attr.origin.is_none());
+    assert!(attr.aspath.is_some());
+
+    // The error carries UPDATE subcode and the attribute.
+    let result = bgp_packet::Attr::parse_attr(&packet[23..], &as4_opt());
+    let Err(BgpParseError::MalformedAttribute { sub_code, data, .. }) = result else {
+        panic!("Expected malformed attribute");
+    };
+    assert_eq!(sub_code, UpdateError::AttributeFlagsError);
+    assert_eq!(data, hex!("c0010100"));
+}
+
+fn parse_notification(packet: &[u8]) -> NotificationPacket {
+    match BgpPacket::parse_packet(packet, &as4_opt()) {
+        Err(err) => err.try_into().unwrap(),
+        Ok((_, packet)) => panic!("Expected parse error but got {:?}", packet),
+    }
+}
+
+#[test]
+fn test_header_error_notification() {
+    // Bad Message Length with the length.
+    let notification = parse_notification(&hex!(
+        "ffffffffffffffffffffffffffffffff" // BGP marker
+        "0014" // Length: 20 bytes
+        "04"   // Type: Keepalive
+        "00"
+    ));
+    assert_eq!(notification.code, NotifyCode::MsgHeaderError);
+    assert_eq!(
+        notification.sub_code,
+        u8::from(HeaderError::BadMessageLength)
+    );
+    assert_eq!(notification.data, hex!("0014"));
+
+    // Bad Message Type with the type.
+    let notification = parse_notification(&hex!(
+        "ffffffffffffffffffffffffffffffff" // BGP marker
+        "0013" // Length: 19 bytes
+        "0a"   // Type: 10
+    ));
+    assert_eq!(notification.sub_code, u8::from(HeaderError::BadMessageType));
+    assert_eq!(notification.data, hex!("0a"));
+
+    // Connection Not Synchronized.
+    let notification = parse_notification(&hex!(
+        "ffffffffffffffffffffffffffff0000" // BGP marker
+        "0013" // Length: 19 bytes
+        "04"   // Type: Keepalive
+    ));
+    assert_eq!(
+        notification.sub_code,
+        u8::from(HeaderError::ConnectionNotSynced)
+    );
+}
+
+#[test]
+fn test_open_error_notification() {
+    // Unsupported Version Number with the supported version.
+    let notification = parse_notification(&hex!(
+        "ffffffffffffffffffffffffffffffff" // BGP marker
+        "001d" // Length: 29 bytes
+        "01"   // Type: Open
+        "03"   // Version: 3
+        "fde8" // AS: 65000
+        "00b4" // Hold Time: 180
+        "0a000001" // BGP Identifier
+        "00"   // Optional Parameters Length: 0
+    ));
+    assert_eq!(notification.code, NotifyCode::OpenMsgError);
+    assert_eq!(
+        notification.sub_code,
+        u8::from(OpenError::UnsupportedVersionNumber)
+    );
+    assert_eq!(notification.data, hex!("0004"));
+
+    // Unsupported Optional Parameter.
+    let notification = parse_notification(&hex!(
+        "ffffffffffffffffffffffffffffffff" // BGP marker
+        "001f" // Length: 31 bytes
+        "01"   // Type: Open
+        "04"   // Version: 4
+        "fde8" // AS: 65000
+        "00b4" // Hold Time: 180
+        "0a000001" // BGP Identifier
+        "02"   // Optional Parameters Length: 2
+        "0100" // Authentication (deprecated)
+    ));
+    assert_eq!(
+        notification.sub_code,
+        u8::from(OpenError::UnsupportedOptionalParameter)
+    );
+
+    // Unsupported Capability with the capability.
+    let err = BgpParseError::unsupported_capability(&[CapabilityPacket::RouteRefresh(
+        CapRefresh::default(),
+    )]);
+    let notification = NotificationPacket::try_from(err).unwrap();
+    assert_eq!(
+        notification.sub_code,
+        u8::from(OpenError::UnsupportedCapability)
+    );
+    assert_eq!(notification.data, hex!("0200"));
+
+    // Unknown capability is sent back with the received code and value.
+    let (_, cap) = CapabilityPacket::parse_cap(&hex!("f002aabb")).unwrap();
+    let err = BgpParseError::unsupported_capability(&[cap]);
+    let notification = NotificationPacket::try_from(err).unwrap();
+    assert_eq!(notification.data, hex!("f002aabb"));
+}
+
+#[test]
+fn test_update_error_notification() {
+    let packet = hex!("c0010100");
+    let err = bgp_packet::Attr::parse_attr(&packet, &as4_opt()).unwrap_err();
+    let notification = NotificationPacket::try_from(err).unwrap();
+    assert_eq!(notification.code, NotifyCode::UpdateMsgError);
+    assert_eq!(
+        notification.sub_code,
+        u8::from(UpdateError::AttributeFlagsError)
+    );
+    assert_eq!(notification.data, packet);
+}
+
+#[test]
+fn test_attribute_parse_error_notification() {
+    // AS_PATH segment claims 5 ASes but carries only 1.
+    let packet = hex!("400206020500000001");
+    let err = bgp_packet::Attr::parse_attr(&packet, &as4_opt()).unwrap_err();
+    let notification = NotificationPacket::try_from(err).unwrap();
+    assert_eq!(
+        notification.sub_code,
+        u8::from(UpdateError::MalformedAspath)
+    );
+    assert_eq!(notification.data, packet);
+
+    // Parse failure follows the message type.
+    let err = BgpParseError::NomError("test error".to_string());
+    let notification = NotificationPacket::try_from(err.message_error(BgpType::Update)).unwrap();
+    assert_eq!(notification.code, NotifyCode::UpdateMsgError);
+    assert_eq!(
+        notification.sub_code,
+        u8::from(UpdateError::MalformedAttributeList)
+    );
+    let err = BgpParseError::NomError("test error".to_string());
+    let notification = NotificationPacket::try_from(err.message_error(BgpType::Open)).unwrap();
+    assert_eq!(notification.code, NotifyCode::OpenMsgError);
+    assert_eq!(notification.sub_code, 0);
+
+    // Incomplete data is not converted into NOTIFICATION.
+    let err = BgpParseError::IncompleteData { needed: 1 };
+    assert!(NotificationPacket::try_from(err).is_err());
+}
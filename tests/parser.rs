@@ -1,9 +1,12 @@
 use bgp_packet::*;
 use hex_literal::hex;
 
+mod common;
+use common::as4_opt;
+
 fn test1(buf: &[u8]) {
     // Parse with AS4 = truue.
-    let packet = BgpPacket::parse_packet(buf, true, None);
+    let packet = BgpPacket::parse_packet(buf, &as4_opt());
     assert!(packet.is_ok());
 
     let (_, packet) = packet.unwrap();
@@ -18,7 +21,7 @@ fn test1(buf: &[u8]) {
 
 fn test2(buf: &[u8]) {
     // Parse with AS4 = truue.
-    let packet = BgpPacket::parse_packet(buf, true, None);
+    let packet = BgpPacket::parse_packet(buf, &as4_opt());
     assert!(packet.is_ok());
 
     let (_, packet) = packet.unwrap();
@@ -95,7 +98,7 @@ ff ff ff ff ff ff ff ff ff ff ff ff ff ff ff ff
 01 01 01 00 0c 20 01 01 01 01
 "
     );
-    let packet = BgpPacket::parse_packet(PACKET, true, None);
+    let packet = BgpPacket::parse_packet(PACKET, &as4_opt());
     assert!(packet.is_ok());
     let (_, packet) = packet.unwrap();
     if let BgpPacket::Update(update) = packet {
@@ -104,3 +107,142 @@ ff ff ff ff ff ff ff ff ff ff ff ff ff ff ff ff
         panic!("Mut be Update packet");
     }
 }
+
+#[test]
+pub fn parse_unknown_attribute() {
+    // ORIGIN and unknown optional transitive attribute type 99.
+    const PACKET: &[u8] = &hex!(
+        "
+ff ff ff ff ff ff ff ff ff ff ff ff ff ff ff ff
+00 20 02 00 00 00 09 40 01 01 00 c0 63 02 aa bb
+"
+    );
+    let (_, packet) = BgpPacket::parse_packet(PACKET, &as4_opt()).unwrap();
+    let BgpPacket::Update(update) = packet else {
+        panic!("Must be Update packet");
+    };
+    let attr = update.bgp_attr.as_ref().unwrap();
+    assert_eq!(attr.unknown.len(), 1);
+    assert_eq!(attr.unknown[0].type_code, 99);
+    assert_eq!(attr.unknown[0].data, vec![0xaa, 0xbb]);
+
+    // Re-emitted with Partial bit.
+    let buf: bytes::BytesMut = (*update).into();
+    assert_eq!(&buf[27..], &hex!("e0 63 02 aa bb"));
+
+    // Unrecognized well-known attribute.
+    const WELLKNOWN: &[u8] = &hex!(
+        "
+ff ff ff ff ff ff ff ff ff ff ff ff ff ff ff ff
+00 20 02 00 00 00 09 40 01 01 00 40 63 02 aa bb
+"
+    );
+    let (_, packet) = BgpPacket::parse_packet(WELLKNOWN, &as4_opt()).unwrap();
+    let BgpPacket::Update(update) = packet else {
+        panic!("Must be Update packet");
+    };
+    let Some(UpdateErrorAction::SessionReset(notification)) = &update.error_action else {
+        panic!("Must be session reset");
+    };
+    assert_eq!(notification.code, NotifyCode::UpdateMsgError);
+    assert_eq!(
+        notification.sub_code,
+        u8::from(UpdateError::UnrecognizedWellknownAttribute)
+    );
+    assert_eq!(notification.data, hex!("40 63 02 aa bb"));
+}
+
+// Parse emitted UPDATE message which must not have any error.
+fn parse_update(buf: &[u8], opt: Option<ParseOption>) -> UpdatePacket {
+    let (_, packet) = BgpPacket::parse_packet(buf, &opt).unwrap();
+    let BgpPacket::Update(parsed) = packet else {
+        panic!("Must be Update packet");
+    };
+    assert!(parsed.error_action.is_none());
+    *parsed
+}
+
+// IPv6 unicast UPDATE with `attr` and 2001:db8:1::/48.
+fn ipv6_update(mut attr: BgpAttr, link_local: Option<std::net::Ipv6Addr>) -> UpdatePacket {
+    attr.aspath = Some("1".parse().unwrap());
+    let mut update = UpdatePacket::new();
+    update.bgp_attr = Some(attr);
+    update.mp_update = Some(MpNlriReachAttr::Ipv6 {
+        snpa: 0,
+        nhop: "2001:db8::1".parse().unwrap(),
+        link_local,
+        updates: vec![Ipv6Nlri {
+            id: 0,
+            prefix: "2001:db8:1::/48".parse().unwrap(),
+        }],
+    });
+    update
+}
+
+#[test]
+pub fn as4_path_old_speaker() {
+    let mut attr = BgpAttr::new();
+    attr.aspath = Some("1 70000 2".parse().unwrap());
+    attr.aggregator = Some(Aggregator::new(70000, "10.0.0.1".parse().unwrap()));
+    attr.nexthop = Some(BgpNexthop::Ipv4("10.0.0.1".parse().unwrap()));
+    let mut update = UpdatePacket::new();
+    update.bgp_attr = Some(attr);
+    update.ipv4_update.push(Ipv4Nlri {
+        id: 0,
+        prefix: "10.0.0.0/8".parse().unwrap(),
+    });
+
+    // AS_TRANS in AS_PATH and AGGREGATOR for OLD BGP speaker.
+    let parsed = parse_update(&update.emit_packet(false), None);
+    let attr = parsed.bgp_attr.as_ref().unwrap();
+    assert_eq!(attr.aspath.as_ref().unwrap().to_string(), "1 1.4464 2");
+    assert_eq!(attr.aggregator.as_ref().unwrap().asn, 70000);
+
+    // NEW BGP speaker does not send AS4_PATH.
+    let buf = update.emit_packet(true);
+    assert!(!buf.windows(2).any(|w| w == [0xc0, 0x11]));
+}
+
+#[test]
+pub fn ipv6_link_local_nexthop() {
+    let update = ipv6_update(BgpAttr::new(), Some("fe80::1".parse().unwrap()));
+    let parsed = parse_update(&update.emit_packet(true), as4_opt());
+    let Some(BgpNexthop::Ipv6 { global, link_local }) = parsed.bgp_attr.unwrap().mp_nexthop else {
+        panic!("IPv6 nexthop expected");
+    };
+    assert_eq!(global, "2001:db8::1".parse::<std::net::Ipv6Addr>().unwrap());
+    assert_eq!(link_local, Some("fe80::1".parse().unwrap()));
+}
+
+#[test]
+pub fn ipv4_nexthop_with_mp_reach() {
+    // IPv4 NLRI with NEXT_HOP and IPv6 NLRI in MP_REACH_NLRI.
+    let mut attr = BgpAttr::new();
+    attr.nexthop = Some(BgpNexthop::Ipv4("10.0.0.1".parse().unwrap()));
+    let mut update = ipv6_update(attr, None);
+    update.ipv4_update.push(Ipv4Nlri {
+        id: 0,
+        prefix: "10.0.0.0/8".parse().unwrap(),
+    });
+
+    let parsed = parse_update(&update.emit_packet(true), as4_opt());
+    let attr = parsed.bgp_attr.as_ref().unwrap();
+    assert!(matches!(attr.nexthop, Some(BgpNexthop::Ipv4(_))));
+    assert!(matches!(attr.mp_nexthop, Some(BgpNexthop::Ipv6 { .. })));
+    assert_eq!(parsed.emit_packet(true), update.emit_packet(true));
+}
+
+#[test]
+pub fn ipv6_ext_community() {
+    let mut attr = BgpAttr::new();
+    attr.ecom_v6 = Some("rt [2001:db8::1]:10".parse().unwrap());
+    let update = ipv6_update(attr, None);
+
+    let buf = update.emit_packet(true);
+    assert!(buf.windows(3).any(|w| w == [0xc0, 0x19, 0x14]));
+    let attr = parse_update(&buf, as4_opt()).bgp_attr.unwrap();
+    assert!(attr.unknown.is_empty());
+    let ecom_v6 = attr.ecom_v6.unwrap();
+    assert_eq!(ecom_v6.to_string(), "rt [2001:db8::1]:10");
+    assert_eq!(ecom_v6.route_targets().len(), 1);
+}